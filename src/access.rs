@@ -0,0 +1,181 @@
+use crate::locale::Locale;
+use crate::{LogEntry, LogLevel};
+use chrono::DateTime;
+use once_cell::sync::Lazy;
+use prettytable::{Cell, Row, Table};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Common Log Format, suivi du référent et de l'agent en Combined Log Format
+static LINE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"^(\S+) \S+ (\S+) \[([^\]]+)\] "(\S+) (\S+)(?: [^"]*)?" (\d{3}) (\d+|-)(?: "([^"]*)" "([^"]*)")?"#,
+    )
+    .unwrap()
+});
+
+/// Niveau selon le statut: 5xx en ERROR, 4xx en WARNING, le reste en INFO
+fn level(status: u16) -> LogLevel {
+    match status {
+        500.. => LogLevel::Error,
+        400..=499 => LogLevel::Warning,
+        _ => LogLevel::Info,
+    }
+}
+
+/// Une ligne de journal d'accès Apache/Nginx; le message est `MÉTHODE
+/// chemin statut` et les autres éléments deviennent des champs (`client`,
+/// `user`, `method`, `path`, `status`, `bytes`, `referer`, `user_agent`).
+pub fn parse_line(line: &str) -> Option<LogEntry> {
+    let caps = LINE.captures(line)?;
+    let datetime = DateTime::parse_from_str(&caps[3], "%d/%b/%Y:%H:%M:%S %z")
+        .ok()?
+        .naive_utc();
+    let status: u16 = caps[6].parse().ok()?;
+    let mut fields = BTreeMap::new();
+    for (key, group) in [
+        ("client", 1),
+        ("user", 2),
+        ("method", 4),
+        ("path", 5),
+        ("status", 6),
+        ("bytes", 7),
+        ("referer", 8),
+        ("user_agent", 9),
+    ] {
+        if let Some(value) = caps.get(group).map(|v| v.as_str())
+            && value != "-"
+        {
+            fields.insert(key.to_string(), value.to_string());
+        }
+    }
+    Some(LogEntry {
+        timestamp: datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
+        datetime,
+        level: level(status),
+        message: format!("{} {} {status}", &caps[4], &caps[5]),
+        tags: Vec::new(),
+        fields,
+    })
+}
+
+/// Requêtes d'un chemin (sans la chaîne de requête) et réponses en erreur
+#[derive(Debug, Serialize)]
+pub struct PathStats {
+    pub path: String,
+    pub requests: usize,
+    pub client_errors: usize,
+    pub server_errors: usize,
+}
+
+/// Répartition des statuts HTTP et chemins les plus demandés
+#[derive(Debug, Serialize)]
+pub struct HttpStats {
+    pub by_status: BTreeMap<String, usize>,
+    pub top_paths: Vec<PathStats>,
+}
+
+pub fn http_stats(entries: &[LogEntry], top_n: usize) -> HttpStats {
+    let mut by_status: BTreeMap<String, usize> = BTreeMap::new();
+    let mut paths: HashMap<&str, PathStats> = HashMap::new();
+    for entry in entries {
+        let Some(status) = entry.fields.get("status") else {
+            continue;
+        };
+        *by_status.entry(status.clone()).or_insert(0) += 1;
+        if let Some(path) = entry.fields.get("path") {
+            let path = path.split('?').next().unwrap_or(path);
+            let stats = paths.entry(path).or_insert_with(|| PathStats {
+                path: path.to_string(),
+                requests: 0,
+                client_errors: 0,
+                server_errors: 0,
+            });
+            stats.requests += 1;
+            match status.as_bytes().first() {
+                Some(b'4') => stats.client_errors += 1,
+                Some(b'5') => stats.server_errors += 1,
+                _ => {}
+            }
+        }
+    }
+    let mut top_paths: Vec<PathStats> = paths.into_values().collect();
+    top_paths.sort_by(|a, b| {
+        b.requests
+            .cmp(&a.requests)
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    top_paths.truncate(top_n);
+    HttpStats {
+        by_status,
+        top_paths,
+    }
+}
+
+pub fn render_tables(stats: &HttpStats, locale: Locale) -> (Table, Table) {
+    let total: usize = stats.by_status.values().sum();
+    let mut status_table = Table::new();
+    status_table.add_row(Row::new(vec![
+        Cell::new("Status"),
+        Cell::new("Count"),
+        Cell::new("Percentage"),
+    ]));
+    for (status, count) in &stats.by_status {
+        status_table.add_row(Row::new(vec![
+            Cell::new(status),
+            Cell::new(&locale.int(count)),
+            Cell::new(&locale.pct(*count as f64 / total as f64 * 100.0, 1)),
+        ]));
+    }
+
+    let mut path_table = Table::new();
+    path_table.add_row(Row::new(vec![
+        Cell::new("Path"),
+        Cell::new("Requests"),
+        Cell::new("4xx"),
+        Cell::new("5xx"),
+    ]));
+    for p in &stats.top_paths {
+        path_table.add_row(Row::new(vec![
+            Cell::new(&p.path),
+            Cell::new(&locale.int(p.requests)),
+            Cell::new(&locale.int(p.client_errors)),
+            Cell::new(&locale.int(p.server_errors)),
+        ]));
+    }
+    (status_table, path_table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_combined_log_format_and_counts_statuses() {
+        let entries: Vec<LogEntry> = [
+            r#"203.0.113.7 - alice [15/Jan/2024:10:30:45 +0100] "GET /api/orders?page=2 HTTP/1.1" 200 512 "https://shop.example/" "Mozilla/5.0""#,
+            r#"203.0.113.8 - - [15/Jan/2024:10:30:46 +0100] "POST /api/orders HTTP/1.1" 502 - "-" "curl/8.0""#,
+            r#"203.0.113.9 - - [15/Jan/2024:10:30:47 +0100] "GET /login HTTP/1.0" 404 128"#,
+        ]
+        .iter()
+        .map(|l| parse_line(l).unwrap())
+        .collect();
+
+        assert_eq!(entries[0].timestamp, "2024-01-15 09:30:45");
+        assert_eq!(entries[0].message, "GET /api/orders?page=2 200");
+        assert_eq!(entries[0].fields["user"], "alice");
+        assert_eq!(entries[0].fields["user_agent"], "Mozilla/5.0");
+        assert_eq!(entries[1].level, LogLevel::Error);
+        assert!(!entries[1].fields.contains_key("bytes"));
+        assert_eq!(entries[2].level, LogLevel::Warning);
+
+        let stats = http_stats(&entries, 5);
+        assert_eq!(stats.by_status["502"], 1);
+        assert_eq!(stats.top_paths[0].path, "/api/orders");
+        assert_eq!(stats.top_paths[0].requests, 2);
+        assert_eq!(stats.top_paths[0].server_errors, 1);
+
+        assert!(parse_line("2024-01-15 10:30:45 [ERROR] text").is_none());
+    }
+}
@@ -0,0 +1,215 @@
+use crate::{LogEntry, LogLevel, noise, parse_period};
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Budget d'erreurs toléré, en nombre par période ou en taux
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorBudget {
+    /// `N/PERIOD`: au plus N erreurs par période, à compter de la première entrée
+    Count { max: usize, period: Duration },
+    /// `PCT%`: au plus PCT % des entrées en erreur
+    Rate { pct: f64 },
+}
+
+impl std::fmt::Display for ErrorBudget {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ErrorBudget::Rate { pct } => write!(f, "{pct}%"),
+            ErrorBudget::Count { max, period } => {
+                let secs = period.as_secs();
+                match [(86400, "d"), (3600, "h"), (60, "min")]
+                    .into_iter()
+                    .find(|(unit, _)| secs % unit == 0)
+                {
+                    Some((unit, name)) if secs == unit => write!(f, "{max}/{name}"),
+                    Some((unit, name)) => write!(f, "{max}/{}{name}", secs / unit),
+                    None => write!(f, "{max}/{secs}s"),
+                }
+            }
+        }
+    }
+}
+
+/// `500/30d`, `50/j` ou `0.5%`
+pub fn parse_error_budget(input: &str) -> Result<ErrorBudget, String> {
+    let invalid = || format!("Budget invalide: {input} (ex: 500/30d ou 0.5%)");
+    if let Some(pct) = input.trim().strip_suffix('%') {
+        let pct: f64 = pct.trim().parse().map_err(|_| invalid())?;
+        if !(pct > 0.0 && pct <= 100.0) {
+            return Err(invalid());
+        }
+        return Ok(ErrorBudget::Rate { pct });
+    }
+    let (max, period) = input.split_once('/').ok_or_else(invalid)?;
+    let max = max
+        .trim()
+        .parse()
+        .ok()
+        .filter(|n| *n > 0)
+        .ok_or_else(invalid)?;
+    Ok(ErrorBudget::Count {
+        max,
+        period: parse_period(period)?,
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub struct BudgetContributor {
+    pub template: String,
+    pub count: usize,
+    /// Part du budget consommée par ce gabarit
+    pub budget_pct: f64,
+}
+
+/// Consommation du budget sur la fenêtre analysée
+#[derive(Debug, Serialize)]
+pub struct BudgetReport {
+    pub budget: String,
+    /// Erreurs tolérées: sur la période (`N/PERIOD`) ou pour le volume analysé (`PCT%`)
+    pub allowed: f64,
+    pub consumed: usize,
+    pub consumed_pct: f64,
+    /// Vitesse de consommation rapportée à celle qui épuiserait tout juste le budget
+    pub burn_rate: f64,
+    /// Date d'épuisement, atteinte ou projetée au rythme observé (budget `N/PERIOD`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exhausted_at: Option<String>,
+    pub projected: bool,
+    pub top_contributors: Vec<BudgetContributor>,
+}
+
+impl BudgetReport {
+    /// Plus d'erreurs que le budget n'en tolère sur la fenêtre analysée
+    pub fn exceeded(&self) -> bool {
+        self.consumed as f64 > self.allowed
+    }
+}
+
+pub fn consumption(
+    entries: &[LogEntry],
+    budget: ErrorBudget,
+    top_n: usize,
+) -> Option<BudgetReport> {
+    let first = entries.iter().map(|e| e.datetime).min()?;
+    let last = entries.iter().map(|e| e.datetime).max()?;
+    let mut errors: Vec<&LogEntry> = entries
+        .iter()
+        .filter(|e| e.level == LogLevel::Error)
+        .collect();
+    errors.sort_by_key(|e| e.datetime);
+    let consumed = errors.len();
+
+    let (allowed, burn_rate, exhausted_at, projected) = match budget {
+        ErrorBudget::Rate { pct } => {
+            let allowed = entries.len() as f64 * pct / 100.0;
+            (allowed, consumed as f64 / allowed, None, false)
+        }
+        ErrorBudget::Count { max, period } => {
+            // Au moins une seconde, pour qu'un lot d'un seul instant reste projetable
+            let span = (last - first).num_seconds().max(1) as f64;
+            let burn_rate = (consumed as f64 / span) / (max as f64 / period.as_secs_f64());
+            let exhausted: Option<NaiveDateTime> = match errors.get(max - 1) {
+                Some(e) => Some(e.datetime),
+                None if consumed > 0 => {
+                    let secs = max as f64 / consumed as f64 * span;
+                    Some(first + chrono::Duration::seconds(secs as i64))
+                }
+                None => None,
+            };
+            let projected = consumed < max;
+            (max as f64, burn_rate, exhausted, projected)
+        }
+    };
+
+    let mut by_template: HashMap<String, usize> = HashMap::new();
+    for entry in &errors {
+        *by_template
+            .entry(noise::normalize(&entry.message))
+            .or_insert(0) += 1;
+    }
+    let mut top_contributors: Vec<BudgetContributor> = by_template
+        .into_iter()
+        .map(|(template, count)| BudgetContributor {
+            template,
+            count,
+            budget_pct: count as f64 / allowed * 100.0,
+        })
+        .collect();
+    top_contributors.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| a.template.cmp(&b.template))
+    });
+    top_contributors.truncate(top_n);
+
+    Some(BudgetReport {
+        budget: budget.to_string(),
+        allowed,
+        consumed,
+        consumed_pct: consumed as f64 / allowed * 100.0,
+        burn_rate,
+        exhausted_at: exhausted_at.map(|d| d.format("%Y-%m-%d %H:%M:%S").to_string()),
+        projected,
+        top_contributors,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_log_line;
+
+    fn entries(lines: &[&str]) -> Vec<LogEntry> {
+        lines.iter().map(|l| parse_log_line(l).unwrap()).collect()
+    }
+
+    #[test]
+    fn parses_count_and_rate_budgets() {
+        assert_eq!(
+            parse_error_budget("500/30d"),
+            Ok(ErrorBudget::Count {
+                max: 500,
+                period: Duration::from_secs(30 * 86400)
+            })
+        );
+        assert_eq!(
+            parse_error_budget("0.5%"),
+            Ok(ErrorBudget::Rate { pct: 0.5 })
+        );
+        assert!(parse_error_budget("0/d").is_err());
+        assert!(parse_error_budget("150%").is_err());
+    }
+
+    #[test]
+    fn projects_exhaustion_and_ranks_contributors() {
+        let logs = entries(&[
+            "2024-01-15 10:00:00 [ERROR] timeout id=1",
+            "2024-01-15 11:00:00 [INFO] ok",
+            "2024-01-15 12:00:00 [ERROR] timeout id=2",
+            "2024-01-15 14:00:00 [ERROR] disk full",
+        ]);
+        let report = consumption(&logs, parse_error_budget("12/d").unwrap(), 5).unwrap();
+        assert_eq!(report.budget, "12/d");
+        assert_eq!(report.consumed, 3);
+        assert_eq!(report.consumed_pct, 25.0);
+        // 3 erreurs en 4 h, contre 12 par 24 h tolérées
+        assert!((report.burn_rate - 1.5).abs() < 1e-9);
+        assert_eq!(report.exhausted_at.as_deref(), Some("2024-01-16 02:00:00"));
+        assert!(report.projected);
+        assert!(!report.exceeded());
+        assert_eq!(report.top_contributors[0].template, "timeout id=<num>");
+        assert_eq!(report.top_contributors[0].budget_pct, 2.0 / 12.0 * 100.0);
+
+        let report = consumption(&logs, parse_error_budget("2/d").unwrap(), 5).unwrap();
+        assert_eq!(report.exhausted_at.as_deref(), Some("2024-01-15 12:00:00"));
+        assert!(!report.projected);
+        assert!(report.exceeded());
+
+        let report = consumption(&logs, parse_error_budget("50%").unwrap(), 5).unwrap();
+        assert_eq!(report.allowed, 2.0);
+        assert_eq!(report.consumed_pct, 150.0);
+        assert!(report.exhausted_at.is_none());
+    }
+}
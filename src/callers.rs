@@ -0,0 +1,115 @@
+use crate::LogEntry;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Jeton `fichier.ext:ligne` laissé par l'appelant (`src/db.rs:123`,
+/// `app/models.py:45`), éventuellement suivi d'une colonne
+static CALLER: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?:^|[\s(\[=])([\w./\\-]+\.(?:rs|py|go|java|kt|scala|js|mjs|ts|rb|php|cs|c|cc|cpp|h|hpp|ex|exs|swift):\d+)(?::\d+)?\b",
+    )
+    .unwrap()
+});
+
+/// Champs qui désignent l'appelant dans les journaux structurés (zap, logrus,
+/// structlog…)
+const CALLER_FIELDS: [&str; 3] = ["caller", "source", "location"];
+
+/// Site d'appel d'une entrée: champ `caller` (ou équivalent) s'il a la forme
+/// `fichier:ligne`, sinon premier jeton de ce genre dans le message
+pub fn call_site(entry: &LogEntry) -> Option<String> {
+    CALLER_FIELDS
+        .iter()
+        .filter_map(|key| entry.fields.get(*key))
+        .find_map(|value| CALLER.captures(value))
+        .or_else(|| CALLER.captures(&entry.message))
+        .map(|caps| caps[1].replace('\\', "/"))
+}
+
+/// Volume émis par une instruction de log
+#[derive(Debug, Serialize)]
+pub struct CallSite {
+    pub site: String,
+    pub count: usize,
+    pub bytes: u64,
+    pub errors: usize,
+    /// Part des entrées de la fenêtre (en %)
+    pub share_pct: f64,
+}
+
+/// Sites d'appel les plus bavards, par nombre d'entrées puis par octets
+pub fn noisiest(entries: &[LogEntry], top_n: usize) -> Vec<CallSite> {
+    let mut sites: HashMap<String, CallSite> = HashMap::new();
+    for entry in entries {
+        let Some(site) = call_site(entry) else {
+            continue;
+        };
+        let stats = sites.entry(site.clone()).or_insert_with(|| CallSite {
+            site,
+            count: 0,
+            bytes: 0,
+            errors: 0,
+            share_pct: 0.0,
+        });
+        stats.count += 1;
+        stats.bytes += entry.line_bytes();
+        if entry.level == crate::LogLevel::Error {
+            stats.errors += 1;
+        }
+    }
+    let mut ranked: Vec<CallSite> = sites.into_values().collect();
+    for site in &mut ranked {
+        site.share_pct = site.count as f64 / entries.len() as f64 * 100.0;
+    }
+    ranked.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| b.bytes.cmp(&a.bytes))
+            .then_with(|| a.site.cmp(&b.site))
+    });
+    ranked.truncate(top_n);
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_log_line;
+
+    #[test]
+    fn ranks_call_sites_from_messages_and_fields() {
+        let mut entries: Vec<LogEntry> = [
+            "2024-01-15 10:00:00 [ERROR] src/db.rs:123 connection reset",
+            "2024-01-15 10:00:01 [ERROR] src/db.rs:123 connection reset",
+            "2024-01-15 10:00:02 [INFO] (app/models.py:45) saved order 42",
+            "2024-01-15 10:00:03 [INFO] listening on 0.0.0.0:8080",
+            "2024-01-15 10:00:03 [WARNING] caller=app/models.py:45 slow query",
+            "2024-01-15 10:00:04 [DEBUG] reading config.yaml:12",
+        ]
+        .iter()
+        .map(|l| parse_log_line(l).unwrap())
+        .collect();
+        entries[5].fields.insert(
+            "caller".to_string(),
+            "internal/api/handler.go:88".to_string(),
+        );
+
+        assert_eq!(call_site(&entries[3]), None);
+        assert_eq!(call_site(&entries[4]).as_deref(), Some("app/models.py:45"));
+        let sites = noisiest(&entries, 5);
+        let names: Vec<_> = sites.iter().map(|s| s.site.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "app/models.py:45",
+                "src/db.rs:123",
+                "internal/api/handler.go:88"
+            ]
+        );
+        assert_eq!(sites[0].count, 2);
+        assert_eq!(sites[1].errors, 2);
+        assert!((sites[1].share_pct - 33.3).abs() < 0.1);
+    }
+}
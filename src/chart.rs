@@ -0,0 +1,211 @@
+use crate::markers::Marker;
+use crate::{LogEntry, LogLevel, truncate_to_hour};
+use chrono::{Duration, NaiveDateTime};
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const SIZE: (u32, u32) = (1000, 700);
+
+/// Niveaux empilés du bas vers le haut, avec leur couleur
+pub const STACK: [(LogLevel, RGBColor); 4] = [
+    (LogLevel::Debug, RGBColor(150, 150, 150)),
+    (LogLevel::Info, RGBColor(60, 160, 90)),
+    (LogLevel::Warning, RGBColor(230, 170, 30)),
+    (LogLevel::Error, RGBColor(210, 40, 40)),
+];
+
+const MARKER_COLOR: RGBColor = RGBColor(60, 90, 200);
+
+/// Comptes horaires chronologiques par niveau, heures vides comprises
+pub struct HourlySeries {
+    pub start: NaiveDateTime,
+    /// Une case par niveau de `STACK`
+    pub counts: Vec<[usize; 4]>,
+}
+
+pub fn hourly_series(entries: &[LogEntry]) -> Option<HourlySeries> {
+    let mut buckets: BTreeMap<NaiveDateTime, [usize; 4]> = BTreeMap::new();
+    for entry in entries {
+        let slot = STACK.iter().position(|(l, _)| *l == entry.level)?;
+        buckets.entry(truncate_to_hour(entry.datetime)).or_default()[slot] += 1;
+    }
+    let start = *buckets.keys().next()?;
+    let end = *buckets.keys().next_back()?;
+    let counts = (0..=(end - start).num_hours())
+        .map(|h| {
+            buckets
+                .get(&(start + Duration::hours(h)))
+                .copied()
+                .unwrap_or_default()
+        })
+        .collect();
+    Some(HourlySeries { start, counts })
+}
+
+fn draw<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    series: &HourlySeries,
+    markers: &[Marker],
+) -> Result<(), String>
+where
+    DB::ErrorType: 'static,
+{
+    let err = |e: DrawingAreaErrorKind<DB::ErrorType>| e.to_string();
+    root.fill(&WHITE).map_err(err)?;
+    let (top, bottom) = root.split_vertically(SIZE.1 / 2);
+
+    let hours = series.counts.len();
+    let label = |x: &usize| {
+        (series.start + Duration::hours(*x as i64))
+            .format("%m-%d %H:00")
+            .to_string()
+    };
+
+    let errors: Vec<usize> = series.counts.iter().map(|c| c[3]).collect();
+    let max_errors = errors.iter().copied().max().unwrap_or(0).max(1);
+    let mut chart = ChartBuilder::on(&top)
+        .caption("Errors by hour", ("sans-serif", 22))
+        .margin(10)
+        .margin_right(40)
+        .x_label_area_size(40)
+        .y_label_area_size(50)
+        .build_cartesian_2d(0..hours.max(2) - 1, 0..max_errors + max_errors / 10 + 1)
+        .map_err(err)?;
+    chart
+        .configure_mesh()
+        .x_label_formatter(&label)
+        .x_labels(8)
+        .draw()
+        .map_err(err)?;
+    chart
+        .draw_series(LineSeries::new(
+            errors.iter().enumerate().map(|(x, y)| (x, *y)),
+            STACK[3].1.stroke_width(2),
+        ))
+        .map_err(err)?;
+
+    // Marqueurs placés à l'heure la plus proche, hors de la série ignorés
+    let y_top = max_errors + max_errors / 10;
+    for marker in markers {
+        let offset = (marker.at - series.start).num_minutes() as f64 / 60.0;
+        if offset < 0.0 || offset.round() as usize >= hours {
+            continue;
+        }
+        let x = offset.round() as usize;
+        chart
+            .draw_series([PathElement::new(
+                vec![(x, 0), (x, y_top)],
+                MARKER_COLOR.stroke_width(1),
+            )])
+            .map_err(err)?;
+        chart
+            .draw_series([Text::new(
+                marker.label.clone(),
+                (x, y_top),
+                ("sans-serif", 13).into_font().color(&MARKER_COLOR),
+            )])
+            .map_err(err)?;
+    }
+
+    let totals: Vec<usize> = series.counts.iter().map(|c| c.iter().sum()).collect();
+    let max_total = totals.iter().copied().max().unwrap_or(0).max(1);
+    let mut chart = ChartBuilder::on(&bottom)
+        .caption("Entries by hour and level", ("sans-serif", 22))
+        .margin(10)
+        .margin_right(40)
+        .x_label_area_size(40)
+        .y_label_area_size(50)
+        .build_cartesian_2d(0..hours.max(2) - 1, 0..max_total + max_total / 10 + 1)
+        .map_err(err)?;
+    chart
+        .configure_mesh()
+        .x_label_formatter(&label)
+        .x_labels(8)
+        .draw()
+        .map_err(err)?;
+
+    // Chaque couche est dessinée avec la somme cumulée jusqu'à son niveau,
+    // de la plus haute à la plus basse pour que les suivantes la recouvrent
+    for slot in (0..STACK.len()).rev() {
+        let (level, color) = &STACK[slot];
+        chart
+            .draw_series(AreaSeries::new(
+                series
+                    .counts
+                    .iter()
+                    .enumerate()
+                    .map(|(x, c)| (x, c[..=slot].iter().sum::<usize>())),
+                0,
+                color.mix(0.8),
+            ))
+            .map_err(err)?
+            .label(level.as_str())
+            .legend(move |(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], color.filled()));
+    }
+    chart
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()
+        .map_err(err)?;
+
+    root.present().map_err(err)
+}
+
+/// Trace la série horaire des erreurs, annotée des marqueurs, et l'empilement
+/// des niveaux, en SVG ou en PNG selon l'extension de `path`.
+pub fn render_chart(path: &Path, entries: &[LogEntry], markers: &[Marker]) -> Result<(), String> {
+    let series = hourly_series(entries).ok_or("Aucune entrée à représenter")?;
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("svg") => draw(
+            SVGBackend::new(path, SIZE).into_drawing_area(),
+            &series,
+            markers,
+        ),
+        Some("png") => draw(
+            BitMapBackend::new(path, SIZE).into_drawing_area(),
+            &series,
+            markers,
+        ),
+        _ => Err(format!(
+            "Extension de graphique non supportée: {} (svg ou png)",
+            path.display()
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_log_line;
+
+    #[test]
+    fn renders_svg_with_stacked_levels() {
+        let entries: Vec<_> = [
+            "2024-01-15 10:00:00 [ERROR] a",
+            "2024-01-15 10:10:00 [INFO] b",
+            "2024-01-15 12:00:00 [WARNING] c",
+        ]
+        .iter()
+        .map(|l| parse_log_line(l).unwrap())
+        .collect();
+
+        let series = hourly_series(&entries).unwrap();
+        assert_eq!(series.counts.len(), 3);
+        assert_eq!(series.counts[0], [0, 1, 0, 1]);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("chart.svg");
+        let markers = [Marker {
+            at: entries[1].datetime,
+            label: "deploy v2".to_string(),
+        }];
+        render_chart(&path, &entries, &markers).unwrap();
+        let svg = std::fs::read_to_string(&path).unwrap();
+        assert!(svg.contains("<svg"));
+        assert!(svg.contains("deploy v2"));
+        assert!(render_chart(&dir.path().join("chart.gif"), &entries, &[]).is_err());
+    }
+}
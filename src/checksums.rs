@@ -0,0 +1,156 @@
+use crate::platform;
+use sha2::{Digest, Sha256};
+use std::cell::RefCell;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+use std::rc::Rc;
+
+/// Manifeste écrit à côté des sorties, au format de `sha256sum -c`
+pub const MANIFEST: &str = "SHA256SUMS";
+
+/// Octets bruts lus sur une entrée (compressés le cas échéant) et leur SHA-256
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadDigest {
+    pub bytes: u64,
+    pub sha256: String,
+}
+
+impl ReadDigest {
+    pub fn of(raw: &[u8]) -> Self {
+        ReadDigest {
+            bytes: raw.len() as u64,
+            sha256: crate::verify::hex(&Sha256::digest(raw)),
+        }
+    }
+}
+
+/// Empreinte calculée au fil de la lecture, sans relire la source ensuite
+/// (ce qui serait impossible pour l'entrée standard ou une URL)
+#[derive(Clone)]
+pub struct Digester(Rc<RefCell<(Sha256, u64)>>);
+
+impl Default for Digester {
+    fn default() -> Self {
+        Digester(Rc::new(RefCell::new((Sha256::new(), 0))))
+    }
+}
+
+struct Digesting<R> {
+    inner: R,
+    digester: Digester,
+}
+
+impl<R: Read> Read for Digesting<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        let mut state = self.digester.0.borrow_mut();
+        state.0.update(&buf[..n]);
+        state.1 += n as u64;
+        Ok(n)
+    }
+}
+
+impl Digester {
+    /// Lecture qui ajoute à l'empreinte chaque octet lu
+    pub fn wrap<'a>(&self, inner: impl Read + 'a) -> Box<dyn Read + 'a> {
+        Box::new(Digesting {
+            inner,
+            digester: self.clone(),
+        })
+    }
+
+    /// Empreinte des octets lus jusqu'ici
+    pub fn digest(&self) -> ReadDigest {
+        let (hasher, bytes) = &*self.0.borrow();
+        ReadDigest {
+            bytes: *bytes,
+            sha256: crate::verify::hex(&hasher.clone().finalize()),
+        }
+    }
+}
+
+/// SHA-256 d'un fichier tel qu'il est sur disque (compressé le cas échéant)
+pub fn sha256_file(path: &Path) -> io::Result<String> {
+    let mut file = platform::open_shared(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(crate::verify::hex(&hasher.finalize()))
+}
+
+/// Ajoute ou met à jour l'empreinte de chaque fichier écrit dans le
+/// `SHA256SUMS` de son répertoire; les lignes des autres fichiers sont
+/// conservées, si bien que les exports successifs d'un même répertoire
+/// partagent un manifeste. Renvoie les manifestes écrits.
+pub fn record(written: &[&Path]) -> io::Result<Vec<std::path::PathBuf>> {
+    let mut manifests = Vec::new();
+    for path in written {
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let name = path
+            .file_name()
+            .ok_or_else(|| io::Error::other(format!("{} n'est pas un fichier", path.display())))?
+            .to_string_lossy();
+        let manifest = dir.join(MANIFEST);
+        let existing = match fs::read_to_string(&manifest) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err),
+        };
+        let mut lines: Vec<String> = existing
+            .lines()
+            .filter(|line| line.split_once("  ").is_none_or(|(_, file)| file != name))
+            .map(str::to_string)
+            .collect();
+        lines.push(format!("{}  {name}", sha256_file(path)?));
+        fs::write(&manifest, lines.join("\n") + "\n")?;
+        if !manifests.contains(&manifest) {
+            manifests.push(manifest);
+        }
+    }
+    Ok(manifests)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_and_updates_the_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let report = dir.path().join("report.json");
+        let chart = dir.path().join("chart.svg");
+        fs::write(&report, "abc").unwrap();
+        fs::write(&chart, "").unwrap();
+
+        let manifests = record(&[&report, &chart]).unwrap();
+        assert_eq!(manifests, [dir.path().join(MANIFEST)]);
+        fs::write(&report, "abcd").unwrap();
+        record(&[&report]).unwrap();
+
+        let text = fs::read_to_string(&manifests[0]).unwrap();
+        assert_eq!(
+            text,
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855  chart.svg\n\
+             88d4266fd4e6338d13b845fcf289579d209c897823b9217da3e161936f031589  report.json\n"
+        );
+
+        let digester = Digester::default();
+        let mut read = Vec::new();
+        digester
+            .wrap(fs::File::open(&report).unwrap())
+            .read_to_end(&mut read)
+            .unwrap();
+        assert_eq!(digester.digest(), ReadDigest::of(&read));
+        assert_eq!(digester.digest().sha256, sha256_file(&report).unwrap());
+    }
+}
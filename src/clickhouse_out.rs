@@ -0,0 +1,145 @@
+use crate::LogEntry;
+use crate::forward::Forwarder;
+use serde_json::json;
+use std::io;
+
+/// Nombre d'entrées accumulées avant un INSERT groupé
+const BATCH: usize = 1000;
+const DEFAULT_TABLE: &str = "loglyzer_logs";
+
+/// Insère les entrées dans une table ClickHouse via l'interface HTTP, par
+/// lots `INSERT ... FORMAT JSONEachRow`; la table est créée si besoin.
+pub struct ClickHouseForwarder {
+    endpoint: String,
+    table: String,
+    pending: String,
+    pending_count: usize,
+}
+
+/// `clickhouse://[user:pass@]hôte:8123[/base.table]` (HTTP) ou
+/// `clickhouse+https://...` en (point d'entrée HTTP, table)
+fn parse_dsn(dsn: &str) -> Option<(String, String)> {
+    let (scheme, rest) = dsn.split_once("://")?;
+    let scheme = match scheme {
+        "clickhouse" => "http",
+        "clickhouse+https" => "https",
+        _ => return None,
+    };
+    let (host, table) = match rest.split_once('/') {
+        Some((host, table)) if !table.is_empty() => (host, table),
+        Some((host, _)) => (host, DEFAULT_TABLE),
+        None => (rest, DEFAULT_TABLE),
+    };
+    let valid_identifier =
+        |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    let valid_table = match table.split_once('.') {
+        Some((db, name)) => valid_identifier(db) && valid_identifier(name),
+        None => valid_identifier(table),
+    };
+    (!host.is_empty() && valid_table).then(|| (format!("{scheme}://{host}/"), table.to_string()))
+}
+
+fn create_table(table: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {table} (\
+         timestamp DateTime, \
+         level LowCardinality(String), \
+         message String, \
+         tags Array(String), \
+         fields Map(String, String)\
+         ) ENGINE = MergeTree ORDER BY (level, timestamp)"
+    )
+}
+
+fn row(entry: &LogEntry) -> String {
+    json!({
+        "timestamp": entry.datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
+        "level": entry.level.as_str(),
+        "message": entry.message,
+        "tags": entry.tags,
+        "fields": entry.fields,
+    })
+    .to_string()
+}
+
+impl ClickHouseForwarder {
+    pub fn connect(dsn: &str) -> io::Result<Self> {
+        let (endpoint, table) = parse_dsn(dsn).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("DSN ClickHouse invalide: {dsn} (clickhouse://hôte:8123/base.table)"),
+            )
+        })?;
+        let forwarder = ClickHouseForwarder {
+            endpoint,
+            table,
+            pending: String::new(),
+            pending_count: 0,
+        };
+        forwarder.query(&create_table(&forwarder.table))?;
+        Ok(forwarder)
+    }
+
+    /// La requête (et ses données éventuelles) voyage dans le corps du POST.
+    fn query(&self, body: &str) -> io::Result<()> {
+        ureq::post(&self.endpoint)
+            .send(body)
+            .map(|_| ())
+            .map_err(io::Error::other)
+    }
+}
+
+impl Forwarder for ClickHouseForwarder {
+    fn send(&mut self, entry: &LogEntry) -> io::Result<()> {
+        if self.pending.is_empty() {
+            self.pending = format!("INSERT INTO {} FORMAT JSONEachRow\n", self.table);
+        }
+        self.pending.push_str(&row(entry));
+        self.pending.push('\n');
+        self.pending_count += 1;
+        if self.pending_count >= BATCH {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.pending_count == 0 {
+            return Ok(());
+        }
+        self.query(&self.pending)?;
+        self.pending.clear();
+        self.pending_count = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_log_line;
+
+    #[test]
+    fn parses_dsn_and_builds_rows() {
+        assert_eq!(
+            parse_dsn("clickhouse+https://ingest:pw@ch:8443/logs.app"),
+            Some((
+                "https://ingest:pw@ch:8443/".to_string(),
+                "logs.app".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_dsn("clickhouse://ch:8123"),
+            Some(("http://ch:8123/".to_string(), "loglyzer_logs".to_string()))
+        );
+        assert_eq!(parse_dsn("clickhouse://ch:8123/logs; DROP TABLE x"), None);
+        assert_eq!(parse_dsn("kafka://ch:8123/logs"), None);
+
+        let mut entry = parse_log_line("2024-01-15 10:30:45 [ERROR] disk full host=db1").unwrap();
+        entry.fields = crate::fields::extract(&entry.message);
+        assert_eq!(
+            row(&entry),
+            r#"{"timestamp":"2024-01-15 10:30:45","level":"ERROR","message":"disk full host=db1","tags":[],"fields":{"host":"db1"}}"#
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use std::io::{self, BufRead, Read};
+
+/// Compression d'un fichier de log, reconnue à sa signature plutôt qu'à
+/// son extension (`app.log.1.gz`, `app.log.zst`, `app.log.bz2`, `app.log.xz`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Codec {
+    Gzip,
+    Zstd,
+    Bzip2,
+    Xz,
+}
+
+impl Codec {
+    const MAGIC: [(Codec, &'static [u8]); 4] = [
+        (Codec::Gzip, &[0x1f, 0x8b]),
+        (Codec::Zstd, &[0x28, 0xb5, 0x2f, 0xfd]),
+        (Codec::Bzip2, b"BZh"),
+        (Codec::Xz, &[0xfd, b'7', b'z', b'X', b'Z', 0x00]),
+    ];
+
+    /// Compression signalée par les premiers octets du fichier
+    pub fn detect(head: &[u8]) -> Option<Codec> {
+        Self::MAGIC
+            .iter()
+            .find(|(_, magic)| head.starts_with(magic))
+            .map(|(codec, _)| *codec)
+    }
+
+    /// Décompression au fil de la lecture; les flux concaténés (rotations
+    /// successives réunies par `cat`) sont lus jusqu'au dernier. Sans la
+    /// fonctionnalité cargo correspondante, l'erreur indique comment l'activer.
+    pub fn decoder<'a>(self, reader: impl BufRead + 'a) -> io::Result<Box<dyn Read + 'a>> {
+        match self {
+            Codec::Gzip => Ok(Box::new(flate2::bufread::MultiGzDecoder::new(reader))),
+            #[cfg(feature = "zstd")]
+            Codec::Zstd => Ok(Box::new(zstd::stream::read::Decoder::with_buffer(reader)?)),
+            #[cfg(feature = "bzip2")]
+            Codec::Bzip2 => Ok(Box::new(bzip2::bufread::MultiBzDecoder::new(reader))),
+            #[cfg(feature = "xz")]
+            Codec::Xz => Ok(Box::new(liblzma::bufread::XzDecoder::new_multi_decoder(
+                reader,
+            ))),
+            #[allow(unreachable_patterns)]
+            codec => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!(
+                    "fichier compressé en {0}, lecture non compilée (cargo build --features {0})",
+                    codec.name()
+                ),
+            )),
+        }
+    }
+
+    /// Nom de la compression, qui est aussi celui de sa fonctionnalité cargo
+    fn name(self) -> &'static str {
+        match self {
+            Codec::Gzip => "gzip",
+            Codec::Zstd => "zstd",
+            Codec::Bzip2 => "bzip2",
+            Codec::Xz => "xz",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const LOG: &[u8] = b"2024-01-15 10:30:45 [ERROR] API timeout\n";
+
+    fn decode(packed: &[u8]) -> io::Result<Vec<u8>> {
+        let mut text = Vec::new();
+        Codec::detect(packed)
+            .expect("signature reconnue")
+            .decoder(packed)?
+            .read_to_end(&mut text)?;
+        Ok(text)
+    }
+
+    #[test]
+    fn detects_and_decodes_compressed_logs() {
+        assert_eq!(Codec::detect(LOG), None);
+        assert_eq!(Codec::detect(b"BZh91AY&SY"), Some(Codec::Bzip2));
+
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gz.write_all(LOG).unwrap();
+        let mut packed = gz.finish().unwrap();
+        packed.extend_from_within(..);
+        assert_eq!(decode(&packed).unwrap(), [LOG, LOG].concat());
+
+        #[cfg(feature = "zstd")]
+        assert_eq!(decode(&zstd::encode_all(LOG, 0).unwrap()).unwrap(), LOG);
+        #[cfg(not(feature = "zstd"))]
+        {
+            let err = decode(&[0x28, 0xb5, 0x2f, 0xfd, 0]).unwrap_err();
+            assert!(err.to_string().contains("--features zstd"));
+        }
+        #[cfg(feature = "bzip2")]
+        {
+            let mut bz = bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::default());
+            bz.write_all(LOG).unwrap();
+            assert_eq!(decode(&bz.finish().unwrap()).unwrap(), LOG);
+        }
+        #[cfg(feature = "xz")]
+        {
+            let mut xz = liblzma::write::XzEncoder::new(Vec::new(), 6);
+            xz.write_all(LOG).unwrap();
+            assert_eq!(decode(&xz.finish().unwrap()).unwrap(), LOG);
+        }
+    }
+}
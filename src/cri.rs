@@ -0,0 +1,149 @@
+use crate::LogEntry;
+use std::io::{self, BufRead, Read};
+
+/// Ligne du format CRI: `horodatage flux drapeau message`, où le drapeau vaut
+/// `F` pour une ligne complète et `P` pour un morceau d'une ligne plus longue
+/// que le tampon du moteur
+#[derive(Debug, PartialEq)]
+struct CriLine<'a> {
+    time: &'a str,
+    stream: &'a str,
+    partial: bool,
+    message: &'a str,
+}
+
+fn split(line: &str) -> Option<CriLine<'_>> {
+    let mut parts = line.splitn(4, ' ');
+    let time = parts.next()?;
+    let stream = parts.next().filter(|s| matches!(*s, "stdout" | "stderr"))?;
+    let partial = match parts.next()? {
+        "P" => true,
+        "F" => false,
+        _ => return None,
+    };
+    Some(CriLine {
+        time,
+        stream,
+        partial,
+        message: parts.next().unwrap_or_default(),
+    })
+}
+
+/// Une ligne CRI, lue comme une sortie de conteneur Docker: le message est
+/// analysé s'il a la forme d'une ligne texte ou JSON, sinon le niveau vient de
+/// ses premiers mots ou du flux. Un morceau `P` isolé (en suivi continu) est
+/// pris tel quel.
+pub fn parse_line(line: &str) -> Option<LogEntry> {
+    let cri = split(line)?;
+    crate::docker::parse_output(cri.message, cri.time, cri.stream)
+}
+
+/// Lignes CRI dont les morceaux `P` sont recollés, flux par flux, à la ligne
+/// `F` qui les termine; l'horodatage retenu est celui du premier morceau. Les
+/// lignes qui n'ont pas la forme CRI passent telles quelles.
+pub struct Reassemble<R> {
+    inner: R,
+    /// Début d'horodatage et morceaux en attente, pour stdout et stderr
+    pending: [Option<(String, String)>; 2],
+    line: Vec<u8>,
+    out: Vec<u8>,
+    pos: usize,
+    done: bool,
+}
+
+impl<R: BufRead> Reassemble<R> {
+    pub fn new(inner: R) -> Self {
+        Reassemble {
+            inner,
+            pending: Default::default(),
+            line: Vec::new(),
+            out: Vec::new(),
+            pos: 0,
+            done: false,
+        }
+    }
+
+    fn emit(&mut self, time: &str, stream: &str, message: &str) {
+        self.out
+            .extend_from_slice(format!("{time} {stream} F {message}\n").as_bytes());
+    }
+
+    fn fill(&mut self) -> io::Result<()> {
+        self.line.clear();
+        if self.inner.read_until(b'\n', &mut self.line)? == 0 {
+            self.done = true;
+            for (slot, stream) in ["stdout", "stderr"].iter().enumerate() {
+                if let Some((time, message)) = self.pending[slot].take() {
+                    self.emit(&time, stream, &message);
+                }
+            }
+            return Ok(());
+        }
+        let text = String::from_utf8_lossy(&self.line).into_owned();
+        let trimmed = text.trim_end_matches(['\n', '\r']);
+        let Some(cri) = split(trimmed) else {
+            self.out.extend_from_slice(&self.line);
+            return Ok(());
+        };
+        let slot = usize::from(cri.stream == "stderr");
+        match (self.pending[slot].as_mut(), cri.partial) {
+            (Some((_, message)), true) => message.push_str(cri.message),
+            (None, true) => {
+                self.pending[slot] = Some((cri.time.to_string(), cri.message.to_string()));
+            }
+            (Some(_), false) => {
+                let (time, mut message) = self.pending[slot].take().unwrap_or_default();
+                message.push_str(cri.message);
+                self.emit(&time, cri.stream, &message);
+            }
+            (None, false) => self.out.extend_from_slice(&self.line),
+        }
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Read for Reassemble<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.out.len() && !self.done {
+            self.out.clear();
+            self.pos = 0;
+            self.fill()?;
+        }
+        let n = buf.len().min(self.out.len() - self.pos);
+        buf[..n].copy_from_slice(&self.out[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LogLevel;
+
+    #[test]
+    fn reassembles_partial_lines_per_stream() {
+        let input = "2024-01-15T10:30:45.123456789Z stdout P {\"ts\":\"2024-01-15T10:30:45Z\",\n\
+                     2024-01-15T10:30:45.2Z stderr F panic: nil map\n\
+                     2024-01-15T10:30:45.3Z stdout P \"level\":\"warn\",\n\
+                     2024-01-15T10:30:45.4Z stdout F \"msg\":\"slow query\"}\n\
+                     2024-01-15T10:30:46Z stdout P tail without end";
+        let mut text = String::new();
+        Reassemble::new(input.as_bytes())
+            .read_to_string(&mut text)
+            .unwrap();
+        let entries: Vec<_> = text.lines().map(|l| parse_line(l).unwrap()).collect();
+        assert_eq!(entries.len(), 3);
+
+        assert_eq!(entries[0].message, "panic: nil map");
+        assert_eq!(entries[0].level, LogLevel::Error);
+        assert_eq!(entries[0].fields["stream"], "stderr");
+        assert_eq!(entries[1].message, "slow query");
+        assert_eq!(entries[1].level, LogLevel::Warning);
+        assert_eq!(entries[2].message, "tail without end");
+        assert_eq!(entries[2].timestamp, "2024-01-15 10:30:46");
+
+        assert!(split("2024-01-15T10:30:45Z stdout X message").is_none());
+        assert!(parse_line("2024-01-15 10:30:45 [INFO] plain text").is_none());
+    }
+}
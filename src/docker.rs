@@ -0,0 +1,340 @@
+use crate::jsonl::{self, InputKeys};
+use crate::{LogEntry, LogLevel};
+use serde::Deserialize;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::Path;
+
+/// Préfixe des entrées désignant un conteneur (`docker://api`), ajoutées par --docker
+pub const SCHEME: &str = "docker://";
+
+/// Démon joint sans DOCKER_HOST
+const DEFAULT_HOST: &str = "unix:///var/run/docker.sock";
+
+/// Type MIME des réponses sans TTY: stdout et stderr entrelacés en trames
+const MULTIPLEXED: &str = "application/vnd.docker.multiplexed-stream";
+
+/// Nom ou identifiant du conteneur désigné par une entrée `docker://nom`
+pub fn container(path: &Path) -> Option<&str> {
+    path.to_str()?.strip_prefix(SCHEME)
+}
+
+trait Stream: Read + Write {}
+impl<T: Read + Write> Stream for T {}
+
+/// Connexion au démon selon DOCKER_HOST: socket Unix ou `tcp://hôte:port` (sans TLS)
+fn connect() -> io::Result<Box<dyn Stream>> {
+    let host = std::env::var("DOCKER_HOST").unwrap_or_else(|_| DEFAULT_HOST.to_string());
+    if let Some(addr) = host.strip_prefix("tcp://") {
+        return Ok(Box::new(std::net::TcpStream::connect(addr)?));
+    }
+    #[cfg(unix)]
+    if let Some(socket) = host.strip_prefix("unix://") {
+        return Ok(Box::new(std::os::unix::net::UnixStream::connect(socket)?));
+    }
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!("DOCKER_HOST non pris en charge: {host} (unix:// ou tcp://)"),
+    ))
+}
+
+/// Journal complet du conteneur, stdout et stderr, au format JSON du pilote
+/// `json-file` de Docker (une ligne `{"log","stream","time"}` par ligne écrite)
+pub fn open(container: &str) -> io::Result<Box<dyn Read>> {
+    let valid = !container.is_empty()
+        && container
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_.-".contains(c));
+    if !valid {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("nom de conteneur invalide: {container}"),
+        ));
+    }
+    let mut stream = connect()?;
+    // HTTP/1.0: le démon ferme la connexion en fin de corps, sans découpage chunked
+    write!(
+        stream,
+        "GET /containers/{container}/logs?stdout=1&stderr=1&timestamps=1 HTTP/1.0\r\nHost: docker\r\n\r\n"
+    )?;
+    let mut reader = BufReader::new(stream);
+    let mut status = String::new();
+    reader.read_line(&mut status)?;
+    let code = status.split_whitespace().nth(1).unwrap_or_default();
+    let mut multiplexed = false;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.eq_ignore_ascii_case("content-type")
+        {
+            multiplexed = value.trim() == MULTIPLEXED;
+        }
+    }
+    match code {
+        "200" => Ok(Box::new(DockerLogs::new(reader, multiplexed))),
+        "404" => Err(io::ErrorKind::NotFound.into()),
+        _ => {
+            let mut body = String::new();
+            reader.read_to_string(&mut body)?;
+            let message = serde_json::from_str::<serde_json::Value>(&body)
+                .ok()
+                .and_then(|v| Some(v.get("message")?.as_str()?.to_string()))
+                .unwrap_or(body);
+            Err(io::Error::other(format!(
+                "réponse Docker {code}: {}",
+                message.trim()
+            )))
+        }
+    }
+}
+
+/// Corps de `/containers/{id}/logs` réécrit en lignes `json-file`. Sans TTY,
+/// chaque trame commence par 8 octets: flux (1 stdout, 2 stderr), 3 octets
+/// nuls, puis la taille du contenu en big-endian. Une ligne peut s'étendre
+/// sur plusieurs trames d'un même flux.
+struct DockerLogs<R> {
+    inner: R,
+    multiplexed: bool,
+    /// Faux si stdout et stderr arrivent mêlés: le flux n'est alors pas indiqué
+    labelled: bool,
+    /// Lignes incomplètes de stdout et de stderr
+    pending: [Vec<u8>; 2],
+    out: Vec<u8>,
+    pos: usize,
+    done: bool,
+}
+
+const STREAMS: [&str; 2] = ["stdout", "stderr"];
+
+impl<R: Read> DockerLogs<R> {
+    fn new(inner: R, multiplexed: bool) -> Self {
+        DockerLogs {
+            inner,
+            multiplexed,
+            labelled: true,
+            pending: Default::default(),
+            out: Vec::new(),
+            pos: 0,
+            done: false,
+        }
+    }
+
+    /// Lit la trame suivante et convertit les lignes qu'elle termine
+    fn fill(&mut self) -> io::Result<()> {
+        let stream = if self.multiplexed {
+            let mut header = [0u8; 8];
+            match self.inner.read_exact(&mut header) {
+                Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => {
+                    self.done = true;
+                    0
+                }
+                Err(err) => return Err(err),
+                Ok(()) => {
+                    let size = u32::from_be_bytes([header[4], header[5], header[6], header[7]]);
+                    let stream = usize::from(header[0] == 2);
+                    let read = (&mut self.inner)
+                        .take(u64::from(size))
+                        .read_to_end(&mut self.pending[stream])?;
+                    // Flux coupé en pleine trame: ne pas livrer une dernière ligne tronquée
+                    if read != size as usize {
+                        return Err(io::Error::new(
+                            io::ErrorKind::UnexpectedEof,
+                            format!("trame Docker tronquée: {read} octets sur {size}"),
+                        ));
+                    }
+                    stream
+                }
+            }
+        } else {
+            let mut chunk = [0u8; 8192];
+            let n = self.inner.read(&mut chunk)?;
+            self.done = n == 0;
+            self.pending[0].extend_from_slice(&chunk[..n]);
+            0
+        };
+        let streams = if self.done { vec![0, 1] } else { vec![stream] };
+        for stream in streams {
+            let pending = &mut self.pending[stream];
+            let end = match memchr::memrchr(b'\n', pending) {
+                _ if self.done => pending.len(),
+                Some(end) => end + 1,
+                None => continue,
+            };
+            let lines: Vec<u8> = pending.drain(..end).collect();
+            for line in lines.split(|&b| b == b'\n').filter(|l| !l.is_empty()) {
+                let line = String::from_utf8_lossy(line);
+                let line = line.trim_end_matches('\r');
+                let (time, log) = line.split_once(' ').unwrap_or((line, ""));
+                let mut record = serde_json::json!({
+                    "log": format!("{log}\n"),
+                    "time": time,
+                });
+                if self.labelled {
+                    record["stream"] = STREAMS[stream].into();
+                }
+                self.out.extend_from_slice(record.to_string().as_bytes());
+                self.out.push(b'\n');
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for DockerLogs<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.out.len() && !self.done {
+            self.out.clear();
+            self.pos = 0;
+            self.fill()?;
+        }
+        let n = buf.len().min(self.out.len() - self.pos);
+        buf[..n].copy_from_slice(&self.out[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// Lignes `horodatage message` d'une source qui mêle stdout et stderr (journal
+/// d'un pod Kubernetes), réécrites au format `json-file` sans le flux
+#[cfg(any(test, feature = "k8s"))]
+pub fn timestamped<'a>(inner: impl Read + 'a) -> Box<dyn Read + 'a> {
+    Box::new(DockerLogs {
+        labelled: false,
+        ..DockerLogs::new(inner, false)
+    })
+}
+
+#[derive(Deserialize)]
+struct Record {
+    log: String,
+    #[serde(default)]
+    stream: String,
+    time: String,
+}
+
+/// Niveau annoncé dans les premiers mots d'une ligne libre (`[WARN]`,
+/// `ERROR:`, `level=error`...)
+fn level_hint(message: &str) -> Option<LogLevel> {
+    message.split_whitespace().take(4).find_map(|word| {
+        let word = word.strip_prefix("level=").unwrap_or(word);
+        jsonl::parse_level(word.trim_matches(|c: char| !c.is_ascii_alphabetic()))
+    })
+}
+
+/// Ligne écrite par un conteneur, horodatée par le moteur. Elle est lue
+/// comme une ligne texte ou JSON quand elle en a la forme; sinon l'horodatage
+/// vient du moteur et le niveau des premiers mots, à défaut du flux (stderr
+/// en ERROR, stdout en INFO). Le flux est gardé dans le champ `stream`.
+pub fn parse_output(log: &str, time: &str, stream: &str) -> Option<LogEntry> {
+    let mut entry = crate::parse_log_line(log)
+        .or_else(|| jsonl::parse_line(log, &InputKeys::default()))
+        .or_else(|| {
+            let datetime = jsonl::parse_time(time)?;
+            let level = level_hint(log).unwrap_or(if stream == "stderr" {
+                LogLevel::Error
+            } else {
+                LogLevel::Info
+            });
+            Some(LogEntry {
+                timestamp: datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
+                datetime,
+                level,
+                message: log.to_string(),
+                tags: Vec::new(),
+                fields: Default::default(),
+            })
+        })?;
+    if !stream.is_empty() {
+        entry
+            .fields
+            .insert("stream".to_string(), stream.to_string());
+    }
+    Some(entry)
+}
+
+/// Une ligne du format `json-file` de Docker
+pub fn parse_line(line: &str) -> Option<LogEntry> {
+    let record: Record = serde_json::from_str(line).ok()?;
+    let log = record.log.trim_end_matches(['\n', '\r']);
+    parse_output(log, &record.time, &record.stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(stream: u8, payload: &str) -> Vec<u8> {
+        let mut frame = vec![stream, 0, 0, 0];
+        frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        frame.extend_from_slice(payload.as_bytes());
+        frame
+    }
+
+    #[test]
+    fn demultiplexes_and_parses_container_logs() {
+        let body = [
+            frame(
+                1,
+                "2024-01-15T10:30:45.123456789Z 2024-01-15 10:30:45 [WARNING] slow\n",
+            ),
+            frame(2, "2024-01-15T10:30:46.5Z panic: "),
+            frame(1, "2024-01-15T10:30:47Z listening on :8080\n"),
+            frame(2, "nil map\n"),
+        ]
+        .concat();
+        let mut text = String::new();
+        DockerLogs::new(body.as_slice(), true)
+            .read_to_string(&mut text)
+            .unwrap();
+        let entries: Vec<_> = text.lines().map(|l| parse_line(l).unwrap()).collect();
+        assert_eq!(entries.len(), 3);
+
+        assert_eq!(entries[0].level, LogLevel::Warning);
+        assert_eq!(entries[0].message, "slow");
+        assert_eq!(entries[0].fields["stream"], "stdout");
+        assert_eq!(entries[1].message, "listening on :8080");
+        assert_eq!(entries[1].level, LogLevel::Info);
+        assert_eq!(entries[2].message, "panic: nil map");
+        assert_eq!(entries[2].level, LogLevel::Error);
+        assert_eq!(entries[2].timestamp, "2024-01-15 10:30:46");
+
+        let tty = b"2024-01-15T10:30:45Z [WARN] disk at 91%\n";
+        let mut text = String::new();
+        DockerLogs::new(tty.as_slice(), false)
+            .read_to_string(&mut text)
+            .unwrap();
+        assert_eq!(parse_line(text.trim()).unwrap().level, LogLevel::Warning);
+
+        let mut text = String::new();
+        timestamped(b"2024-01-15T10:30:45Z fatal error\n".as_slice())
+            .read_to_string(&mut text)
+            .unwrap();
+        let entry = parse_line(text.trim()).unwrap();
+        assert_eq!(entry.level, LogLevel::Error);
+        assert!(!entry.fields.contains_key("stream"));
+        assert_eq!(container(Path::new("docker://api-1")), Some("api-1"));
+    }
+
+    #[test]
+    fn rejects_a_truncated_frame() {
+        let mut body = frame(
+            1,
+            "2024-01-15T10:30:45Z first
+",
+        );
+        let cut = frame(
+            1,
+            "2024-01-15T10:30:46Z second line
+",
+        );
+        body.extend_from_slice(&cut[..cut.len() - 6]);
+        let mut text = String::new();
+        let err = DockerLogs::new(body.as_slice(), true)
+            .read_to_string(&mut text)
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        assert!(!text.contains("second"));
+    }
+}
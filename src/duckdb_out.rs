@@ -0,0 +1,184 @@
+use crate::LogEntry;
+use crate::export::columns;
+use crate::lookup::split_csv_line;
+use crate::metadata::ReportMetadata;
+use duckdb::{Connection, appender_params_from_iter};
+use std::path::Path;
+
+/// Séparateur des tags dans la table de transit (l'appender ne sait pas
+/// encore ajouter de listes)
+const TAG_SEPARATOR: char = '\u{1f}';
+
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+/// Écrit les entrées dans la table `entries` (mêmes colonnes que l'export
+/// Arrow) et les statistiques dans `stats(metric, key, value)`, sur le modèle
+/// de `--format csv`. Les métadonnées du rapport vont dans `metadata(key,
+/// value)` et ses entrées dans `inputs(file, bytes, sha256)`. Les tables
+/// existantes du fichier sont remplacées.
+pub fn write(
+    path: &Path,
+    entries: &[LogEntry],
+    select: &[String],
+    stats_csv: &str,
+    metadata: Option<&ReportMetadata>,
+) -> duckdb::Result<()> {
+    let conn = Connection::open(path)?;
+    let columns = columns(entries, select);
+
+    let staging: Vec<String> = columns
+        .iter()
+        .map(|c| format!("{} VARCHAR", quote(c)))
+        .collect();
+    conn.execute_batch(&format!(
+        "CREATE OR REPLACE TEMP TABLE entries_staging ({})",
+        staging.join(", ")
+    ))?;
+    {
+        let mut appender = conn.appender("entries_staging")?;
+        for entry in entries {
+            let row = columns.iter().map(|c| match c.as_str() {
+                "timestamp" => Some(entry.datetime.format("%Y-%m-%d %H:%M:%S").to_string()),
+                "level" => Some(entry.level.as_str().to_string()),
+                "message" => Some(entry.message.clone()),
+                "tags" => Some(entry.tags.join(&TAG_SEPARATOR.to_string())),
+                field => entry.fields.get(field).cloned(),
+            });
+            appender.append_row(appender_params_from_iter(row))?;
+        }
+    }
+    let projection: Vec<String> = columns
+        .iter()
+        .map(|c| match c.as_str() {
+            "timestamp" => "CAST(timestamp AS TIMESTAMP) AS timestamp".to_string(),
+            "tags" => format!(
+                "CASE WHEN tags = '' THEN []::VARCHAR[] \
+                 ELSE string_split(tags, chr({})) END AS tags",
+                TAG_SEPARATOR as u32
+            ),
+            other => quote(other),
+        })
+        .collect();
+    conn.execute_batch(&format!(
+        "CREATE OR REPLACE TABLE entries AS SELECT {} FROM entries_staging; \
+         DROP TABLE entries_staging; \
+         CREATE OR REPLACE TABLE stats (metric VARCHAR, key VARCHAR, value VARCHAR); \
+         CREATE OR REPLACE TABLE metadata (key VARCHAR, value VARCHAR); \
+         CREATE OR REPLACE TABLE inputs (file VARCHAR, bytes UBIGINT, sha256 VARCHAR);",
+        projection.join(", ")
+    ))?;
+
+    let mut appender = conn.appender("stats")?;
+    for line in stats_csv.lines().skip(1) {
+        let values = split_csv_line(line);
+        appender.append_row(appender_params_from_iter(values.iter().take(3)))?;
+    }
+    drop(appender);
+
+    let Some(meta) = metadata else {
+        return Ok(());
+    };
+    let mut appender = conn.appender("metadata")?;
+    let rows = [
+        ("version", Some(meta.version.to_string())),
+        ("command_line", Some(meta.command_line.clone())),
+        ("generated_at", Some(meta.generated_at.clone())),
+        ("wall_time_ms", Some(meta.wall_time_ms.to_string())),
+        ("host", meta.host.clone()),
+    ];
+    for (key, value) in rows {
+        if let Some(value) = value {
+            appender.append_row([key, value.as_str()])?;
+        }
+    }
+    drop(appender);
+    let mut appender = conn.appender("inputs")?;
+    for input in &meta.inputs {
+        appender.append_row(duckdb::params![input.file, input.bytes, input.sha256])?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_log_line;
+
+    #[test]
+    fn writes_queryable_entries_and_stats() {
+        let mut entries: Vec<LogEntry> = [
+            "2024-01-15 10:30:45 [ERROR] timeout host=db1",
+            "2024-01-15 10:31:00 [INFO] ok",
+        ]
+        .iter()
+        .map(|l| parse_log_line(l).unwrap())
+        .collect();
+        for entry in &mut entries {
+            entry.fields = crate::fields::extract(&entry.message);
+        }
+        entries[0].tags = vec!["payment".to_string(), "external".to_string()];
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs.duckdb");
+        write(
+            &path,
+            &entries,
+            &[],
+            "metric,key,value\ntotal,,2\nlevel,ERROR,1\n",
+            Some(&ReportMetadata::new(
+                vec![crate::metadata::InputMeta::new(
+                    Path::new("app.log"),
+                    Some(42),
+                    None,
+                )],
+                std::time::Duration::ZERO,
+            )),
+        )
+        .unwrap();
+
+        let conn = Connection::open(&path).unwrap();
+        let (host, tags): (String, usize) = conn
+            .query_row(
+                "SELECT host, len(tags) FROM entries WHERE level = 'ERROR' \
+                 AND timestamp = TIMESTAMP '2024-01-15 10:30:45'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((host.as_str(), tags), ("db1", 2));
+        let empty: usize = conn
+            .query_row(
+                "SELECT len(tags) FROM entries WHERE level = 'INFO'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(empty, 0);
+        let errors: String = conn
+            .query_row(
+                "SELECT value FROM stats WHERE metric = 'level' AND key = 'ERROR'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(errors, "1");
+        let version: String = conn
+            .query_row(
+                "SELECT value FROM metadata WHERE key = 'version'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(version, env!("CARGO_PKG_VERSION"));
+        let bytes: u64 = conn
+            .query_row(
+                "SELECT bytes FROM inputs WHERE file = 'app.log'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(bytes, 42);
+    }
+}
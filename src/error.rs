@@ -0,0 +1,95 @@
+use crate::{EXIT_ERROR, EXIT_MISSING_FILE};
+use std::io;
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Erreurs remontées jusqu'à `main`, qui affiche le message et sort avec le
+/// code associé. Les autres erreurs (`?` sur io, serde...) sortent en 1.
+#[derive(Debug, Error)]
+pub enum LoglyzerError {
+    #[error("Fichier introuvable: {}", .0.display())]
+    MissingFile(PathBuf),
+
+    /// Fichier ouvert sans partage par un autre processus (Windows), même
+    /// après quelques tentatives
+    #[error("Fichier verrouillé par un autre processus: {}", .0.display())]
+    Locked(PathBuf),
+
+    #[error("Impossible de lire le fichier {}: {source}", path.display())]
+    Unreadable { path: PathBuf, source: io::Error },
+
+    #[error("Impossible d'écrire {}: {source}", path.display())]
+    Unwritable { path: PathBuf, source: io::Error },
+
+    /// Arguments, configuration, tables ou marqueurs rejetés à la lecture
+    #[error("{0}")]
+    Invalid(String),
+
+    /// Source ou destination réseau injoignable; `target` complète
+    /// « Impossible de … » (ex: « joindre syslog://hôte:514 »)
+    #[error("Impossible de {target}: {reason}")]
+    Connect { target: String, reason: String },
+}
+
+impl LoglyzerError {
+    /// Fichier introuvable ou verrouillé à la lecture, erreur de lecture sinon
+    pub fn reading(path: &std::path::Path, err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::NotFound => LoglyzerError::MissingFile(path.to_path_buf()),
+            _ if crate::platform::is_locked(&err) => LoglyzerError::Locked(path.to_path_buf()),
+            _ => LoglyzerError::Unreadable {
+                path: path.to_path_buf(),
+                source: err,
+            },
+        }
+    }
+
+    /// Sortie déjà présente (sans --force ni --append), erreur d'écriture sinon
+    pub fn writing(path: &std::path::Path, err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::AlreadyExists => LoglyzerError::Invalid(err.to_string()),
+            _ => LoglyzerError::Unwritable {
+                path: path.to_path_buf(),
+                source: err,
+            },
+        }
+    }
+
+    pub fn connect(target: impl Into<String>, reason: impl std::fmt::Display) -> Self {
+        LoglyzerError::Connect {
+            target: target.into(),
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            LoglyzerError::MissingFile(_) => EXIT_MISSING_FILE,
+            _ => EXIT_ERROR,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn maps_read_failures_to_exit_codes() {
+        let missing = LoglyzerError::reading(Path::new("app.log"), io::ErrorKind::NotFound.into());
+        assert_eq!(missing.to_string(), "Fichier introuvable: app.log");
+        assert_eq!(missing.exit_code(), EXIT_MISSING_FILE);
+
+        let denied =
+            LoglyzerError::reading(Path::new("app.log"), io::ErrorKind::PermissionDenied.into());
+        assert!(matches!(denied, LoglyzerError::Unreadable { .. }));
+        assert_eq!(denied.exit_code(), EXIT_ERROR);
+
+        let down = LoglyzerError::connect("joindre syslog://h:514", "connection refused");
+        assert_eq!(
+            down.to_string(),
+            "Impossible de joindre syslog://h:514: connection refused"
+        );
+    }
+}
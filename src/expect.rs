@@ -0,0 +1,163 @@
+use crate::{LogEntry, LogLevel};
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Op {
+    fn holds(self, actual: f64, bound: f64) -> bool {
+        match self {
+            Op::Lt => actual < bound,
+            Op::Le => actual <= bound,
+            Op::Gt => actual > bound,
+            Op::Ge => actual >= bound,
+        }
+    }
+}
+
+/// Une attente de `--expect`: part (`%`) ou nombre d'entrées d'un niveau
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expectation {
+    text: String,
+    level: LogLevel,
+    op: Op,
+    bound: f64,
+    pct: bool,
+}
+
+/// `ERROR<1%`, `WARNING<=5%` ou `INFO>100`: niveau, comparaison (<, <=, >,
+/// >=), puis un pourcentage des entrées retenues ou un nombre
+pub fn parse_expectation(input: &str) -> Result<Expectation, String> {
+    let invalid = || format!("Attente invalide: {input} (ex: ERROR<1%,WARNING<5%)");
+    let at = input.find(['<', '>']).ok_or_else(invalid)?;
+    let level = LogLevel::from_str(input[..at].trim()).ok_or_else(invalid)?;
+    let (op, value) = [("<=", Op::Le), (">=", Op::Ge), ("<", Op::Lt), (">", Op::Gt)]
+        .into_iter()
+        .find_map(|(sym, op)| input[at..].strip_prefix(sym).map(|v| (op, v.trim())))
+        .ok_or_else(invalid)?;
+    let (value, pct) = match value.strip_suffix('%') {
+        Some(v) => (v.trim(), true),
+        None => (value, false),
+    };
+    let bound: f64 = value.parse().map_err(|_| invalid())?;
+    if !bound.is_finite() || bound < 0.0 || (pct && bound > 100.0) {
+        return Err(invalid());
+    }
+    Ok(Expectation {
+        text: input.split_whitespace().collect(),
+        level,
+        op,
+        bound,
+        pct,
+    })
+}
+
+/// Résultat d'une attente sur les entrées retenues
+#[derive(Debug)]
+pub struct Outcome<'a> {
+    pub expectation: &'a Expectation,
+    pub count: usize,
+    pub total: usize,
+    pub passed: bool,
+}
+
+fn rate(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 / total as f64 * 100.0
+    }
+}
+
+pub fn evaluate<'a>(expectations: &'a [Expectation], entries: &[LogEntry]) -> Vec<Outcome<'a>> {
+    expectations
+        .iter()
+        .map(|expectation| {
+            let count = entries
+                .iter()
+                .filter(|e| e.level == expectation.level)
+                .count();
+            let total = entries.len();
+            let actual = if expectation.pct {
+                rate(count, total)
+            } else {
+                count as f64
+            };
+            Outcome {
+                expectation,
+                count,
+                total,
+                passed: expectation.op.holds(actual, expectation.bound),
+            }
+        })
+        .collect()
+}
+
+pub fn render(outcomes: &[Outcome]) -> String {
+    let mut output = String::from("Attentes (--expect):\n");
+    let width = outcomes
+        .iter()
+        .map(|o| o.expectation.text.len())
+        .max()
+        .unwrap_or(0);
+    for o in outcomes {
+        let actual = if o.expectation.pct {
+            format!("{:.2}% ({}/{})", rate(o.count, o.total), o.count, o.total)
+        } else {
+            o.count.to_string()
+        };
+        writeln!(
+            output,
+            "  {} {:<width$}  {actual}",
+            if o.passed { "✓" } else { "✗" },
+            o.expectation.text,
+        )
+        .unwrap();
+    }
+    let failed = outcomes.iter().filter(|o| !o.passed).count();
+    if failed == 0 {
+        output.push_str("Profil conforme.");
+    } else {
+        write!(output, "{failed} attente(s) non tenue(s).").unwrap();
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_log_line;
+
+    #[test]
+    fn evaluates_rates_and_counts() {
+        let entries: Vec<LogEntry> = [
+            "2024-01-15 10:30:45 [ERROR] timeout",
+            "2024-01-15 10:30:46 [WARNING] slow",
+            "2024-01-15 10:30:47 [INFO] ok",
+            "2024-01-15 10:30:48 [INFO] ok",
+        ]
+        .iter()
+        .map(|l| parse_log_line(l).unwrap())
+        .collect();
+        let expectations: Vec<Expectation> = ["ERROR<1%", "warn <= 25 %", "INFO>=2"]
+            .into_iter()
+            .map(|e| parse_expectation(e).unwrap())
+            .collect();
+        let outcomes = evaluate(&expectations, &entries);
+        let passed: Vec<bool> = outcomes.iter().map(|o| o.passed).collect();
+        assert_eq!(passed, [false, true, true]);
+        let text = render(&outcomes);
+        assert!(text.contains("✗ ERROR<1%   25.00% (1/4)"));
+        assert!(text.contains("1 attente(s) non tenue(s)."));
+
+        assert!(parse_expectation("FATAL<1%").is_err());
+        assert!(parse_expectation("ERROR=1%").is_err());
+        assert!(parse_expectation("ERROR<150%").is_err());
+        assert!(parse_expectation("").is_err());
+    }
+}
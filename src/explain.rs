@@ -0,0 +1,126 @@
+use prettytable::{Cell, Row, Table};
+use std::fmt::Write;
+
+/// Une étape de filtrage et le nombre d'entrées qu'elle a écartées
+#[derive(Debug)]
+pub struct Stage {
+    pub label: String,
+    pub removed: usize,
+}
+
+/// Bilan de `--explain`: lignes lues, puis entrées écartées à chaque étape,
+/// dans l'ordre où les filtres s'appliquent
+#[derive(Debug)]
+pub struct Explain {
+    pub lines: usize,
+    pub skipped: usize,
+    /// Entrées dont le niveau a changé via `[[reclassify]]`, si des règles existent
+    pub reclassified: Option<usize>,
+    pub stages: Vec<Stage>,
+    pub partial: bool,
+}
+
+impl Explain {
+    pub fn kept(&self) -> usize {
+        self.lines - self.skipped - self.stages.iter().map(|s| s.removed).sum::<usize>()
+    }
+}
+
+pub fn render(explain: &Explain, source: &str) -> String {
+    let mut output = String::new();
+    writeln!(output, "Filter plan: {source}\n").unwrap();
+    if explain.partial {
+        writeln!(
+            output,
+            "⚠️  Lecture interrompue (Ctrl-C): comptes partiels\n"
+        )
+        .unwrap();
+    }
+
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![
+        Cell::new("Stage"),
+        Cell::new("Removed"),
+        Cell::new("Remaining"),
+    ]));
+    let mut remaining = explain.lines;
+    table.add_row(Row::new(vec![
+        Cell::new("lignes lues"),
+        Cell::new(""),
+        Cell::new(&remaining.to_string()),
+    ]));
+    remaining -= explain.skipped;
+    table.add_row(Row::new(vec![
+        Cell::new("format reconnu"),
+        Cell::new(&explain.skipped.to_string()),
+        Cell::new(&remaining.to_string()),
+    ]));
+    for stage in &explain.stages {
+        remaining -= stage.removed;
+        table.add_row(Row::new(vec![
+            Cell::new(&stage.label),
+            Cell::new(&stage.removed.to_string()),
+            Cell::new(&remaining.to_string()),
+        ]));
+    }
+    write!(output, "{table}").unwrap();
+
+    if let Some(count) = explain.reclassified {
+        writeln!(
+            output,
+            "\nReclassement ([[reclassify]]): {count} entrées changent de niveau avant le filtrage."
+        )
+        .unwrap();
+    }
+    if explain.stages.is_empty() {
+        writeln!(
+            output,
+            "\nAucun filtre actif: toutes les entrées reconnues sont retenues."
+        )
+        .unwrap();
+    } else if let Some(stage) = explain
+        .stages
+        .iter()
+        .filter(|s| s.removed > 0)
+        .max_by_key(|s| s.removed)
+        && explain.kept() == 0
+    {
+        writeln!(
+            output,
+            "\nAucune entrée retenue; l'étape la plus sélective est « {} ».",
+            stage.label
+        )
+        .unwrap();
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_remaining_counts_per_stage() {
+        let explain = Explain {
+            lines: 100,
+            skipped: 4,
+            reclassified: Some(3),
+            stages: vec![
+                Stage {
+                    label: "--errors-only".to_string(),
+                    removed: 80,
+                },
+                Stage {
+                    label: "--search \"timeout\"".to_string(),
+                    removed: 16,
+                },
+            ],
+            partial: false,
+        };
+        assert_eq!(explain.kept(), 0);
+        let text = render(&explain, "app.log");
+        assert!(text.contains("| --errors-only      | 80      | 16        |"));
+        assert!(text.contains("3 entrées changent de niveau"));
+        assert!(text.contains("l'étape la plus sélective est « --errors-only »"));
+    }
+}
@@ -0,0 +1,232 @@
+use crate::LogEntry;
+use crate::fields::BUILTIN;
+use crate::metadata::ReportMetadata;
+use arrow_array::builder::{ListBuilder, StringBuilder};
+use arrow_array::{ArrayRef, RecordBatch, StringArray, TimestampSecondArray};
+use arrow_ipc::writer::StreamWriter;
+use arrow_schema::{ArrowError, DataType, Field, Schema, TimeUnit};
+use serde_json::{Map, Value};
+use std::collections::{BTreeSet, HashMap};
+use std::io::Write;
+use std::sync::Arc;
+
+/// Taille des lots Arrow écrits dans le flux IPC
+const BATCH_SIZE: usize = 64 * 1024;
+
+/// Clé des métadonnées du rapport dans le schéma Arrow
+pub const METADATA_KEY: &str = "loglyzer";
+
+/// Colonnes exportées: la projection `select` si fournie, sinon les colonnes
+/// intégrées suivies de tous les champs rencontrés.
+pub fn columns(entries: &[LogEntry], select: &[String]) -> Vec<String> {
+    if !select.is_empty() {
+        return select.to_vec();
+    }
+    let fields: BTreeSet<&String> = entries.iter().flat_map(|e| e.fields.keys()).collect();
+    BUILTIN
+        .iter()
+        .map(|c| c.to_string())
+        .chain(
+            fields
+                .into_iter()
+                .filter(|f| !BUILTIN.contains(&f.as_str()))
+                .cloned(),
+        )
+        .collect()
+}
+
+fn arrow_field(name: &str) -> Field {
+    match name {
+        "timestamp" => Field::new(name, DataType::Timestamp(TimeUnit::Second, None), false),
+        "level" | "message" => Field::new(name, DataType::Utf8, false),
+        "tags" => Field::new(
+            name,
+            DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
+            false,
+        ),
+        _ => Field::new(name, DataType::Utf8, true),
+    }
+}
+
+fn arrow_column(name: &str, entries: &[LogEntry]) -> ArrayRef {
+    match name {
+        "timestamp" => Arc::new(TimestampSecondArray::from_iter_values(
+            entries.iter().map(|e| e.datetime.and_utc().timestamp()),
+        )),
+        "level" => Arc::new(StringArray::from_iter_values(
+            entries.iter().map(|e| e.level.as_str()),
+        )),
+        "message" => Arc::new(StringArray::from_iter_values(
+            entries.iter().map(|e| e.message.as_str()),
+        )),
+        "tags" => {
+            let mut tags = ListBuilder::new(StringBuilder::new());
+            for entry in entries {
+                for tag in &entry.tags {
+                    tags.values().append_value(tag);
+                }
+                tags.append(true);
+            }
+            Arc::new(tags.finish())
+        }
+        _ => Arc::new(StringArray::from_iter(
+            entries
+                .iter()
+                .map(|e| e.fields.get(name).map(String::as_str)),
+        )),
+    }
+}
+
+/// Écrit les entrées filtrées en flux Arrow IPC (lisible par
+/// `pandas.read_feather`/`pyarrow.ipc.open_stream` ou `polars.read_ipc_stream`).
+pub fn write_arrow(
+    entries: &[LogEntry],
+    select: &[String],
+    metadata: Option<&ReportMetadata>,
+    out: impl Write,
+) -> Result<(), ArrowError> {
+    let columns = columns(entries, select);
+    let schema_metadata = metadata
+        .map(|meta| {
+            HashMap::from([(
+                METADATA_KEY.to_string(),
+                serde_json::json!(meta).to_string(),
+            )])
+        })
+        .unwrap_or_default();
+    let schema = Arc::new(
+        Schema::new(columns.iter().map(|c| arrow_field(c)).collect::<Vec<_>>())
+            .with_metadata(schema_metadata),
+    );
+    let mut writer = StreamWriter::try_new(out, &schema)?;
+    for chunk in entries.chunks(BATCH_SIZE) {
+        let arrays = columns.iter().map(|c| arrow_column(c, chunk)).collect();
+        writer.write(&RecordBatch::try_new(schema.clone(), arrays)?)?;
+    }
+    writer.finish()
+}
+
+fn json_value(name: &str, entry: &LogEntry) -> Value {
+    match name {
+        "timestamp" => Value::from(entry.timestamp.as_str()),
+        "level" => Value::from(entry.level.as_str()),
+        "message" => Value::from(entry.message.as_str()),
+        "tags" => Value::from(entry.tags.clone()),
+        _ => entry
+            .fields
+            .get(name)
+            .map_or(Value::Null, |v| Value::from(v.as_str())),
+    }
+}
+
+/// Objet JSON d'une entrée: colonnes intégrées et champs propres à l'entrée,
+/// ou seulement la projection `select` si elle est fournie.
+pub fn entry_json(entry: &LogEntry, select: &[String]) -> Map<String, Value> {
+    if select.is_empty() {
+        BUILTIN
+            .iter()
+            .map(|c| c.to_string())
+            .chain(
+                entry
+                    .fields
+                    .keys()
+                    .filter(|f| !BUILTIN.contains(&f.as_str()))
+                    .cloned(),
+            )
+            .map(|c| {
+                let value = json_value(&c, entry);
+                (c, value)
+            })
+            .collect()
+    } else {
+        select
+            .iter()
+            .map(|c| (c.clone(), json_value(c, entry)))
+            .collect()
+    }
+}
+
+/// Écrit les entrées filtrées en JSON Lines, un objet par entrée.
+pub fn write_jsonl(
+    entries: &[LogEntry],
+    select: &[String],
+    mut out: impl Write,
+) -> std::io::Result<()> {
+    for entry in entries {
+        serde_json::to_writer(&mut out, &entry_json(entry, select))?;
+        out.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fields, parse_log_line};
+    use arrow_array::Array;
+    use arrow_ipc::reader::StreamReader;
+    use std::io::Cursor;
+
+    fn entries() -> Vec<LogEntry> {
+        let mut entries: Vec<_> = [
+            "2024-01-15 10:30:45 [ERROR] API timeout user=bob",
+            "2024-01-15 10:31:45 [INFO] OK",
+        ]
+        .iter()
+        .map(|l| parse_log_line(l).unwrap())
+        .collect();
+        entries[0].tags = vec!["api".to_string()];
+        for entry in &mut entries {
+            entry.fields = fields::extract(&entry.message);
+        }
+        entries
+    }
+
+    #[test]
+    fn arrow_stream_round_trips_entries() {
+        let mut buf = Vec::new();
+        let meta = ReportMetadata::new(Vec::new(), std::time::Duration::ZERO);
+        write_arrow(&entries(), &[], Some(&meta), &mut buf).unwrap();
+
+        let mut reader = StreamReader::try_new(Cursor::new(buf), None).unwrap();
+        let stored: Value =
+            serde_json::from_str(&reader.schema().metadata()[METADATA_KEY]).unwrap();
+        assert_eq!(stored["version"], env!("CARGO_PKG_VERSION"));
+        let batch = reader.next().unwrap().unwrap();
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(batch.num_columns(), 5);
+        let levels = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(levels.value(0), "ERROR");
+        let ts = batch
+            .column(0)
+            .as_any()
+            .downcast_ref::<TimestampSecondArray>()
+            .unwrap();
+        assert_eq!(ts.value(1) - ts.value(0), 60);
+        assert!(!batch.column(3).is_null(1));
+        let user = batch.column_by_name("user").unwrap();
+        assert!(user.is_null(1));
+    }
+
+    #[test]
+    fn jsonl_keeps_fields_and_applies_projection() {
+        let mut buf = Vec::new();
+        write_jsonl(&entries(), &[], &mut buf).unwrap();
+        let first = String::from_utf8(buf).unwrap();
+        let first: Value = serde_json::from_str(first.lines().next().unwrap()).unwrap();
+        assert_eq!(first["user"], "bob");
+        assert_eq!(first["tags"][0], "api");
+
+        let mut buf = Vec::new();
+        let select = ["level".to_string(), "user".to_string()];
+        write_jsonl(&entries(), &select, &mut buf).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "{\"level\":\"ERROR\",\"user\":\"bob\"}\n{\"level\":\"INFO\",\"user\":null}\n"
+        );
+    }
+}
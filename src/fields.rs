@@ -0,0 +1,246 @@
+use crate::lookup::LookupTable;
+use crate::{LogEntry, UNTAGGED};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Colonnes toujours disponibles, dans l'ordre d'export par défaut
+pub const BUILTIN: [&str; 4] = ["timestamp", "level", "message", "tags"];
+
+static FIELD_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?:^|\s)([A-Za-z_][\w.-]*)=("(?:[^"\\]|\\.)*"|\S+)"#).unwrap());
+
+#[derive(Debug, Serialize)]
+pub struct ValueCount {
+    pub value: String,
+    pub count: usize,
+}
+
+/// Valeurs les plus fréquentes d'un champ
+#[derive(Debug, Serialize)]
+pub struct FieldTop {
+    pub field: String,
+    /// Nombre d'entrées portant le champ
+    pub matched: usize,
+    pub values: Vec<ValueCount>,
+}
+
+/// Valeur de repli d'une entrée qui ne porte pas le champ demandé
+const MISSING: &str = "-";
+
+/// Matrice de comptes croisant deux dimensions
+#[derive(Debug, Serialize)]
+pub struct Pivot {
+    pub columns: String,
+    pub rows: String,
+    pub column_keys: Vec<String>,
+    /// Comptes par valeur de ligne puis de colonne
+    pub counts: BTreeMap<String, BTreeMap<String, usize>>,
+}
+
+/// Paires `clé=valeur` (valeur éventuellement entre guillemets) présentes
+/// dans le message.
+pub fn extract(message: &str) -> BTreeMap<String, String> {
+    FIELD_RE
+        .captures_iter(message)
+        .map(|caps| {
+            let value = &caps[2];
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .map(|v| v.replace("\\\"", "\""))
+                .unwrap_or_else(|| value.to_string());
+            (caps[1].to_string(), value)
+        })
+        .collect()
+}
+
+/// Regex de `--capture`: chaque groupe nommé devient un champ des entrées
+pub fn parse_capture(input: &str) -> Result<Regex, String> {
+    let regex = Regex::new(input).map_err(|e| format!("Capture invalide: {e}"))?;
+    if regex.capture_names().flatten().next().is_none() {
+        return Err(format!(
+            "La capture doit nommer au moins un groupe, ex: tenant=(?P<tenant>\\w+) ({input})"
+        ));
+    }
+    Ok(regex)
+}
+
+/// Noms des groupes de `--capture`, sans doublon, dans l'ordre de la ligne de commande
+pub fn capture_names(captures: &[Regex]) -> Vec<&str> {
+    let mut names: Vec<&str> = Vec::new();
+    for name in captures.iter().flat_map(|re| re.capture_names().flatten()) {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Groupes nommés de la première correspondance de chaque regex dans le
+/// message; ils remplacent un champ du même nom, la capture étant explicite
+pub fn capture(message: &str, captures: &[Regex], fields: &mut BTreeMap<String, String>) {
+    for re in captures {
+        let Some(caps) = re.captures(message) else {
+            continue;
+        };
+        for name in re.capture_names().flatten() {
+            if let Some(value) = caps.name(name) {
+                fields.insert(name.to_string(), value.as_str().to_string());
+            }
+        }
+    }
+}
+
+/// Champs ajoutés à chaque entrée, en analyse comme en suivi: groupes de
+/// `--capture`, puis, si un rendu les consomme, paires `clé=valeur` du
+/// message et colonnes des tables `--lookup`.
+pub struct Enricher<'a> {
+    pub captures: &'a [Regex],
+    pub extract: bool,
+    pub lookups: Vec<LookupTable>,
+}
+
+impl Enricher<'_> {
+    pub fn enrich(&self, entry: &mut LogEntry) {
+        if !self.captures.is_empty() {
+            capture(&entry.message, self.captures, &mut entry.fields);
+        }
+        if self.extract {
+            // Les champs JSON priment sur les `clé=valeur` du message
+            for (key, value) in extract(&entry.message) {
+                entry.fields.entry(key).or_insert(value);
+            }
+            for table in &self.lookups {
+                table.enrich(&mut entry.fields);
+            }
+        }
+    }
+}
+
+/// Les `n` valeurs les plus fréquentes de `field` parmi les entrées qui le
+/// portent, ex æquo départagés par ordre alphabétique.
+pub fn top_values(entries: &[LogEntry], field: &str, n: usize) -> FieldTop {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for value in entries.iter().filter_map(|e| e.fields.get(field)) {
+        *counts.entry(value).or_insert(0) += 1;
+    }
+    let matched = counts.values().sum();
+    let mut values: Vec<_> = counts
+        .into_iter()
+        .map(|(value, count)| ValueCount {
+            value: value.to_string(),
+            count,
+        })
+        .collect();
+    values.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+    values.truncate(n);
+    FieldTop {
+        field: field.to_string(),
+        matched,
+        values,
+    }
+}
+
+/// Valeurs d'une dimension pour une entrée: niveau, heure, étiquettes
+/// (plusieurs possibles) ou champ structuré.
+fn dimension(entry: &LogEntry, name: &str) -> Vec<String> {
+    match name {
+        "level" => vec![entry.level.as_str().to_string()],
+        "hour" => vec![entry.datetime.format("%H:00").to_string()],
+        "tag" | "tags" if entry.tags.is_empty() => vec![UNTAGGED.to_string()],
+        "tag" | "tags" => entry.tags.clone(),
+        _ => vec![
+            entry
+                .fields
+                .get(name)
+                .cloned()
+                .unwrap_or_else(|| MISSING.to_string()),
+        ],
+    }
+}
+
+pub fn pivot(entries: &[LogEntry], columns: &str, rows: &str) -> Pivot {
+    let mut column_keys = BTreeSet::new();
+    let mut counts: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
+    for entry in entries {
+        let column_values = dimension(entry, columns);
+        for row in dimension(entry, rows) {
+            let cells = counts.entry(row).or_default();
+            for column in &column_values {
+                *cells.entry(column.clone()).or_insert(0) += 1;
+                column_keys.insert(column.clone());
+            }
+        }
+    }
+    Pivot {
+        columns: columns.to_string(),
+        rows: rows.to_string(),
+        column_keys: column_keys.into_iter().collect(),
+        counts,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_plain_and_quoted_values() {
+        let fields = extract(r#"request done user=alice path="/a b" status=500 ratio=x=1"#);
+        assert_eq!(fields["user"], "alice");
+        assert_eq!(fields["path"], "/a b");
+        assert_eq!(fields["status"], "500");
+        assert_eq!(fields["ratio"], "x=1");
+        assert_eq!(fields.len(), 4);
+        assert!(extract("a == b").is_empty());
+    }
+
+    #[test]
+    fn top_values_and_pivot_over_extracted_fields() {
+        let entries: Vec<_> = [
+            "2024-01-15 10:00:00 [ERROR] payment failed user=bob",
+            "2024-01-15 10:00:01 [ERROR] payment failed user=alice",
+            "2024-01-15 10:00:02 [ERROR] payment failed user=bob",
+            "2024-01-15 10:00:03 [ERROR] payment failed",
+        ]
+        .iter()
+        .map(|l| {
+            let mut e = crate::parse_log_line(l).unwrap();
+            e.fields = extract(&e.message);
+            e
+        })
+        .collect();
+
+        let top = top_values(&entries, "user", 1);
+        let pivot = pivot(&entries, "level", "user");
+        assert_eq!(pivot.column_keys, vec!["ERROR"]);
+        assert_eq!(pivot.counts["bob"]["ERROR"], 2);
+        assert_eq!(pivot.counts[MISSING]["ERROR"], 1);
+
+        assert_eq!(top.matched, 3);
+        assert_eq!(top.values.len(), 1);
+        assert_eq!(top.values[0].value, "bob");
+        assert_eq!(top.values[0].count, 2);
+    }
+
+    #[test]
+    fn captures_named_groups_as_fields() {
+        let captures = [
+            parse_capture(r"tenant=(?P<tenant>\w+)").unwrap(),
+            parse_capture(r"in (?P<ms>\d+)ms|(?P<tenant>shared)").unwrap(),
+        ];
+        assert!(parse_capture(r"tenant=(\w+)").is_err());
+        assert_eq!(capture_names(&captures), ["tenant", "ms"]);
+
+        let mut fields = BTreeMap::from([("tenant".to_string(), "json".to_string())]);
+        capture("query tenant=acme done in 42ms", &captures, &mut fields);
+        assert_eq!(fields["tenant"], "acme");
+        assert_eq!(fields["ms"], "42");
+
+        let mut fields = BTreeMap::new();
+        capture("no match here", &captures, &mut fields);
+        assert!(fields.is_empty());
+    }
+}
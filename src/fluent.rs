@@ -0,0 +1,236 @@
+use crate::follow::{CHANNEL_CAPACITY, LineSource};
+use crate::{LogLevel, parse_log_line};
+use chrono::DateTime;
+use flate2::read::GzDecoder;
+use rmpv::Value;
+use std::io::{self, BufReader, Read};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::thread;
+
+/// Ligne convertie au format `TIMESTAMP [LEVEL] message`, avec le tag Fluent
+/// en étiquette `fluent=TAG`.
+type FluentLine = (String, Option<String>);
+
+/// Clés usuelles du message et du niveau dans un enregistrement Fluent
+const MESSAGE_KEYS: [&str; 3] = ["log", "message", "msg"];
+const LEVEL_KEYS: [&str; 3] = ["level", "severity", "lvl"];
+
+fn field<'a>(record: &'a Value, keys: &[&str]) -> Option<&'a str> {
+    record
+        .as_map()?
+        .iter()
+        .find_map(|(k, v)| keys.contains(&k.as_str()?).then(|| v.as_str()).flatten())
+}
+
+/// Secondes depuis l'époque: entier, flottant ou EventTime (extension 0)
+fn event_seconds(time: &Value) -> Option<i64> {
+    match time {
+        Value::Ext(0, bytes) if bytes.len() == 8 => {
+            Some(u32::from_be_bytes(bytes[..4].try_into().ok()?) as i64)
+        }
+        Value::F32(_) | Value::F64(_) => time.as_f64().map(|t| t as i64),
+        _ => time.as_i64(),
+    }
+}
+
+/// Convertit un couple `(time, record)`. Une ligne déjà au format natif
+/// (fluent-bit qui suit un fichier loglyzer) est reprise telle quelle.
+pub fn to_line(tag: &str, time: &Value, record: &Value) -> Option<FluentLine> {
+    let message = field(record, &MESSAGE_KEYS)?.trim_end();
+    let origin = Some(format!("fluent={tag}"));
+    if parse_log_line(message).is_some() {
+        return Some((message.to_string(), origin));
+    }
+    let at = DateTime::from_timestamp(event_seconds(time)?, 0)?.naive_utc();
+    let level = field(record, &LEVEL_KEYS)
+        .and_then(LogLevel::from_str)
+        .unwrap_or(LogLevel::Info);
+    Some((
+        format!(
+            "{} [{}] {message}",
+            at.format("%Y-%m-%d %H:%M:%S"),
+            level.as_str()
+        ),
+        origin,
+    ))
+}
+
+/// Décode un message des trois modes du protocole Forward (Message, Forward,
+/// PackedForward, éventuellement gzip) et retourne ses lignes et son
+/// éventuel identifiant d'acquittement `chunk`.
+pub fn decode(message: &Value) -> io::Result<(Vec<FluentLine>, Option<Value>)> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "message Forward invalide");
+    let items = message.as_array().ok_or_else(invalid)?;
+    let tag = items.first().and_then(Value::as_str).ok_or_else(invalid)?;
+    let option = |index: usize, key: &str| {
+        items
+            .get(index)?
+            .as_map()?
+            .iter()
+            .find_map(|(k, v)| (k.as_str() == Some(key)).then(|| v.clone()))
+    };
+
+    let mut lines = Vec::new();
+    let chunk = match items.get(1).ok_or_else(invalid)? {
+        // Forward: [tag, [[time, record], ...], option]
+        Value::Array(entries) => {
+            for entry in entries {
+                if let Some([time, record]) = entry.as_array().map(Vec::as_slice) {
+                    lines.extend(to_line(tag, time, record));
+                }
+            }
+            option(2, "chunk")
+        }
+        // PackedForward: [tag, bin(time record time record...), option]
+        Value::Binary(_) | Value::String(_) => {
+            let packed = items[1].as_slice().unwrap_or_default();
+            let mut raw = Vec::new();
+            if option(2, "compressed").as_ref().and_then(Value::as_str) == Some("gzip") {
+                GzDecoder::new(packed).read_to_end(&mut raw)?;
+            } else {
+                raw.extend_from_slice(packed);
+            }
+            let mut cursor = raw.as_slice();
+            while !cursor.is_empty() {
+                let entry = rmpv::decode::read_value(&mut cursor).map_err(|_| invalid())?;
+                if let Some([time, record]) = entry.as_array().map(Vec::as_slice) {
+                    lines.extend(to_line(tag, time, record));
+                }
+            }
+            option(2, "chunk")
+        }
+        // Message: [tag, time, record, option]
+        time => {
+            lines.extend(to_line(tag, time, items.get(2).ok_or_else(invalid)?));
+            option(3, "chunk")
+        }
+    };
+    Ok((lines, chunk))
+}
+
+fn serve(stream: TcpStream, tx: &SyncSender<FluentLine>) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+    loop {
+        let message = match rmpv::decode::read_value(&mut reader) {
+            Ok(message) => message,
+            Err(rmpv::decode::Error::InvalidMarkerRead(err))
+                if err.kind() == io::ErrorKind::UnexpectedEof =>
+            {
+                return Ok(());
+            }
+            Err(err) => return Err(io::Error::new(io::ErrorKind::InvalidData, err)),
+        };
+        let (lines, chunk) = decode(&message)?;
+        for line in lines {
+            if tx.send(line).is_err() {
+                return Ok(());
+            }
+        }
+        if let Some(chunk) = chunk {
+            let ack = Value::Map(vec![(Value::from("ack"), chunk)]);
+            rmpv::encode::write_value(&mut writer, &ack)?;
+        }
+    }
+}
+
+/// Point de réception du protocole Forward de Fluentd/Fluent Bit (msgpack
+/// sur TCP, sans authentification ni TLS).
+pub struct FluentListener {
+    lines: Receiver<FluentLine>,
+}
+
+impl FluentListener {
+    pub fn bind(addr: &str) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let (tx, lines) = mpsc::sync_channel(CHANNEL_CAPACITY);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let tx = tx.clone();
+                thread::spawn(move || {
+                    if let Err(err) = serve(stream, &tx) {
+                        eprintln!("Connexion Forward interrompue: {err}");
+                    }
+                });
+            }
+        });
+        Ok(FluentListener { lines })
+    }
+}
+
+impl LineSource for FluentListener {
+    fn poll_lines(&mut self) -> io::Result<Vec<FluentLine>> {
+        Ok(self.lines.try_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(pairs: &[(&str, &str)]) -> Value {
+        Value::Map(
+            pairs
+                .iter()
+                .map(|(k, v)| (Value::from(*k), Value::from(*v)))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn decodes_message_and_forward_modes() {
+        let message = Value::Array(vec![
+            Value::from("app.web"),
+            Value::from(1705314645),
+            record(&[("log", "disk full"), ("level", "error")]),
+            record(&[("chunk", "abc")]),
+        ]);
+        let (lines, chunk) = decode(&message).unwrap();
+        assert_eq!(
+            lines,
+            vec![(
+                "2024-01-15 10:30:45 [ERROR] disk full".to_string(),
+                Some("fluent=app.web".to_string())
+            )]
+        );
+        assert_eq!(chunk, Some(Value::from("abc")));
+
+        let event_time = Value::Ext(0, [1705314645u32.to_be_bytes(), [0; 4]].concat());
+        let forward = Value::Array(vec![
+            Value::from("app.web"),
+            Value::Array(vec![
+                Value::Array(vec![event_time, record(&[("message", "ok")])]),
+                Value::Array(vec![
+                    Value::from(0),
+                    record(&[("log", "2024-01-15 10:31:00 [WARNING] slow\n")]),
+                ]),
+            ]),
+        ]);
+        let (lines, chunk) = decode(&forward).unwrap();
+        assert_eq!(lines[0].0, "2024-01-15 10:30:45 [INFO] ok");
+        assert_eq!(lines[1].0, "2024-01-15 10:31:00 [WARNING] slow");
+        assert_eq!(chunk, None);
+    }
+
+    #[test]
+    fn decodes_gzip_packed_forward() {
+        use flate2::{Compression, write::GzEncoder};
+        use std::io::Write;
+
+        let mut packed = Vec::new();
+        let entry = Value::Array(vec![Value::from(1705314645), record(&[("log", "a")])]);
+        rmpv::encode::write_value(&mut packed, &entry).unwrap();
+        rmpv::encode::write_value(&mut packed, &entry).unwrap();
+        let mut gz = GzEncoder::new(Vec::new(), Compression::default());
+        gz.write_all(&packed).unwrap();
+
+        let message = Value::Array(vec![
+            Value::from("t"),
+            Value::Binary(gz.finish().unwrap()),
+            record(&[("compressed", "gzip")]),
+        ]);
+        let (lines, _) = decode(&message).unwrap();
+        assert_eq!(lines.len(), 2);
+    }
+}
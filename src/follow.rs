@@ -0,0 +1,730 @@
+use crate::fields::Enricher;
+use crate::forward::{self, Forwarder};
+use crate::platform;
+use crate::rotate::RotatingWriter;
+use crate::rules::{Reclassifier, Tagger};
+use crate::state::Checkpoints;
+use crate::theme::Theme;
+use crate::throughput::Throttled;
+use crate::{
+    Cli, LineFormat, LogEntry, LogLevel, colorize_levels, filter_entries, highlight, search_regex,
+};
+use colored::Colorize;
+use prettytable::{Cell, Row, Table};
+use std::collections::{HashMap, VecDeque};
+use std::fs::OpenOptions;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Fenêtres glissantes affichées par `--top-view`, à la manière de `top`
+pub const WINDOWS: [(&str, Duration); 3] = [
+    ("1m", Duration::from_secs(60)),
+    ("5m", Duration::from_secs(5 * 60)),
+    ("15m", Duration::from_secs(15 * 60)),
+];
+
+/// Capacité des files entre les fils de réception réseau et la boucle de
+/// suivi: une fois pleine, les producteurs attendent (la connexion n'est plus
+/// lue) au lieu d'accumuler en mémoire pendant une analyse ou une sortie lente.
+pub const CHANNEL_CAPACITY: usize = 10_000;
+
+/// Source de lignes suivie en continu; chaque ligne peut porter l'origine
+/// (pod, conteneur...) ajoutée en étiquette aux entrées qu'elle produit.
+pub trait LineSource {
+    fn poll_lines(&mut self) -> io::Result<Vec<(String, Option<String>)>>;
+
+    /// Position atteinte après les lignes déjà retournées, si la source
+    /// sait la rejouer (voir `--state`)
+    fn checkpoint(&self) -> Option<String> {
+        None
+    }
+
+    /// Reprend la lecture à une position enregistrée par `checkpoint`
+    fn resume(&mut self, _cursor: &str) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Lit les lignes ajoutées à un fichier depuis le dernier appel, comme `tail -f`.
+pub struct Follower {
+    path: PathBuf,
+    offset: u64,
+    /// Fin de ligne incomplète, en octets: un caractère UTF-8 peut être
+    /// coupé entre deux lectures
+    pending: Vec<u8>,
+}
+
+impl Follower {
+    /// Se positionne en fin de fichier: seules les nouvelles lignes seront lues.
+    pub fn at_end(path: &Path) -> io::Result<Self> {
+        let offset = platform::open_shared(path)?.metadata()?.len();
+        Ok(Follower {
+            path: path.to_path_buf(),
+            offset,
+            pending: Vec::new(),
+        })
+    }
+
+    /// Retourne les lignes complètes apparues depuis le dernier appel.
+    /// Un fichier tronqué (rotation) est relu depuis le début; un fichier
+    /// momentanément verrouillé par son écrivain (Windows) sera relu au
+    /// prochain appel.
+    pub fn poll(&mut self) -> io::Result<Vec<String>> {
+        let mut file = match platform::open_shared(&self.path) {
+            Err(err) if platform::is_locked(&err) => return Ok(Vec::new()),
+            file => file?,
+        };
+        let len = file.metadata()?.len();
+        if len < self.offset {
+            self.offset = 0;
+            self.pending.clear();
+        }
+        if len == self.offset {
+            return Ok(Vec::new());
+        }
+
+        file.seek(SeekFrom::Start(self.offset))?;
+        let before = self.pending.len();
+        file.take(len - self.offset)
+            .read_to_end(&mut self.pending)?;
+        self.offset += (self.pending.len() - before) as u64;
+
+        let mut lines = Vec::new();
+        while let Some(pos) = memchr::memchr(b'\n', &self.pending) {
+            let line: Vec<u8> = self.pending.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&line);
+            lines.push(line.trim_end_matches(['\n', '\r']).to_string());
+        }
+        Ok(lines)
+    }
+}
+
+impl LineSource for Follower {
+    fn poll_lines(&mut self) -> io::Result<Vec<(String, Option<String>)>> {
+        Ok(self.poll()?.into_iter().map(|line| (line, None)).collect())
+    }
+
+    /// Décalage de la fin de la dernière ligne complète
+    fn checkpoint(&self) -> Option<String> {
+        Some((self.offset - self.pending.len() as u64).to_string())
+    }
+
+    fn resume(&mut self, cursor: &str) -> io::Result<()> {
+        self.offset = cursor.parse().map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Position invalide dans --state: {cursor}"),
+            )
+        })?;
+        self.pending.clear();
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct WindowCounts {
+    pub total: usize,
+    pub by_level: HashMap<&'static str, usize>,
+}
+
+impl WindowCounts {
+    pub fn errors(&self) -> usize {
+        self.by_level
+            .get(LogLevel::Error.as_str())
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Moyenne par minute sur la fenêtre, comme la charge de `uptime`
+    pub fn per_minute(count: usize, span: Duration) -> f64 {
+        count as f64 / (span.as_secs_f64() / 60.0)
+    }
+
+    pub fn error_rate(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.errors() as f64 / self.total as f64 * 100.0
+        }
+    }
+}
+
+/// Entrées reçues pendant la plus grande fenêtre, horodatées à leur arrivée.
+#[derive(Default)]
+pub struct RollingWindow {
+    events: VecDeque<(Instant, LogLevel, String)>,
+}
+
+impl RollingWindow {
+    pub fn push(&mut self, at: Instant, entry: &LogEntry) {
+        self.events
+            .push_back((at, entry.level.clone(), entry.message.clone()));
+    }
+
+    pub fn prune(&mut self, now: Instant) {
+        let horizon = WINDOWS[WINDOWS.len() - 1].1;
+        while let Some((at, _, _)) = self.events.front() {
+            if now.duration_since(*at) > horizon {
+                self.events.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn recent(
+        &self,
+        window: Duration,
+        now: Instant,
+    ) -> impl Iterator<Item = &(Instant, LogLevel, String)> {
+        self.events
+            .iter()
+            .rev()
+            .take_while(move |(at, _, _)| now.duration_since(*at) <= window)
+    }
+
+    pub fn counts(&self, window: Duration, now: Instant) -> WindowCounts {
+        let mut counts = WindowCounts::default();
+        for (_, level, _) in self.recent(window, now) {
+            counts.total += 1;
+            *counts.by_level.entry(level.as_str()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    pub fn top_errors(&self, window: Duration, now: Instant, n: usize) -> Vec<(String, usize)> {
+        let mut freq: HashMap<&str, usize> = HashMap::new();
+        for (_, level, message) in self.recent(window, now) {
+            if *level == LogLevel::Error {
+                *freq.entry(message).or_insert(0) += 1;
+            }
+        }
+        let mut top: Vec<_> = freq.into_iter().map(|(m, c)| (m.to_string(), c)).collect();
+        top.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        top.truncate(n);
+        top
+    }
+}
+
+/// Tampon circulaire des dernières entrées retenues, vidé dans le rapport
+/// quand une alerte se déclenche pour donner le contexte immédiat.
+pub struct RecentEntries {
+    lines: VecDeque<String>,
+    capacity: usize,
+}
+
+impl RecentEntries {
+    pub fn new(capacity: usize) -> Self {
+        RecentEntries {
+            lines: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, line: String) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.lines.len() == self.capacity {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &String> {
+        self.lines.iter()
+    }
+}
+
+fn format_entry(entry: &LogEntry) -> String {
+    format!(
+        "{} [{}] {}",
+        entry.timestamp,
+        entry.level.as_str(),
+        entry.message
+    )
+}
+
+fn render_alert(errors: usize, threshold: usize, recent: &RecentEntries) -> String {
+    use std::fmt::Write;
+
+    let mut output = String::new();
+    writeln!(
+        output,
+        "\n!!! ALERTE: {errors} erreurs sur la dernière minute (seuil {threshold})"
+    )
+    .unwrap();
+    writeln!(output, "Dernières entrées:").unwrap();
+    for line in recent.iter() {
+        writeln!(output, "  {line}").unwrap();
+    }
+    output
+}
+
+/// Ligne de charge `1m/5m/15m` des entrées et des erreurs par minute
+fn render_load(window: &RollingWindow, now: Instant) -> String {
+    let rates: Vec<_> = WINDOWS
+        .iter()
+        .map(|(_, span)| {
+            let counts = window.counts(*span, now);
+            (
+                WindowCounts::per_minute(counts.total, *span),
+                WindowCounts::per_minute(counts.errors(), *span),
+            )
+        })
+        .collect();
+    format!(
+        "load (entries/min, 1m 5m 15m): {:.2} {:.2} {:.2}  errors/min: {:.2} {:.2} {:.2}",
+        rates[0].0, rates[1].0, rates[2].0, rates[0].1, rates[1].1, rates[2].1
+    )
+}
+
+fn render_top_view(
+    label: &str,
+    window: &RollingWindow,
+    now: Instant,
+    top_n: usize,
+    theme: &Theme,
+) -> String {
+    use std::fmt::Write;
+
+    let mut output = String::new();
+    writeln!(output, " loglyzer --top-view  {label}").unwrap();
+    writeln!(output, " {}\n", render_load(window, now)).unwrap();
+
+    let mut table = Table::new();
+    let mut header = vec![
+        Cell::new("Window"),
+        Cell::new("Total"),
+        Cell::new("Error %"),
+        Cell::new("Per min"),
+    ];
+    header.extend(
+        ["ERROR", "WARNING", "INFO", "DEBUG"]
+            .iter()
+            .map(|l| Cell::new(l)),
+    );
+    table.add_row(Row::new(header));
+    for (label, span) in WINDOWS {
+        let counts = window.counts(span, now);
+        let mut row = vec![
+            Cell::new(label),
+            Cell::new(&counts.total.to_string()),
+            Cell::new(&format!("{:.1}%", counts.error_rate())),
+            Cell::new(&format!(
+                "{:.2}",
+                WindowCounts::per_minute(counts.total, span)
+            )),
+        ];
+        row.extend(
+            ["ERROR", "WARNING", "INFO", "DEBUG"]
+                .iter()
+                .map(|l| Cell::new(&counts.by_level.get(l).copied().unwrap_or(0).to_string())),
+        );
+        table.add_row(Row::new(row));
+    }
+    writeln!(output, "{}", colorize_levels(&table.to_string(), theme)).unwrap();
+
+    let (label, span) = WINDOWS[WINDOWS.len() - 1];
+    let top = window.top_errors(span, now, top_n);
+    writeln!(output, "\nTop recent errors (last {label}):").unwrap();
+    if top.is_empty() {
+        writeln!(output, "(aucune)").unwrap();
+    } else {
+        let mut error_table = Table::new();
+        error_table.add_row(Row::new(vec![
+            Cell::new("Error Message"),
+            Cell::new("Occurrences"),
+        ]));
+        for (message, count) in top {
+            error_table.add_row(Row::new(vec![
+                Cell::new(&message),
+                Cell::new(&count.to_string()),
+            ]));
+        }
+        writeln!(output, "{error_table}").unwrap();
+    }
+
+    output
+}
+
+/// Suit le fichier jusqu'à interruption (Ctrl-C), en affichant soit les
+/// entrées filtrées au fil de l'eau (ou en les écrivant dans `--output`),
+/// soit la vue `--top-view`.
+pub fn run(cli: &Cli, rules: &LiveRules, top_n: usize, theme: &Theme) -> io::Result<()> {
+    let mut follower = Follower::at_end(cli.input())?;
+    let label = cli.input().display().to_string();
+    run_source(cli, &mut follower, &label, rules, top_n, theme)
+}
+
+/// Traitements appliqués à chaque ligne suivie, comme en analyse complète
+pub struct LiveRules<'a> {
+    pub reclassifier: &'a Reclassifier,
+    pub tagger: &'a Tagger,
+    pub enricher: &'a Enricher<'a>,
+}
+
+impl LiveRules<'_> {
+    /// Entrée d'une ligne suivie, reclassée, étiquetée et enrichie, plus
+    /// l'étiquette de son origine; `None` si la ligne est illisible.
+    fn entry(&self, line: &str, origin: Option<String>, format: &LineFormat) -> Option<LogEntry> {
+        let mut entry = format.parse(line)?;
+        if let Some(level) = self.reclassifier.level(&entry.level, &entry.message) {
+            entry.level = level;
+        }
+        entry.tags.extend(self.tagger.tags(&entry.message));
+        entry.tags.extend(origin);
+        self.enricher.enrich(&mut entry);
+        Some(entry)
+    }
+}
+
+/// Boucle de suivi commune à toutes les sources; `label` titre la vue `--top-view`.
+pub fn run_source(
+    cli: &Cli,
+    source: &mut dyn LineSource,
+    label: &str,
+    rules: &LiveRules,
+    top_n: usize,
+    theme: &Theme,
+) -> io::Result<()> {
+    let forwarder = match &cli.forward {
+        Some(url) => Some(forward::connect(url, cli.forward_max)?),
+        None => None,
+    };
+    follow_source(cli, source, label, rules, forwarder, top_n, theme)
+}
+
+/// Boucle de `run_source`, la destination de `--forward` déjà ouverte
+fn follow_source(
+    cli: &Cli,
+    source: &mut dyn LineSource,
+    label: &str,
+    rules: &LiveRules,
+    mut forwarder: Option<Box<dyn Forwarder>>,
+    top_n: usize,
+    theme: &Theme,
+) -> io::Result<()> {
+    let mut window = RollingWindow::default();
+    let search_lower = cli.search.as_ref().map(|s| s.to_lowercase());
+    let search_re = cli.search.as_deref().map(search_regex);
+    let refresh = Duration::from_secs(cli.refresh.max(1));
+    let mut recent = RecentEntries::new(cli.recent);
+    let mut last_alert: Option<String> = None;
+    let mut alert_active = false;
+    let mut output = match &cli.output {
+        Some(path) => Some(RotatingWriter::open(
+            path,
+            cli.output_rotate,
+            cli.output_keep,
+        )?),
+        None => None,
+    };
+    let mut checkpoints = match &cli.state {
+        Some(path) => {
+            let checkpoints = Checkpoints::load(path)?;
+            if let Some(cursor) = checkpoints.get(label) {
+                source.resume(cursor)?;
+            }
+            Some(checkpoints)
+        }
+        None => None,
+    };
+
+    let mut throttled;
+    let source: &mut dyn LineSource = match cli.max_throughput {
+        Some(rate) => {
+            throttled = Throttled::new(source, rate);
+            &mut throttled
+        }
+        None => source,
+    };
+
+    let format = cli.line_format(None);
+    loop {
+        let now = Instant::now();
+        let entries: Vec<LogEntry> = source
+            .poll_lines()?
+            .into_iter()
+            .filter_map(|(line, origin)| rules.entry(&line, origin, &format))
+            .collect();
+        let entries = filter_entries(
+            entries,
+            cli.errors_only,
+            search_lower.as_deref(),
+            cli.since,
+            cli.until,
+            &cli.exclude_window,
+        );
+
+        for entry in &entries {
+            window.push(now, entry);
+            recent.push(format_entry(entry));
+            if let Some(forwarder) = forwarder.as_mut() {
+                forwarder.send(entry)?;
+            }
+            if let Some(out) = output.as_mut() {
+                out.write_line(&format_entry(entry))?;
+            } else if !cli.top_view {
+                let line = colorize_levels(&format_entry(entry), theme);
+                match &search_re {
+                    Some(re) => println!(
+                        "{}",
+                        highlight(&line, re, |m| m.black().on_yellow().to_string())
+                    ),
+                    None => println!("{line}"),
+                }
+            }
+        }
+        if let Some(forwarder) = forwarder.as_mut() {
+            forwarder.flush()?;
+        }
+        // Après le flush seulement: une entrée n'est acquise qu'une fois réémise
+        if let Some(checkpoints) = checkpoints.as_mut()
+            && let Some(cursor) = source.checkpoint()
+        {
+            checkpoints.commit(label, cursor)?;
+        }
+        window.prune(now);
+
+        if let Some(threshold) = cli.alert_threshold {
+            let errors = window.counts(WINDOWS[0].1, now).errors();
+            if errors >= threshold && !alert_active {
+                let alert = render_alert(errors, threshold, &recent);
+                match &cli.recent_out {
+                    Some(path) => {
+                        let mut out = OpenOptions::new().create(true).append(true).open(path)?;
+                        out.write_all(alert.as_bytes())?;
+                    }
+                    None if !cli.top_view => print!("{alert}"),
+                    None => {}
+                }
+                last_alert = Some(alert);
+            }
+            // Réarmement une fois le taux repassé sous le seuil
+            alert_active = errors >= threshold;
+        }
+
+        if cli.verbose && !cli.top_view {
+            eprintln!("{}", render_load(&window, now));
+        }
+
+        if cli.top_view {
+            print!(
+                "\x1b[2J\x1b[H{}",
+                render_top_view(label, &window, now, top_n, theme)
+            );
+            if let Some(alert) = &last_alert {
+                print!("{alert}");
+            }
+        }
+
+        thread::sleep(refresh);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_log_line;
+    use std::cell::RefCell;
+    use std::io::Write;
+    use std::rc::Rc;
+
+    #[test]
+    fn follower_reads_only_appended_complete_lines() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "2024-01-15 10:30:45 [INFO] before").unwrap();
+        let mut follower = Follower::at_end(file.path()).unwrap();
+        assert!(follower.poll().unwrap().is_empty());
+
+        write!(
+            file,
+            "2024-01-15 10:30:46 [ERROR] after\n2024-01-15 10:30:47 [INFO] par"
+        )
+        .unwrap();
+        assert_eq!(
+            follower.poll().unwrap(),
+            vec!["2024-01-15 10:30:46 [ERROR] after"]
+        );
+        writeln!(file, "tial").unwrap();
+        assert_eq!(
+            follower.poll().unwrap(),
+            vec!["2024-01-15 10:30:47 [INFO] partial"]
+        );
+
+        // Fin de ligne Windows et caractère UTF-8 coupé entre deux lectures
+        let line = "2024-01-15 10:30:48 [WARNING] délai dépassé\r\n".as_bytes();
+        file.write_all(&line[..32]).unwrap();
+        assert!(follower.poll().unwrap().is_empty());
+        file.write_all(&line[32..]).unwrap();
+        assert_eq!(
+            follower.poll().unwrap(),
+            vec!["2024-01-15 10:30:48 [WARNING] délai dépassé"]
+        );
+    }
+
+    #[test]
+    fn follower_resumes_from_checkpoint() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(
+            file,
+            "2024-01-15 10:30:45 [INFO] sent\n2024-01-15 10:30:46 [INFO] par"
+        )
+        .unwrap();
+        let mut follower = Follower::at_end(file.path()).unwrap();
+        follower.resume("0").unwrap();
+        assert_eq!(follower.poll().unwrap().len(), 1);
+        let cursor = follower.checkpoint().unwrap();
+        assert_eq!(cursor, "32");
+
+        writeln!(file, "tial").unwrap();
+        let mut restarted = Follower::at_end(file.path()).unwrap();
+        restarted.resume(&cursor).unwrap();
+        assert_eq!(
+            restarted.poll().unwrap(),
+            vec!["2024-01-15 10:30:46 [INFO] partial"]
+        );
+        assert!(restarted.resume("abc").is_err());
+    }
+
+    #[test]
+    fn recent_entries_keeps_last_n() {
+        let mut recent = RecentEntries::new(2);
+        for line in ["a", "b", "c"] {
+            recent.push(line.to_string());
+        }
+        assert_eq!(recent.iter().collect::<Vec<_>>(), vec!["b", "c"]);
+    }
+
+    #[test]
+    fn rolling_window_counts_per_window() {
+        let start = Instant::now();
+        let mut window = RollingWindow::default();
+        let old = parse_log_line("2024-01-15 10:30:45 [ERROR] API timeout").unwrap();
+        let new = parse_log_line("2024-01-15 10:40:45 [INFO] OK").unwrap();
+        window.push(start, &old);
+        let now = start + Duration::from_secs(4 * 60);
+        window.push(now, &new);
+
+        assert_eq!(window.counts(WINDOWS[0].1, now).total, 1);
+        let five = window.counts(WINDOWS[1].1, now);
+        assert_eq!(five.total, 2);
+        assert_eq!(five.error_rate(), 50.0);
+        assert_eq!(WindowCounts::per_minute(five.total, WINDOWS[1].1), 0.4);
+        assert!(render_load(&window, now).ends_with("errors/min: 0.00 0.20 0.07"));
+        assert_eq!(
+            window.top_errors(WINDOWS[2].1, now, 3),
+            vec![("API timeout".to_string(), 1)]
+        );
+
+        window.prune(start + Duration::from_secs(16 * 60));
+        assert_eq!(window.counts(WINDOWS[2].1, now).total, 1);
+    }
+
+    #[test]
+    fn live_entries_are_reclassified_tagged_and_enriched() {
+        let config: crate::rules::Config = toml::from_str(
+            r#"
+            [[reclassify]]
+            from = "WARNING"
+            pattern = 'disk 9\d%'
+            to = "ERROR"
+
+            [[tag]]
+            name = "storage"
+            pattern = "disk"
+            "#,
+        )
+        .unwrap();
+        let reclassifier = Reclassifier::from_config(&config).unwrap();
+        let tagger = Tagger::from_config(&config).unwrap();
+        let captures = [crate::fields::parse_capture(r"on (?P<volume>/\w+)").unwrap()];
+        let enricher = Enricher {
+            captures: &captures,
+            extract: true,
+            lookups: Vec::new(),
+        };
+        let rules = LiveRules {
+            reclassifier: &reclassifier,
+            tagger: &tagger,
+            enricher: &enricher,
+        };
+        let entry = rules
+            .entry(
+                "2024-01-15 10:30:45 [WARNING] disk 95% full on /data host=db1",
+                Some("pod=web".to_string()),
+                &LineFormat::Text,
+            )
+            .unwrap();
+        assert_eq!(entry.level, LogLevel::Error);
+        assert_eq!(entry.tags, vec!["storage", "pod=web"]);
+        assert_eq!(entry.fields["volume"], "/data");
+        assert_eq!(entry.fields["host"], "db1");
+        let unreadable = rules.entry("not a log line", None, &LineFormat::Text);
+        assert!(unreadable.is_none());
+    }
+
+    /// Rend un seul lot de lignes, puis une erreur qui termine la boucle de suivi
+    struct OneBatch(Option<Vec<String>>);
+
+    impl LineSource for OneBatch {
+        fn poll_lines(&mut self) -> io::Result<Vec<(String, Option<String>)>> {
+            match self.0.take() {
+                Some(lines) => Ok(lines.into_iter().map(|l| (l, None)).collect()),
+                None => Err(io::Error::other("fin du lot")),
+            }
+        }
+    }
+
+    struct Collect(Rc<RefCell<Vec<LogEntry>>>);
+
+    impl Forwarder for Collect {
+        fn send(&mut self, entry: &LogEntry) -> io::Result<()> {
+            self.0.borrow_mut().push(entry.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn forwarded_live_entries_carry_their_fields() {
+        let cli = crate::parse_cli([
+            "loglyzer",
+            "--follow",
+            "--forward",
+            "syslog://127.0.0.1:514",
+            "--capture",
+            r"tenant (?P<tenant>\w+)",
+            "app.log",
+        ]);
+        let enricher = cli.enricher().unwrap();
+        let rules = LiveRules {
+            reclassifier: &Reclassifier::default(),
+            tagger: &Tagger::default(),
+            enricher: &enricher,
+        };
+        let mut source = OneBatch(Some(vec![
+            "2024-01-15 10:30:45 [ERROR] quota exceeded for tenant acme region=eu".to_string(),
+        ]));
+        let sent = Rc::new(RefCell::new(Vec::new()));
+        let forwarder: Box<dyn Forwarder> = Box::new(Collect(sent.clone()));
+        let theme = Theme::default();
+        let end = follow_source(
+            &cli,
+            &mut source,
+            "test",
+            &rules,
+            Some(forwarder),
+            5,
+            &theme,
+        );
+        assert_eq!(end.unwrap_err().to_string(), "fin du lot");
+
+        let sent = sent.borrow();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].fields["tenant"], "acme");
+        assert_eq!(sent[0].fields["region"], "eu");
+    }
+}
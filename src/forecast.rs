@@ -0,0 +1,116 @@
+use crate::{LogEntry, LogLevel, truncate_to_hour};
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+const ALPHA: f64 = 0.5;
+const BETA: f64 = 0.3;
+/// Nombre minimal d'heures d'historique pour tenter une projection
+const MIN_HISTORY: usize = 3;
+
+/// Projection des erreurs par lissage exponentiel double (Holt), à prendre
+/// comme un ordre de grandeur: aucune saisonnalité n'est modélisée.
+#[derive(Debug, Serialize)]
+pub struct ErrorForecast {
+    pub method: &'static str,
+    pub history_hours: usize,
+    pub next_hour: f64,
+    pub next_hour_low: f64,
+    pub next_hour_high: f64,
+    pub next_day: f64,
+    pub caveat: String,
+}
+
+/// Série horaire chronologique des erreurs, heures sans erreur comprises
+fn hourly_errors(entries: &[LogEntry]) -> Vec<f64> {
+    let mut buckets: BTreeMap<NaiveDateTime, usize> = BTreeMap::new();
+    for entry in entries {
+        let bucket = buckets.entry(truncate_to_hour(entry.datetime)).or_insert(0);
+        if entry.level == LogLevel::Error {
+            *bucket += 1;
+        }
+    }
+    let (Some(first), Some(last)) = (buckets.keys().next(), buckets.keys().next_back()) else {
+        return Vec::new();
+    };
+    let hours = (*last - *first).num_hours() as usize + 1;
+    (0..hours)
+        .map(|h| {
+            let at = *first + chrono::Duration::hours(h as i64);
+            buckets.get(&at).copied().unwrap_or(0) as f64
+        })
+        .collect()
+}
+
+pub fn forecast_errors(entries: &[LogEntry]) -> Option<ErrorForecast> {
+    let series = hourly_errors(entries);
+    if series.len() < MIN_HISTORY {
+        return None;
+    }
+
+    let mut level = series[0];
+    let mut trend = series[1] - series[0];
+    let mut squared_errors = 0.0;
+    for &value in &series[1..] {
+        let predicted = level + trend;
+        squared_errors += (value - predicted).powi(2);
+        let previous_level = level;
+        level = ALPHA * value + (1.0 - ALPHA) * (level + trend);
+        trend = BETA * (level - previous_level) + (1.0 - BETA) * trend;
+    }
+    let rmse = (squared_errors / (series.len() - 1) as f64).sqrt();
+
+    let next_hour = (level + trend).max(0.0);
+    let next_day = (1..=24).map(|k| (level + k as f64 * trend).max(0.0)).sum();
+
+    Some(ErrorForecast {
+        method: "holt",
+        history_hours: series.len(),
+        next_hour,
+        next_hour_low: (next_hour - 1.96 * rmse).max(0.0),
+        next_hour_high: next_hour + 1.96 * rmse,
+        next_day,
+        caveat: format!(
+            "Projection indicative sur {} h d'historique, sans saisonnalité; \
+             l'intervalle (±1,96 × RMSE) suppose des écarts stables.",
+            series.len()
+        ),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_log_line;
+
+    fn entries(lines: &[&str]) -> Vec<LogEntry> {
+        lines.iter().map(|l| parse_log_line(l).unwrap()).collect()
+    }
+
+    #[test]
+    fn hourly_series_fills_quiet_hours() {
+        let e = entries(&[
+            "2024-01-15 10:10:00 [ERROR] a",
+            "2024-01-15 10:20:00 [ERROR] b",
+            "2024-01-15 12:00:00 [INFO] c",
+        ]);
+        assert_eq!(hourly_errors(&e), vec![2.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn forecast_follows_growing_trend() {
+        let e = entries(&[
+            "2024-01-15 10:00:00 [ERROR] a",
+            "2024-01-15 11:00:00 [ERROR] a",
+            "2024-01-15 11:00:01 [ERROR] a",
+            "2024-01-15 12:00:00 [ERROR] a",
+            "2024-01-15 12:00:01 [ERROR] a",
+            "2024-01-15 12:00:02 [ERROR] a",
+        ]);
+        let f = forecast_errors(&e).unwrap();
+        assert_eq!(f.history_hours, 3);
+        assert!((f.next_hour - 4.0).abs() < 1e-9);
+        assert!(f.next_day > 24.0 * 3.0);
+        assert!(forecast_errors(&e[..3]).is_none());
+    }
+}
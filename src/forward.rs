@@ -0,0 +1,194 @@
+use crate::syslog_out::SyslogForwarder;
+use crate::{LogEntry, noise, parse_period};
+use chrono::NaiveDateTime;
+use std::collections::HashMap;
+use std::io;
+use std::time::Duration;
+
+/// Destination vers laquelle `--forward` réémet les entrées filtrées
+pub trait Forwarder {
+    fn send(&mut self, entry: &LogEntry) -> io::Result<()>;
+
+    /// Vide les entrées mises en tampon, à la fin d'un lot
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Nombre d'entrées écartées par la limite de débit
+    fn suppressed(&self) -> usize {
+        0
+    }
+}
+
+/// Au plus `max` entrées par empreinte et par fenêtre `per`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub max: usize,
+    pub per: Duration,
+}
+
+/// `10/min`, `100/h` ou `5/30s`
+pub fn parse_rate_limit(input: &str) -> Result<RateLimit, String> {
+    let (max, per) = input
+        .split_once('/')
+        .ok_or_else(|| format!("Limite invalide: {input} (ex: 10/min)"))?;
+    let max = max
+        .trim()
+        .parse()
+        .map_err(|_| format!("Limite invalide: {input} (ex: 10/min)"))?;
+    Ok(RateLimit {
+        max,
+        per: parse_period(per)?,
+    })
+}
+
+/// Applique une limite de débit par empreinte (niveau et gabarit du message),
+/// mesurée sur l'horodatage des entrées, pour qu'une boucle de crash
+/// n'inonde pas la destination.
+struct RateLimited {
+    inner: Box<dyn Forwarder>,
+    limit: RateLimit,
+    windows: HashMap<String, (NaiveDateTime, usize)>,
+    suppressed: usize,
+}
+
+impl RateLimited {
+    fn allow(&mut self, entry: &LogEntry) -> bool {
+        let fingerprint = format!(
+            "{} {}",
+            entry.level.as_str(),
+            noise::normalize(&entry.message)
+        );
+        let per = chrono::Duration::from_std(self.limit.per).unwrap_or(chrono::Duration::MAX);
+        let window = self
+            .windows
+            .entry(fingerprint)
+            .or_insert((entry.datetime, 0));
+        if entry.datetime - window.0 >= per {
+            *window = (entry.datetime, 0);
+        }
+        window.1 += 1;
+        window.1 <= self.limit.max
+    }
+}
+
+impl Forwarder for RateLimited {
+    fn send(&mut self, entry: &LogEntry) -> io::Result<()> {
+        if self.allow(entry) {
+            self.inner.send(entry)
+        } else {
+            self.suppressed += 1;
+            Ok(())
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    fn suppressed(&self) -> usize {
+        self.suppressed
+    }
+}
+
+/// Ouvre la destination selon le schéma de l'URL.
+pub fn connect(url: &str, limit: Option<RateLimit>) -> io::Result<Box<dyn Forwarder>> {
+    let inner: Box<dyn Forwarder> = match url.split_once("://").map(|(scheme, _)| scheme) {
+        #[cfg(feature = "kafka")]
+        Some("kafka") => Box::new(crate::kafka_out::KafkaForwarder::connect(url)?),
+        #[cfg(not(feature = "kafka"))]
+        Some("kafka") => {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Sortie Kafka non disponible: recompiler avec --features kafka",
+            ));
+        }
+        #[cfg(feature = "opensearch")]
+        Some("opensearch" | "opensearch+https") => {
+            Box::new(crate::opensearch_out::OpenSearchForwarder::connect(url)?)
+        }
+        #[cfg(not(feature = "opensearch"))]
+        Some("opensearch" | "opensearch+https") => {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Sortie OpenSearch non disponible: recompiler avec --features opensearch",
+            ));
+        }
+        #[cfg(feature = "clickhouse")]
+        Some("clickhouse" | "clickhouse+https") => {
+            Box::new(crate::clickhouse_out::ClickHouseForwarder::connect(url)?)
+        }
+        #[cfg(not(feature = "clickhouse"))]
+        Some("clickhouse" | "clickhouse+https") => {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Sortie ClickHouse non disponible: recompiler avec --features clickhouse",
+            ));
+        }
+        _ => Box::new(SyslogForwarder::connect(url)?),
+    };
+    Ok(match limit {
+        Some(limit) => Box::new(RateLimited {
+            inner,
+            limit,
+            windows: HashMap::new(),
+            suppressed: 0,
+        }),
+        None => inner,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_log_line;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    struct Collect(Rc<RefCell<Vec<String>>>);
+
+    impl Forwarder for Collect {
+        fn send(&mut self, entry: &LogEntry) -> io::Result<()> {
+            self.0.borrow_mut().push(entry.message.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn rate_limits_each_fingerprint_per_window() {
+        assert_eq!(
+            parse_rate_limit("10/min"),
+            Ok(RateLimit {
+                max: 10,
+                per: Duration::from_secs(60)
+            })
+        );
+        assert_eq!(
+            parse_rate_limit("2/30s").unwrap().per,
+            Duration::from_secs(30)
+        );
+        assert!(parse_rate_limit("10").is_err());
+
+        let sent = Rc::new(RefCell::new(Vec::new()));
+        let mut forwarder = RateLimited {
+            inner: Box::new(Collect(sent.clone())),
+            limit: parse_rate_limit("2/min").unwrap(),
+            windows: HashMap::new(),
+            suppressed: 0,
+        };
+        for line in [
+            "2024-01-15 10:00:00 [ERROR] crash pid=1",
+            "2024-01-15 10:00:10 [ERROR] crash pid=2",
+            "2024-01-15 10:00:20 [ERROR] crash pid=3",
+            "2024-01-15 10:00:30 [INFO] started",
+            "2024-01-15 10:01:00 [ERROR] crash pid=4",
+        ] {
+            forwarder.send(&parse_log_line(line).unwrap()).unwrap();
+        }
+        assert_eq!(
+            *sent.borrow(),
+            vec!["crash pid=1", "crash pid=2", "started", "crash pid=4"]
+        );
+        assert_eq!(forwarder.suppressed(), 1);
+    }
+}
@@ -0,0 +1,259 @@
+use crate::locale::Locale;
+use crate::rules::Categorizer;
+use crate::{LogEntry, UNTAGGED, extract_hour};
+use prettytable::{Cell, Row, Table};
+use serde::{Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap};
+
+/// Groupe des entrées sans le champ demandé
+pub const MISSING: &str = "-";
+
+/// Dimension de `--group-by`: attribut intégré ou champ `clé=valeur` extrait
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Dimension {
+    Level,
+    Hour,
+    Tag,
+    Category,
+    Field(String),
+}
+
+impl Dimension {
+    pub fn name(&self) -> &str {
+        match self {
+            Dimension::Level => "level",
+            Dimension::Hour => "hour",
+            Dimension::Tag => "tag",
+            Dimension::Category => "category",
+            Dimension::Field(name) => name,
+        }
+    }
+
+    /// En-tête de colonne: capitalisé pour les attributs intégrés, nom du champ sinon
+    fn label(&self) -> String {
+        match self {
+            Dimension::Field(name) => name.clone(),
+            builtin => {
+                let name = builtin.name();
+                name[..1].to_uppercase() + &name[1..]
+            }
+        }
+    }
+
+    pub fn is_field(&self) -> bool {
+        matches!(self, Dimension::Field(_))
+    }
+
+    /// Clés de l'entrée sur cette dimension: une seule, sauf pour les tags
+    /// (l'entrée compte alors dans chacun de ses tags)
+    fn keys(&self, entry: &LogEntry, categorizer: &Categorizer) -> Vec<String> {
+        match self {
+            Dimension::Level => vec![entry.level.as_str().to_string()],
+            Dimension::Hour => {
+                vec![extract_hour(&entry.timestamp).unwrap_or_else(|| MISSING.to_string())]
+            }
+            Dimension::Tag if entry.tags.is_empty() => vec![UNTAGGED.to_string()],
+            Dimension::Tag => entry.tags.clone(),
+            Dimension::Category => vec![categorizer.categorize(&entry.message).to_string()],
+            Dimension::Field(name) => {
+                vec![
+                    entry
+                        .fields
+                        .get(name)
+                        .cloned()
+                        .unwrap_or_else(|| MISSING.to_string()),
+                ]
+            }
+        }
+    }
+}
+
+impl Serialize for Dimension {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+/// `level`, `hour`, `tag`, `category` ou le nom d'un champ extrait
+pub fn parse_dimension(input: &str) -> Result<Dimension, String> {
+    let name = input.trim();
+    Ok(match name.to_lowercase().as_str() {
+        "level" => Dimension::Level,
+        "hour" => Dimension::Hour,
+        "tag" => Dimension::Tag,
+        "category" => Dimension::Category,
+        _ if !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')) =>
+        {
+            Dimension::Field(name.to_string())
+        }
+        _ => {
+            return Err(format!(
+                "Dimension invalide: {input} (level, hour, tag, category ou nom de champ)"
+            ));
+        }
+    })
+}
+
+/// Comptes d'un groupe, puis de ses sous-groupes sur la dimension suivante
+#[derive(Debug, Default, Serialize)]
+pub struct GroupStats {
+    pub total: usize,
+    pub bytes: u64,
+    pub by_level: HashMap<String, usize>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub groups: BTreeMap<String, GroupStats>,
+}
+
+/// Ajoute l'entrée à son groupe sur chaque dimension, de la première à la dernière
+pub fn add(
+    groups: &mut BTreeMap<String, GroupStats>,
+    entry: &LogEntry,
+    dimensions: &[Dimension],
+    categorizer: &Categorizer,
+) {
+    let Some((dimension, rest)) = dimensions.split_first() else {
+        return;
+    };
+    for key in dimension.keys(entry, categorizer) {
+        let group = groups.entry(key).or_default();
+        group.total += 1;
+        group.bytes += entry.line_bytes();
+        *group
+            .by_level
+            .entry(entry.level.as_str().to_string())
+            .or_insert(0) += 1;
+        add(&mut group.groups, entry, rest, categorizer);
+    }
+}
+
+/// Heures et niveaux dans l'ordre naturel, autres dimensions par volume décroissant
+fn sorted<'a>(
+    groups: &'a BTreeMap<String, GroupStats>,
+    dimension: &Dimension,
+) -> Vec<(&'a String, &'a GroupStats)> {
+    let mut groups: Vec<_> = groups.iter().collect();
+    if !matches!(dimension, Dimension::Hour | Dimension::Level) {
+        groups.sort_by(|a, b| b.1.bytes.cmp(&a.1.bytes).then_with(|| a.0.cmp(b.0)));
+    }
+    groups
+}
+
+/// Tableau imbriqué: une colonne par dimension, chaque groupe suivi de ses
+/// sous-groupes en retrait d'une colonne
+pub fn render_table(
+    groups: &BTreeMap<String, GroupStats>,
+    dimensions: &[Dimension],
+    level_names: &[&String],
+    locale: Locale,
+) -> Table {
+    fn rows(
+        table: &mut Table,
+        groups: &BTreeMap<String, GroupStats>,
+        dimensions: &[Dimension],
+        depth: usize,
+        level_names: &[&String],
+        locale: Locale,
+    ) {
+        let Some(dimension) = dimensions.get(depth) else {
+            return;
+        };
+        for (key, group) in sorted(groups, dimension) {
+            let mut row: Vec<Cell> = (0..dimensions.len())
+                .map(|i| Cell::new(if i == depth { key } else { "" }))
+                .collect();
+            row.push(Cell::new(&locale.int(group.total)));
+            row.push(Cell::new(&locale.bytes(group.bytes)));
+            row.extend(
+                level_names
+                    .iter()
+                    .map(|l| Cell::new(&locale.int(group.by_level.get(*l).copied().unwrap_or(0)))),
+            );
+            table.add_row(Row::new(row));
+            rows(
+                table,
+                &group.groups,
+                dimensions,
+                depth + 1,
+                level_names,
+                locale,
+            );
+        }
+    }
+
+    let mut header: Vec<Cell> = dimensions.iter().map(|d| Cell::new(&d.label())).collect();
+    header.extend([Cell::new("Total"), Cell::new("Bytes")]);
+    header.extend(level_names.iter().map(|l| Cell::new(l)));
+    let mut table = Table::new();
+    table.add_row(Row::new(header));
+    rows(&mut table, groups, dimensions, 0, level_names, locale);
+    table
+}
+
+/// Lignes CSV `group`, `group_bytes` et `group_level`; la clé d'un
+/// sous-groupe est le chemin de ses groupes parents (`ERROR/api`)
+pub fn csv_rows(groups: &BTreeMap<String, GroupStats>, prefix: &str, output: &mut String) {
+    for (key, group) in groups {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}/{key}")
+        };
+        output.push_str(&format!("group,{path},{}\n", group.total));
+        output.push_str(&format!("group_bytes,{path},{}\n", group.bytes));
+        let mut levels: Vec<_> = group.by_level.iter().collect();
+        levels.sort();
+        for (level, count) in levels {
+            output.push_str(&format!("group_level,{path} {level},{count}\n"));
+        }
+        csv_rows(&group.groups, &path, output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fields, parse_log_line};
+
+    #[test]
+    fn nests_groups_across_dimensions() {
+        let entries: Vec<LogEntry> = [
+            "2024-01-15 10:30:45 [ERROR] timeout component=api",
+            "2024-01-15 10:31:45 [ERROR] timeout component=db",
+            "2024-01-15 11:02:00 [ERROR] timeout component=api",
+            "2024-01-15 11:05:00 [INFO] ok",
+        ]
+        .iter()
+        .map(|l| {
+            let mut entry = parse_log_line(l).unwrap();
+            entry.fields = fields::extract(&entry.message);
+            entry
+        })
+        .collect();
+        let dimensions: Vec<Dimension> = "level,component,hour"
+            .split(',')
+            .map(|d| parse_dimension(d).unwrap())
+            .collect();
+        assert_eq!(dimensions[1], Dimension::Field("component".to_string()));
+
+        let mut groups = BTreeMap::new();
+        for entry in &entries {
+            add(&mut groups, entry, &dimensions, &Categorizer::default());
+        }
+        assert_eq!(groups["ERROR"].total, 3);
+        assert_eq!(groups["ERROR"].groups["api"].total, 2);
+        assert_eq!(groups["ERROR"].groups["api"].groups["11:00"].total, 1);
+        assert_eq!(groups["INFO"].groups[MISSING].total, 1);
+
+        let mut csv = String::new();
+        csv_rows(&groups, "", &mut csv);
+        assert!(csv.contains("group,ERROR/api/10:00,1\n"));
+        let table = render_table(&groups, &dimensions, &[], Locale::Plain).to_string();
+        assert!(table.contains("| ERROR |           |       | 3     |"));
+        assert!(table.contains("|       | api       |       | 2     |"));
+
+        assert!(parse_dimension("a b").is_err());
+    }
+}
@@ -0,0 +1,111 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Nombre de lignes rejetées conservées pour l'analyse des formats
+pub const SAMPLE_SIZE: usize = 50;
+
+/// Formats reconnus dans les lignes rejetées, du plus spécifique au plus général
+static SHAPES: Lazy<Vec<(Regex, &'static str)>> =
+    Lazy::new(|| {
+        [
+        (r"^\s*\{", "JSON (un objet par ligne): essayer --input-format json"),
+        (
+            r"^\d{4}-\d{2}-\d{2}T\S+ (stdout|stderr) [FP] ",
+            "format CRI de Kubernetes (horodatage, flux, drapeau F/P): essayer --input-format cri",
+        ),
+        (
+            r"^<\d{1,3}>\d+ ",
+            "syslog RFC 5424 (<PRI>VERSION horodatage hôte ...): essayer --input-format syslog",
+        ),
+        (
+            r"^(<\d{1,3}>)?[A-Z][a-z]{2} [ \d]\d \d{2}:\d{2}:\d{2} ",
+            "syslog BSD RFC 3164 (Jan 15 10:30:45 hôte app: ...): essayer --input-format syslog",
+        ),
+        (
+            r#"^\S+ \S+ \S+ \[\d{2}/\w{3}/\d{4}:\d{2}:\d{2}:\d{2} [^\]]*\] ""#,
+            "journal d'accès Apache/Nginx (Combined Log Format): essayer --input-format access",
+        ),
+        (
+            r"^\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}",
+            "horodatage ISO 8601 avec 'T' (2024-01-15T10:30:45)",
+        ),
+        (
+            r"^\d{4}-\d{2}-\d{2}[ T]\d{2}:\d{2}:\d{2}[.,]\d+",
+            "horodatage avec fractions de seconde (10:30:45.123)",
+        ),
+        (
+            r"^\d{4}-\d{2}-\d{2}\s+\d{2}:\d{2}:\d{2}\s+\[\w+\]",
+            "niveau inconnu entre crochets (seuls INFO, WARN/WARNING, ERROR, DEBUG sont reconnus)",
+        ),
+        (
+            r"^\d{4}-\d{2}-\d{2}\s+\d{2}:\d{2}:\d{2}\s+\w+[:\s]",
+            "niveau sans crochets (2024-01-15 10:30:45 ERROR ...)",
+        ),
+        (
+            r"^\w+=(\S+|\x22[^\x22]*\x22)(\s+\w+=(\S+|\x22[^\x22]*\x22))+\s*$",
+            "logfmt (clé=valeur): essayer --input-format logfmt",
+        ),
+        (
+            r"^(\s+|at |Caused by|Traceback|\.\.\. \d+ more)",
+            "lignes de continuation (traces de pile multi-lignes)",
+        ),
+    ]
+    .into_iter()
+    .map(|(re, label)| (Regex::new(re).unwrap(), label))
+    .collect()
+    });
+
+/// Décrit les formats les plus probables des lignes rejetées, du plus
+/// fréquent au moins fréquent.
+pub fn suggest(samples: &[String]) -> Vec<String> {
+    let mut counts = vec![0usize; SHAPES.len()];
+    for line in samples {
+        if let Some(idx) = SHAPES.iter().position(|(re, _)| re.is_match(line)) {
+            counts[idx] += 1;
+        }
+    }
+
+    let mut found: Vec<_> = counts
+        .into_iter()
+        .enumerate()
+        .filter(|(_, count)| *count > 0)
+        .collect();
+    found.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    found
+        .into_iter()
+        .map(|(idx, count)| {
+            format!(
+                "{count}/{} lignes rejetées échantillonnées ressemblent à: {}",
+                samples.len(),
+                SHAPES[idx].1
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suggest_recognizes_iso8601_and_json() {
+        let samples = vec![
+            "2024-01-15T10:30:45Z [ERROR] boom".to_string(),
+            "2024-01-15T10:30:46Z [INFO] ok".to_string(),
+            r#"{"ts":"2024-01-15","level":"error"}"#.to_string(),
+            "garbage".to_string(),
+        ];
+        let hints = suggest(&samples);
+        assert_eq!(hints.len(), 2);
+        assert!(hints[0].starts_with("2/4"));
+        assert!(hints[0].contains("ISO 8601"));
+        assert!(hints[1].contains("JSON"));
+    }
+
+    #[test]
+    fn suggest_flags_unknown_levels() {
+        let samples = vec!["2024-01-15 10:30:45 [TRACE] detail".to_string()];
+        assert!(suggest(&samples)[0].contains("niveau inconnu"));
+    }
+}
@@ -0,0 +1,220 @@
+use crate::rules::Categorizer;
+use crate::{LogEntry, LogLevel, fields, noise};
+use chrono::{NaiveDateTime, TimeDelta, Timelike};
+use prettytable::{Cell, Row, Table};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+/// Champs `clé=valeur` qui nomment le composant émetteur, par priorité;
+/// à défaut, la catégorie de l'erreur en tient lieu.
+const COMPONENT_KEYS: [&str; 3] = ["component", "service", "app"];
+
+/// Erreurs regroupées par gabarit de message
+#[derive(Debug, PartialEq)]
+pub struct ErrorGroup {
+    pub template: String,
+    pub count: usize,
+    pub example: String,
+    pub first: NaiveDateTime,
+    pub last: NaiveDateTime,
+}
+
+/// Trame d'un rapport d'incident, point de départ d'un postmortem
+#[derive(Debug)]
+pub struct Incident {
+    pub entries: usize,
+    pub errors: usize,
+    pub first_error: (NaiveDateTime, String),
+    /// Minute la plus chargée en erreurs
+    pub peak: (NaiveDateTime, usize),
+    pub last_error: NaiveDateTime,
+    pub groups: Vec<ErrorGroup>,
+    pub components: Vec<(String, usize)>,
+}
+
+fn component(entry: &LogEntry, categorizer: &Categorizer) -> String {
+    let found = fields::extract(&entry.message);
+    COMPONENT_KEYS
+        .iter()
+        .find_map(|key| found.get(*key).cloned())
+        .unwrap_or_else(|| categorizer.categorize(&entry.message).to_string())
+}
+
+/// `None` si aucune erreur ne figure parmi les entrées.
+pub fn build(entries: &[LogEntry], top_n: usize, categorizer: &Categorizer) -> Option<Incident> {
+    let errors: Vec<&LogEntry> = entries
+        .iter()
+        .filter(|e| e.level == LogLevel::Error)
+        .collect();
+    let first = errors.iter().min_by_key(|e| e.datetime)?;
+    let last_error = errors.iter().map(|e| e.datetime).max()?;
+
+    let mut per_minute: BTreeMap<NaiveDateTime, usize> = BTreeMap::new();
+    let mut groups: HashMap<String, ErrorGroup> = HashMap::new();
+    let mut components: HashMap<String, usize> = HashMap::new();
+    for entry in &errors {
+        let minute = entry.datetime.with_second(0).unwrap_or(entry.datetime);
+        *per_minute.entry(minute).or_insert(0) += 1;
+
+        let template = noise::normalize(&entry.message);
+        let group = groups
+            .entry(template.clone())
+            .or_insert_with(|| ErrorGroup {
+                template,
+                count: 0,
+                example: entry.message.clone(),
+                first: entry.datetime,
+                last: entry.datetime,
+            });
+        group.count += 1;
+        group.first = group.first.min(entry.datetime);
+        group.last = group.last.max(entry.datetime);
+
+        *components.entry(component(entry, categorizer)).or_insert(0) += 1;
+    }
+    // En cas d'égalité, la minute la plus ancienne est retenue
+    let peak = per_minute
+        .iter()
+        .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+        .map(|(at, n)| (*at, *n))?;
+
+    let mut groups: Vec<ErrorGroup> = groups.into_values().collect();
+    groups.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.first.cmp(&b.first)));
+    groups.truncate(top_n);
+    let mut components: Vec<(String, usize)> = components.into_iter().collect();
+    components.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    Some(Incident {
+        entries: entries.len(),
+        errors: errors.len(),
+        first_error: (first.datetime, first.message.clone()),
+        peak,
+        last_error,
+        groups,
+        components,
+    })
+}
+
+/// Durée lisible, à la minute près au-delà d'une heure
+fn format_span(span: TimeDelta) -> String {
+    let secs = span.num_seconds();
+    match secs {
+        s if s < 60 => format!("{s}s"),
+        s if s < 3600 => format!("{}m{:02}s", s / 60, s % 60),
+        s => format!("{}h{:02}m", s / 3600, s % 3600 / 60),
+    }
+}
+
+pub fn render(incident: &Incident, source: &str) -> String {
+    let mut output = String::new();
+    writeln!(output, "Incident report: {source}\n").unwrap();
+    writeln!(
+        output,
+        "{} erreurs sur {} entrées ({:.1}%), entre {} et {} ({}).\n",
+        incident.errors,
+        incident.entries,
+        incident.errors as f64 / incident.entries as f64 * 100.0,
+        incident.first_error.0,
+        incident.last_error,
+        format_span(incident.last_error - incident.first_error.0)
+    )
+    .unwrap();
+
+    writeln!(output, "Timeline:").unwrap();
+    writeln!(
+        output,
+        "- {}  Première erreur: {}",
+        incident.first_error.0, incident.first_error.1
+    )
+    .unwrap();
+    writeln!(
+        output,
+        "- {}  Pic: {} erreurs dans la minute, {} après la première",
+        incident.peak.0.format("%Y-%m-%d %H:%M"),
+        incident.peak.1,
+        format_span(incident.peak.0 - incident.first_error.0.with_second(0).unwrap())
+    )
+    .unwrap();
+    writeln!(
+        output,
+        "- {}  Retour au calme: dernière erreur",
+        incident.last_error
+    )
+    .unwrap();
+
+    writeln!(output, "\nTop error groups:").unwrap();
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![
+        Cell::new("Group"),
+        Cell::new("Count"),
+        Cell::new("First seen"),
+        Cell::new("Last seen"),
+        Cell::new("Example"),
+    ]));
+    for group in &incident.groups {
+        table.add_row(Row::new(vec![
+            Cell::new(&group.template),
+            Cell::new(&group.count.to_string()),
+            Cell::new(&group.first.format("%H:%M:%S").to_string()),
+            Cell::new(&group.last.format("%H:%M:%S").to_string()),
+            Cell::new(&group.example),
+        ]));
+    }
+    writeln!(output, "{table}").unwrap();
+
+    writeln!(output, "Affected components:").unwrap();
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![Cell::new("Component"), Cell::new("Errors")]));
+    for (name, count) in &incident.components {
+        table.add_row(Row::new(vec![
+            Cell::new(name),
+            Cell::new(&count.to_string()),
+        ]));
+    }
+    write!(output, "{table}").unwrap();
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_log_line;
+
+    #[test]
+    fn builds_timeline_groups_and_components() {
+        let entries: Vec<LogEntry> = [
+            "2024-01-15 10:00:00 [INFO] deploy started",
+            "2024-01-15 10:01:10 [ERROR] timeout calling payments id=1",
+            "2024-01-15 10:02:05 [ERROR] timeout calling payments id=2",
+            "2024-01-15 10:02:30 [ERROR] query failed service=ledger",
+            "2024-01-15 10:02:50 [ERROR] timeout calling payments id=3",
+            "2024-01-15 10:07:00 [ERROR] query failed service=ledger",
+            "2024-01-15 10:10:00 [INFO] recovered",
+        ]
+        .iter()
+        .map(|l| parse_log_line(l).unwrap())
+        .collect();
+
+        let incident = build(&entries, 5, &Categorizer::default()).unwrap();
+        assert_eq!(incident.errors, 5);
+        assert_eq!(incident.first_error.1, "timeout calling payments id=1");
+        assert_eq!(incident.peak.1, 3);
+        assert_eq!(incident.peak.0.to_string(), "2024-01-15 10:02:00");
+        assert_eq!(incident.last_error.to_string(), "2024-01-15 10:07:00");
+        assert_eq!(
+            incident.groups[0].template,
+            "timeout calling payments id=<num>"
+        );
+        assert_eq!(incident.groups[0].count, 3);
+        assert_eq!(incident.groups[1].example, "query failed service=ledger");
+        assert_eq!(
+            incident.components,
+            vec![("network".to_string(), 3), ("ledger".to_string(), 2)]
+        );
+
+        let report = render(&incident, "app.log");
+        assert!(report.contains("5 erreurs sur 7 entrées"));
+        assert!(report.contains("Pic: 3 erreurs dans la minute, 1m00s après la première"));
+        assert!(build(&entries[..1], 5, &Categorizer::default()).is_none());
+    }
+}
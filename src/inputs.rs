@@ -0,0 +1,126 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Fichiers d'un répertoire et de ses sous-répertoires, fichiers et
+/// répertoires cachés (`.git`, `.lock`...) exclus
+fn walk(dir: &Path, files: &mut BTreeSet<PathBuf>) -> Result<(), String> {
+    let read =
+        fs::read_dir(dir).map_err(|e| format!("Lecture de {} impossible: {e}", dir.display()))?;
+    for entry in read {
+        let entry = entry.map_err(|e| format!("Lecture de {} impossible: {e}", dir.display()))?;
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        if path.is_dir() {
+            walk(&path, files)?;
+        } else if path.is_file() {
+            files.insert(path);
+        }
+    }
+    Ok(())
+}
+
+/// Fichiers d'entrée: chaque répertoire est remplacé par les fichiers qu'il
+/// contient, récursivement, et chaque motif `--glob` (`logs/**/*.log`) par
+/// les fichiers qui y correspondent. Les chemins qui ne sont pas des
+/// répertoires restent tels quels (un fichier absent sera signalé à la
+/// lecture); ceux d'un même répertoire ou motif sont triés.
+pub fn expand(paths: &[PathBuf], globs: &[String]) -> Result<Vec<PathBuf>, String> {
+    fn push(files: BTreeSet<PathBuf>, inputs: &mut Vec<PathBuf>) {
+        for file in files {
+            if !inputs.contains(&file) {
+                inputs.push(file);
+            }
+        }
+    }
+
+    let mut inputs = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            let mut files = BTreeSet::new();
+            walk(path, &mut files)?;
+            if files.is_empty() {
+                return Err(format!(
+                    "Aucun fichier dans le répertoire {}",
+                    path.display()
+                ));
+            }
+            push(files, &mut inputs);
+        } else {
+            push(BTreeSet::from([path.clone()]), &mut inputs);
+        }
+    }
+    for pattern in globs {
+        let options = glob::MatchOptions {
+            require_literal_leading_dot: true,
+            ..Default::default()
+        };
+        let matches = glob::glob_with(pattern, options)
+            .map_err(|e| format!("Motif --glob invalide {pattern}: {e}"))?;
+        let files: BTreeSet<PathBuf> = matches
+            .filter_map(Result::ok)
+            .filter(|p| p.is_file())
+            .collect();
+        if files.is_empty() {
+            return Err(format!("Aucun fichier ne correspond à --glob {pattern}"));
+        }
+        push(files, &mut inputs);
+    }
+    Ok(inputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_directories_and_globs() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("api/old")).unwrap();
+        fs::create_dir_all(root.join(".cache")).unwrap();
+        for file in [
+            "web.log",
+            "api/b.log",
+            "api/a.log",
+            "api/old/a.log.1.gz",
+            ".cache/x.log",
+        ] {
+            fs::write(root.join(file), "").unwrap();
+        }
+
+        let files = expand(&[root.join("api")], &[]).unwrap();
+        assert_eq!(
+            files,
+            [
+                root.join("api/a.log"),
+                root.join("api/b.log"),
+                root.join("api/old/a.log.1.gz")
+            ]
+        );
+
+        let pattern = format!("{}/**/*.log", root.display());
+        let files = expand(&[root.join("web.log")], &[pattern]).unwrap();
+        assert_eq!(
+            files,
+            [
+                root.join("web.log"),
+                root.join("api/a.log"),
+                root.join("api/b.log")
+            ]
+        );
+
+        let missing = format!("{}/*.txt", root.display());
+        assert!(
+            expand(&[], &[missing])
+                .unwrap_err()
+                .contains("Aucun fichier")
+        );
+        assert_eq!(
+            expand(&[root.join("absent.log")], &[]).unwrap(),
+            [root.join("absent.log")]
+        );
+    }
+}
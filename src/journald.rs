@@ -0,0 +1,235 @@
+use crate::{LogEntry, LogLevel};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use serde_json::Value;
+use std::borrow::Cow;
+use std::io::{self, Read};
+use std::path::Path;
+use std::process::{Child, ChildStdout, Command, Stdio};
+use std::sync::OnceLock;
+
+/// Préfixe des entrées désignant le journal systemd (`journal://` ou
+/// `journal://unité`), ajoutées par --journal
+pub const SCHEME: &str = "journal://";
+
+/// Champs du journal gardés comme champs de l'entrée, sous un nom court
+const FIELDS: [(&str, &str); 4] = [
+    ("_SYSTEMD_UNIT", "unit"),
+    ("SYSLOG_IDENTIFIER", "identifier"),
+    ("_PID", "pid"),
+    ("_HOSTNAME", "host"),
+];
+
+/// Début du journal lu, fixé une fois d'après --since
+static SINCE: OnceLock<NaiveDateTime> = OnceLock::new();
+
+pub fn since(time: NaiveDateTime) {
+    let _ = SINCE.set(time);
+}
+
+/// Unité désignée par une entrée `journal://unité`, `Some("")` pour tout le journal
+pub fn unit(path: &Path) -> Option<&str> {
+    path.to_str()?.strip_prefix(SCHEME)
+}
+
+/// Niveau syslog (PRIORITY, de 0 à 7) ramené aux niveaux de l'outil
+fn priority_level(priority: &str) -> Option<LogLevel> {
+    match priority {
+        "0" | "1" | "2" | "3" => Some(LogLevel::Error),
+        "4" => Some(LogLevel::Warning),
+        "5" | "6" => Some(LogLevel::Info),
+        "7" => Some(LogLevel::Debug),
+        _ => None,
+    }
+}
+
+/// Valeur d'un champ du journal: une chaîne, ou un tableau d'octets quand
+/// le contenu n'est pas de l'UTF-8 imprimable (couleurs ANSI, binaire)
+fn field_text(value: &Value) -> Option<Cow<'_, str>> {
+    match value {
+        Value::String(text) => Some(Cow::Borrowed(text)),
+        Value::Array(bytes) => {
+            let bytes = bytes
+                .iter()
+                .map(|b| b.as_u64().and_then(|b| u8::try_from(b).ok()))
+                .collect::<Option<Vec<u8>>>()?;
+            Some(Cow::Owned(String::from_utf8_lossy(&bytes).into_owned()))
+        }
+        _ => None,
+    }
+}
+
+/// Une entrée au format de `journalctl -o json`: horodatage en microsecondes
+/// (`__REALTIME_TIMESTAMP`, ramené à l'heure locale comme l'affiche
+/// journalctl), PRIORITY et MESSAGE. Sans PRIORITY, l'entrée est en INFO;
+/// unité, identifiant, pid et hôte deviennent des champs.
+pub fn parse_line(line: &str) -> Option<LogEntry> {
+    let Value::Object(object) = serde_json::from_str(line).ok()? else {
+        return None;
+    };
+    let text = |key: &str| object.get(key).and_then(field_text);
+    let micros: i64 = text("__REALTIME_TIMESTAMP")?.parse().ok()?;
+    let datetime = DateTime::from_timestamp_micros(micros)?
+        .with_timezone(&Local)
+        .naive_local();
+    let level = match text("PRIORITY") {
+        Some(priority) => priority_level(&priority)?,
+        None => LogLevel::Info,
+    };
+    Some(LogEntry {
+        timestamp: datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
+        datetime,
+        level,
+        message: text("MESSAGE")?.into_owned(),
+        tags: Vec::new(),
+        fields: FIELDS
+            .iter()
+            .filter_map(|(key, name)| Some((name.to_string(), text(key)?.into_owned())))
+            .collect(),
+    })
+}
+
+/// `journalctl -o json` sur le journal local: toutes les entrées, ou celles
+/// de l'unité (`nginx` désigne `nginx.service`), à partir de `since` (heure
+/// locale)
+fn command(unit: &str, since: Option<NaiveDateTime>) -> Command {
+    let mut command = Command::new("journalctl");
+    command.args(["--output=json", "--no-pager", "--quiet"]);
+    if let Some(since) = since {
+        // Heure sautée au passage à l'heure d'été: UTC, faute de mieux
+        let epoch = Local
+            .from_local_datetime(&since)
+            .earliest()
+            .map_or_else(|| since.and_utc().timestamp(), |t| t.timestamp());
+        command.arg(format!("--since=@{epoch}"));
+    }
+    if !unit.is_empty() {
+        let unit = if unit.contains('.') {
+            unit.to_string()
+        } else {
+            format!("{unit}.service")
+        };
+        command.arg(format!("_SYSTEMD_UNIT={unit}"));
+    }
+    command
+}
+
+/// Sortie de `journalctl`, dont le code de retour est vérifié en fin de
+/// lecture pour qu'un journal illisible ne passe pas pour un journal vide
+struct Journal {
+    child: Child,
+    stdout: ChildStdout,
+}
+
+impl Read for Journal {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.stdout.read(buf)?;
+        if n == 0 && !buf.is_empty() {
+            let status = self.child.wait()?;
+            if !status.success() {
+                return Err(io::Error::other(format!("journalctl a échoué ({status})")));
+            }
+        }
+        Ok(n)
+    }
+}
+
+impl Drop for Journal {
+    fn drop(&mut self) {
+        // Lecture abandonnée (Ctrl-C, --head): inutile de laisser journalctl tourner
+        if let Ok(None) = self.child.try_wait() {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+}
+
+fn spawn(mut command: Command) -> io::Result<Box<dyn Read>> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|err| match err.kind() {
+            io::ErrorKind::NotFound => io::Error::new(
+                io::ErrorKind::NotFound,
+                "journalctl introuvable: --journal lit le journal par journalctl",
+            ),
+            _ => err,
+        })?;
+    let stdout = child.stdout.take().expect("sortie standard redirigée");
+    Ok(Box::new(Journal { child, stdout }))
+}
+
+/// Journal local (toutes les entrées, ou celles de l'unité), à partir de
+/// --since s'il est donné
+pub fn open(unit: &str) -> io::Result<Box<dyn Read>> {
+    spawn(command(unit, SINCE.get().copied()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2024-01-15 10:30:45 UTC, dans le fuseau de la machine qui lance les tests
+    fn local_time() -> NaiveDateTime {
+        Local.timestamp_opt(1705314645, 0).unwrap().naive_local()
+    }
+
+    #[test]
+    fn parses_journalctl_json_entries() {
+        let line = r#"{"__REALTIME_TIMESTAMP":"1705314645123456","MESSAGE":"Failed to start nginx.service","PRIORITY":"3","_SYSTEMD_UNIT":"init.scope","_PID":"1","_BOOT_ID":"a1b2"}"#;
+        let entry = parse_line(line).unwrap();
+        let expected = local_time().format("%Y-%m-%d %H:%M:%S").to_string();
+        assert_eq!(entry.timestamp, expected);
+        assert_eq!(entry.level, LogLevel::Error);
+        assert_eq!(entry.message, "Failed to start nginx.service");
+        assert_eq!(entry.fields["unit"], "init.scope");
+        assert_eq!(entry.fields["pid"], "1");
+        assert!(!entry.fields.contains_key("_BOOT_ID"));
+
+        let notice = r#"{"__REALTIME_TIMESTAMP":"1705314645000000","PRIORITY":"5","MESSAGE":"ok"}"#;
+        assert_eq!(parse_line(notice).unwrap().level, LogLevel::Info);
+        let bare = r#"{"__REALTIME_TIMESTAMP":"1705314645000000","MESSAGE":"ok"}"#;
+        assert_eq!(parse_line(bare).unwrap().level, LogLevel::Info);
+        // Message non imprimable: journalctl le rend en tableau d'octets
+        let bytes = r#"{"__REALTIME_TIMESTAMP":"1705314645000000","MESSAGE":[27,91,51,49,109,100,111,119,110,255]}"#;
+        assert_eq!(parse_line(bytes).unwrap().message, "\u{1b}[31mdown\u{fffd}");
+        assert_eq!(unit(Path::new("journal://sshd")), Some("sshd"));
+    }
+
+    #[test]
+    fn builds_journalctl_arguments() {
+        let command = command("sshd", Some(local_time()));
+        let args: Vec<_> = command.get_args().map(|a| a.to_str().unwrap()).collect();
+        assert_eq!(command.get_program(), "journalctl");
+        assert_eq!(
+            args,
+            [
+                "--output=json",
+                "--no-pager",
+                "--quiet",
+                "--since=@1705314645",
+                "_SYSTEMD_UNIT=sshd.service"
+            ]
+        );
+        let command = super::command("", None);
+        assert_eq!(command.get_args().count(), 3);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reports_a_failed_journalctl() {
+        let mut out = String::new();
+        let mut ok = Command::new("sh");
+        ok.args(["-c", "echo '{}'"]);
+        spawn(ok).unwrap().read_to_string(&mut out).unwrap();
+        assert_eq!(out, "{}\n");
+
+        let mut failing = Command::new("sh");
+        failing.args(["-c", "echo partial; exit 1"]);
+        let err = spawn(failing)
+            .unwrap()
+            .read_to_string(&mut out)
+            .unwrap_err();
+        assert!(err.to_string().contains("journalctl a échoué"));
+    }
+}
@@ -0,0 +1,159 @@
+use crate::{LogEntry, LogLevel};
+use chrono::{DateTime, NaiveDateTime};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// Clés lues pour l'horodatage, le niveau et le message (JSON et logfmt);
+/// en JSON, un nom pointé (`log.level`) désigne une clé imbriquée
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputKeys {
+    pub timestamp: String,
+    pub level: String,
+    pub message: String,
+}
+
+impl Default for InputKeys {
+    fn default() -> Self {
+        InputKeys {
+            timestamp: "ts".to_string(),
+            level: "level".to_string(),
+            message: "msg".to_string(),
+        }
+    }
+}
+
+/// `ts=time,level=severity,msg=message`; les clés omises gardent leur valeur
+/// par défaut
+pub fn parse_keys(input: &str) -> Result<InputKeys, String> {
+    let mut keys = InputKeys::default();
+    for pair in input.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let invalid =
+            || format!("Clé d'entrée invalide: {pair} (ex: ts=time,level=severity,msg=message)");
+        let (name, key) = pair.split_once('=').ok_or_else(invalid)?;
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(invalid());
+        }
+        let slot = match name.trim() {
+            "ts" | "timestamp" => &mut keys.timestamp,
+            "level" => &mut keys.level,
+            "msg" | "message" => &mut keys.message,
+            _ => return Err(invalid()),
+        };
+        *slot = key.to_string();
+    }
+    Ok(keys)
+}
+
+fn lookup<'a>(object: &'a Map<String, Value>, key: &str) -> Option<&'a Value> {
+    object.get(key).or_else(|| {
+        let mut parts = key.split('.');
+        let first = object.get(parts.next()?)?;
+        parts.try_fold(first, |value, part| value.get(part))
+    })
+}
+
+fn from_epoch(n: f64) -> Option<NaiveDateTime> {
+    let millis = if n.abs() >= 1e11 { n } else { n * 1000.0 };
+    DateTime::from_timestamp_millis(millis as i64).map(|d| d.naive_utc())
+}
+
+/// RFC 3339 (ramené en UTC), `AAAA-MM-JJ[T ]HH:MM:SS[.fff]` ou epoch en
+/// secondes ou millisecondes
+pub fn parse_time(s: &str) -> Option<NaiveDateTime> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|d| d.naive_utc())
+        .ok()
+        .or_else(|| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f").ok())
+        .or_else(|| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f").ok())
+        .or_else(|| s.parse().ok().and_then(from_epoch))
+}
+
+fn parse_timestamp(value: &Value) -> Option<NaiveDateTime> {
+    match value {
+        Value::Number(n) => from_epoch(n.as_f64()?),
+        Value::String(s) => parse_time(s),
+        _ => None,
+    }
+}
+
+/// Niveaux usuels des bibliothèques de logs structurés, en plus de ceux du
+/// format texte
+pub fn parse_level(level: &str) -> Option<LogLevel> {
+    LogLevel::from_str(level).or_else(|| match level.to_lowercase().as_str() {
+        "err" | "fatal" | "critical" | "crit" | "panic" => Some(LogLevel::Error),
+        "trace" => Some(LogLevel::Debug),
+        "notice" => Some(LogLevel::Info),
+        _ => None,
+    })
+}
+
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// Une ligne JSON en entrée; les autres clés de premier niveau à valeur
+/// simple deviennent des champs, comme les `clé=valeur` du format texte
+pub fn parse_line(line: &str, keys: &InputKeys) -> Option<LogEntry> {
+    if !line.trim_start().starts_with('{') {
+        return None;
+    }
+    let Value::Object(object) = serde_json::from_str(line).ok()? else {
+        return None;
+    };
+    let datetime = parse_timestamp(lookup(&object, &keys.timestamp)?)?;
+    let level = parse_level(lookup(&object, &keys.level)?.as_str()?)?;
+    let message = lookup(&object, &keys.message)?.as_str()?.to_string();
+    let fields: BTreeMap<String, String> = object
+        .iter()
+        .filter(|(k, _)| ![&keys.timestamp, &keys.level, &keys.message].contains(k))
+        .filter_map(|(k, v)| scalar(v).map(|v| (k.clone(), v)))
+        .collect();
+    Some(LogEntry {
+        timestamp: datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
+        datetime,
+        level,
+        message,
+        tags: Vec::new(),
+        fields,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_json_lines_with_configurable_keys() {
+        let keys = InputKeys::default();
+        let entry = parse_line(
+            r#"{"ts":"2024-01-15T10:30:45.120+02:00","level":"error","msg":"timeout","host":"db1","retries":3,"ctx":{"a":1}}"#,
+            &keys,
+        )
+        .unwrap();
+        assert_eq!(entry.timestamp, "2024-01-15 08:30:45");
+        assert_eq!(entry.level, LogLevel::Error);
+        assert_eq!(entry.message, "timeout");
+        assert_eq!(entry.fields["host"], "db1");
+        assert_eq!(entry.fields["retries"], "3");
+        assert!(!entry.fields.contains_key("ctx"));
+
+        let keys = parse_keys("ts=time, level=log.severity, msg=message").unwrap();
+        let entry = parse_line(
+            r#"{"time":1705314645000,"log":{"severity":"WARN"},"message":"slow"}"#,
+            &keys,
+        )
+        .unwrap();
+        assert_eq!(entry.timestamp, "2024-01-15 10:30:45");
+        assert_eq!(entry.level, LogLevel::Warning);
+
+        assert!(parse_line("2024-01-15 10:30:45 [ERROR] text", &keys).is_none());
+        assert!(parse_line(r#"{"time":"yesterday","message":"x"}"#, &keys).is_none());
+        assert!(parse_keys("when=ts").is_err());
+    }
+}
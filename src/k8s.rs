@@ -0,0 +1,183 @@
+use crate::follow::{CHANNEL_CAPACITY, LineSource};
+use chrono::NaiveDateTime;
+use futures_util::{AsyncBufReadExt, StreamExt};
+use k8s_openapi::api::core::v1::Pod;
+use k8s_openapi::jiff::Timestamp;
+use kube::api::{ListParams, LogParams};
+use kube::{Api, Client, ResourceExt};
+use std::io::{self, Read};
+use std::path::Path;
+use std::sync::OnceLock;
+use std::sync::mpsc::{self, Receiver};
+use tokio::runtime::Runtime;
+
+/// Logs des pods correspondant à un sélecteur de labels, lus en continu par
+/// une tâche par pod. Les pods démarrés après la connexion ne sont pas suivis.
+pub struct PodLogs {
+    lines: Receiver<(String, String)>,
+    // Garde les tâches de lecture en vie tant que la source existe
+    _runtime: Runtime,
+}
+
+impl PodLogs {
+    pub fn connect(namespace: &str, selector: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?;
+        let (api, pods) = runtime.block_on(async {
+            let client = Client::try_default().await?;
+            let api: Api<Pod> = Api::namespaced(client, namespace);
+            let pods = api.list(&ListParams::default().labels(selector)).await?;
+            Ok::<_, kube::Error>((api, pods))
+        })?;
+        if pods.items.is_empty() {
+            return Err(format!("aucun pod ne correspond au sélecteur '{selector}'").into());
+        }
+
+        let (tx, lines) = mpsc::sync_channel(CHANNEL_CAPACITY);
+        for pod in pods {
+            let name = pod.name_any();
+            let api = api.clone();
+            let tx = tx.clone();
+            runtime.spawn(async move {
+                // Comme --follow sur un fichier: seules les nouvelles lignes
+                let params = LogParams {
+                    follow: true,
+                    tail_lines: Some(0),
+                    ..LogParams::default()
+                };
+                let stream = match api.log_stream(&name, &params).await {
+                    Ok(stream) => stream,
+                    Err(err) => {
+                        eprintln!("Logs du pod {name} indisponibles: {err}");
+                        return;
+                    }
+                };
+                let mut lines = stream.lines();
+                while let Some(Ok(line)) = lines.next().await {
+                    // File pleine: bloque ce fil de travail, pas tout le runtime
+                    let sent = tokio::task::block_in_place(|| tx.send((name.clone(), line)));
+                    if sent.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+
+        Ok(PodLogs {
+            lines,
+            _runtime: runtime,
+        })
+    }
+}
+
+impl LineSource for PodLogs {
+    fn poll_lines(&mut self) -> io::Result<Vec<(String, Option<String>)>> {
+        Ok(self
+            .lines
+            .try_iter()
+            .map(|(pod, line)| (line, Some(format!("pod={pod}"))))
+            .collect())
+    }
+}
+
+/// Préfixe des entrées désignant un pod (`k8s://namespace/pod[/conteneur]`),
+/// ajoutées par `--k8s NAMESPACE/POD`
+pub const SCHEME: &str = "k8s://";
+
+/// Début du journal demandé à l'API (sinceTime), fixé une fois d'après --since
+static SINCE: OnceLock<NaiveDateTime> = OnceLock::new();
+
+pub fn since(time: NaiveDateTime) {
+    let _ = SINCE.set(time);
+}
+
+/// Pod, et conteneur s'il en a plusieurs, dont le journal est analysé
+#[derive(Debug, PartialEq)]
+pub struct PodTarget<'a> {
+    namespace: &'a str,
+    pod: &'a str,
+    container: Option<&'a str>,
+}
+
+fn target(value: &str) -> Option<PodTarget<'_>> {
+    let parts: Vec<&str> = value.split('/').collect();
+    if parts.iter().any(|p| p.is_empty()) {
+        return None;
+    }
+    match parts[..] {
+        [namespace, pod] => Some(PodTarget {
+            namespace,
+            pod,
+            container: None,
+        }),
+        [namespace, pod, container] => Some(PodTarget {
+            namespace,
+            pod,
+            container: Some(container),
+        }),
+        _ => None,
+    }
+}
+
+/// Valeur de `--k8s`: `namespace/pod` ou `namespace/pod/conteneur`
+pub fn parse_target(value: &str) -> Result<String, String> {
+    target(value)
+        .map(|_| value.to_string())
+        .ok_or_else(|| format!("pod invalide: {value} (NAMESPACE/POD[/CONTAINER])"))
+}
+
+/// Pod désigné par une entrée `k8s://namespace/pod[/conteneur]`
+pub fn pod(path: &Path) -> Option<PodTarget<'_>> {
+    target(path.to_str()?.strip_prefix(SCHEME)?)
+}
+
+/// Journal du pod jusqu'à maintenant, horodaté par Kubernetes et réécrit au
+/// format `json-file` de Docker pour profiter du même repli sur le niveau
+pub fn open(target: &PodTarget) -> io::Result<Box<dyn Read>> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    let params = LogParams {
+        container: target.container.map(str::to_string),
+        since_time: SINCE
+            .get()
+            .and_then(|since| Timestamp::from_second(since.and_utc().timestamp()).ok()),
+        timestamps: true,
+        ..LogParams::default()
+    };
+    let text = runtime
+        .block_on(async {
+            let client = Client::try_default().await?;
+            let api: Api<Pod> = Api::namespaced(client, target.namespace);
+            api.logs(target.pod, &params).await
+        })
+        .map_err(|err| match err {
+            kube::Error::Api(status) if status.is_not_found() => io::ErrorKind::NotFound.into(),
+            err => io::Error::other(err),
+        })?;
+    Ok(crate::docker::timestamped(io::Cursor::new(
+        text.into_bytes(),
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_pod_targets() {
+        assert_eq!(
+            pod(Path::new("k8s://prod/api-7d9f/app")),
+            Some(PodTarget {
+                namespace: "prod",
+                pod: "api-7d9f",
+                container: Some("app"),
+            })
+        );
+        assert_eq!(target("prod/api-7d9f").unwrap().container, None);
+        assert!(parse_target("api-7d9f").is_err());
+        assert!(parse_target("prod//app").is_err());
+        assert!(pod(Path::new("prod/api-7d9f")).is_none());
+    }
+}
@@ -0,0 +1,91 @@
+use crate::LogEntry;
+use crate::export::entry_json;
+use crate::forward::Forwarder;
+use kafka::producer::{Producer, Record, RequiredAcks};
+use std::io;
+use std::time::Duration;
+
+/// Nombre d'entrées accumulées avant un envoi groupé
+const BATCH: usize = 500;
+
+/// Publie les entrées en JSON (même objet que `--format jsonl`) sur un topic.
+pub struct KafkaForwarder {
+    producer: Producer,
+    topic: String,
+    pending: Vec<Vec<u8>>,
+}
+
+/// `kafka://broker1:9092,broker2:9092/topic` en (brokers, topic)
+fn parse_url(url: &str) -> Option<(Vec<String>, String)> {
+    let (brokers, topic) = url.strip_prefix("kafka://")?.split_once('/')?;
+    let brokers: Vec<String> = brokers
+        .split(',')
+        .filter(|b| !b.is_empty())
+        .map(str::to_string)
+        .collect();
+    (!brokers.is_empty() && !topic.is_empty()).then(|| (brokers, topic.to_string()))
+}
+
+impl KafkaForwarder {
+    pub fn connect(url: &str) -> io::Result<Self> {
+        let (brokers, topic) = parse_url(url).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("URL Kafka invalide: {url} (kafka://broker:9092/topic)"),
+            )
+        })?;
+        let producer = Producer::from_hosts(brokers)
+            .with_ack_timeout(Duration::from_secs(5))
+            .with_required_acks(RequiredAcks::One)
+            .create()
+            .map_err(io::Error::other)?;
+        Ok(KafkaForwarder {
+            producer,
+            topic,
+            pending: Vec::new(),
+        })
+    }
+}
+
+impl Forwarder for KafkaForwarder {
+    fn send(&mut self, entry: &LogEntry) -> io::Result<()> {
+        self.pending
+            .push(serde_json::to_vec(&entry_json(entry, &[]))?);
+        if self.pending.len() >= BATCH {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let records: Vec<_> = self
+            .pending
+            .iter()
+            .map(|value| Record::from_value(&self.topic, value.as_slice()))
+            .collect();
+        self.producer.send_all(&records).map_err(io::Error::other)?;
+        self.pending.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_brokers_and_topic() {
+        assert_eq!(
+            parse_url("kafka://a:9092,b:9092/logs"),
+            Some((
+                vec!["a:9092".to_string(), "b:9092".to_string()],
+                "logs".to_string()
+            ))
+        );
+        assert_eq!(parse_url("kafka://a:9092"), None);
+        assert_eq!(parse_url("kafka:///logs"), None);
+    }
+}
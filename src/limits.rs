@@ -0,0 +1,241 @@
+use crate::LogStats;
+use crate::groups::GroupStats;
+use std::collections::{BTreeMap, HashSet};
+
+/// Plafonds de `--max-top`, `--max-buckets` et `--max-examples`, appliqués
+/// au rapport quel que soit son format
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Limits {
+    pub top: Option<usize>,
+    pub buckets: Option<usize>,
+    pub examples: Option<usize>,
+}
+
+/// Tronque une liste déjà classée, les premiers éléments étant conservés
+fn first<T>(items: &mut Vec<T>, max: Option<usize>) -> bool {
+    match max {
+        Some(max) if items.len() > max => {
+            items.truncate(max);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Tronque une liste chronologique en gardant les `max` derniers éléments
+fn last<T>(items: &mut Vec<T>, max: Option<usize>) -> bool {
+    match max {
+        Some(max) if items.len() > max => {
+            items.drain(..items.len() - max);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Clés des `max` plus forts poids (à égalité, ordre des clés), `None` s'il
+/// n'y a rien à retirer
+fn heaviest<'a>(
+    weights: impl Iterator<Item = (&'a String, u64)>,
+    max: Option<usize>,
+) -> Option<HashSet<String>> {
+    let mut weights: Vec<_> = weights.collect();
+    let max = max.filter(|max| weights.len() > *max)?;
+    weights.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    Some(
+        weights
+            .into_iter()
+            .take(max)
+            .map(|(k, _)| k.clone())
+            .collect(),
+    )
+}
+
+fn groups(groups: &mut BTreeMap<String, GroupStats>, max: Option<usize>) -> bool {
+    let mut cut = false;
+    if let Some(keep) = heaviest(groups.iter().map(|(k, g)| (k, g.bytes)), max) {
+        groups.retain(|k, _| keep.contains(k));
+        cut = true;
+    }
+    for group in groups.values_mut() {
+        cut |= self::groups(&mut group.groups, max);
+    }
+    cut
+}
+
+/// Applique les plafonds et renvoie les sections tronquées
+pub fn apply(stats: &mut LogStats, limits: Limits) -> Vec<&'static str> {
+    let mut truncated = Vec::new();
+    let mut note = |section: &'static str, cut: bool| {
+        if cut && !truncated.contains(&section) {
+            truncated.push(section);
+        }
+    };
+
+    let top = limits.top;
+    note("top_errors", first(&mut stats.top_errors, top));
+    note("noise", first(&mut stats.noise, top));
+    note("noise_scores", first(&mut stats.noise_scores, top));
+    if let Some(sites) = &mut stats.call_sites {
+        note("call_sites", first(sites, top));
+    }
+    note("first_occurrences", last(&mut stats.first_occurrences, top));
+    if let Some(resolved) = &mut stats.resolved {
+        note("resolved", first(resolved, top));
+    }
+    if let Some(growth) = &mut stats.growth {
+        note("growth", first(&mut growth.groups, top));
+    }
+    if let Some(drift) = &mut stats.severity_drift {
+        note("severity_drift", first(drift, top));
+    }
+    if let Some(budget) = &mut stats.error_budget {
+        note("error_budget", first(&mut budget.top_contributors, top));
+    }
+    if let Some(field) = &mut stats.top_field {
+        note("top_field", first(&mut field.values, top));
+    }
+    for field in &mut stats.captured {
+        note("captured", first(&mut field.values, top));
+    }
+    if let Some(http) = &mut stats.http {
+        note("http", first(&mut http.top_paths, top));
+        if let Some(keep) = heaviest(http.by_status.iter().map(|(k, n)| (k, *n as u64)), top) {
+            http.by_status.retain(|k, _| keep.contains(k));
+            note("http", true);
+        }
+    }
+    if let Some(weekly) = &mut stats.week_over_week {
+        note("week_over_week", first(&mut weekly.by_error, top));
+    }
+    if let Some(keep) = heaviest(
+        stats.errors_by_category.iter().map(|(k, n)| (k, *n as u64)),
+        top,
+    ) {
+        stats.errors_by_category.retain(|k, _| keep.contains(k));
+        stats
+            .errors_by_category_by_hour
+            .retain(|k, _| keep.contains(k));
+        note("errors_by_category", true);
+    }
+    note("groups", groups(&mut stats.groups, top));
+    if let Some(pivot) = &mut stats.pivot {
+        let rows = pivot
+            .counts
+            .iter()
+            .map(|(k, cells)| (k, cells.values().sum::<usize>() as u64));
+        if let Some(keep) = heaviest(rows, top) {
+            pivot.counts.retain(|k, _| keep.contains(k));
+            note("pivot", true);
+        }
+        let columns = pivot.column_keys.iter().map(|c| {
+            let total: usize = pivot.counts.values().filter_map(|cells| cells.get(c)).sum();
+            (c, total as u64)
+        });
+        if let Some(keep) = heaviest(columns, top) {
+            pivot.column_keys.retain(|c| keep.contains(c));
+            for cells in pivot.counts.values_mut() {
+                cells.retain(|c, _| keep.contains(c));
+            }
+            note("pivot", true);
+        }
+    }
+
+    // Seaux horaires: les plus tardifs sont conservés
+    if let Some(max) = limits.buckets {
+        let mut hours: Vec<String> = stats.entries_by_bucket_by_level.keys().cloned().collect();
+        hours.sort();
+        if hours.len() > max {
+            let keep: HashSet<String> = hours.split_off(hours.len() - max).into_iter().collect();
+            stats
+                .entries_by_bucket_by_level
+                .retain(|h, _| keep.contains(h));
+            stats.bytes_by_hour.retain(|h, _| keep.contains(h));
+            stats.errors_by_hour.retain(|h, _| keep.contains(h));
+            stats.error_rate_by_hour.retain(|h, _| keep.contains(h));
+            for by_hour in stats.errors_by_category_by_hour.values_mut() {
+                by_hour.retain(|h, _| keep.contains(h));
+            }
+            note("hours", true);
+        }
+    }
+    if let Some(weekly) = &mut stats.week_over_week
+        && last(&mut weekly.weeks, limits.buckets)
+    {
+        for series in weekly.by_level.iter_mut().chain(&mut weekly.by_error) {
+            last(&mut series.counts, limits.buckets);
+        }
+        note("week_over_week", true);
+    }
+
+    let examples = limits.examples;
+    note("parse_hints", first(&mut stats.parse_hints, examples));
+    for marker in &mut stats.markers {
+        note("markers", first(&mut marker.new_errors, examples));
+    }
+    if let Some(correlation) = &mut stats.correlation {
+        note("correlation", first(&mut correlation.orphan_keys, examples));
+    }
+
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Categorizer;
+    use crate::{LogEntry, analyze_logs, parse_log_line};
+
+    #[test]
+    fn caps_lists_buckets_and_groups() {
+        let entries: Vec<LogEntry> = [
+            "2024-01-15 08:00:00 [ERROR] a",
+            "2024-01-15 09:00:00 [ERROR] b",
+            "2024-01-15 10:00:00 [ERROR] b",
+            "2024-01-15 11:00:00 [ERROR] c",
+            "2024-01-15 11:30:00 [ERROR] c",
+            "2024-01-15 11:45:00 [ERROR] c",
+        ]
+        .iter()
+        .map(|l| parse_log_line(l).unwrap())
+        .collect();
+        let dimensions = [crate::groups::Dimension::Hour];
+        let mut stats = analyze_logs(
+            &entries,
+            5,
+            None,
+            None,
+            0,
+            &Categorizer::default(),
+            &dimensions,
+        );
+        let limits = Limits {
+            top: Some(2),
+            buckets: Some(3),
+            examples: None,
+        };
+        let truncated = apply(&mut stats, limits);
+        assert_eq!(
+            truncated,
+            [
+                "top_errors",
+                "noise_scores",
+                "first_occurrences",
+                "groups",
+                "hours"
+            ]
+        );
+        let messages: Vec<_> = stats
+            .top_errors
+            .iter()
+            .map(|e| e.message.as_str())
+            .collect();
+        assert_eq!(messages, ["c", "b"]);
+        let mut hours: Vec<_> = stats.errors_by_hour.keys().map(String::as_str).collect();
+        hours.sort();
+        assert_eq!(hours, ["09:00", "10:00", "11:00"]);
+        assert_eq!(stats.groups.keys().collect::<Vec<_>>(), ["08:00", "11:00"]);
+
+        assert!(apply(&mut stats, Limits::default()).is_empty());
+    }
+}
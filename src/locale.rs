@@ -0,0 +1,153 @@
+use clap::ValueEnum;
+use std::fmt::Display;
+
+/// Espace fine insécable: séparateur de milliers et espace avant `%` en français
+const NARROW_NBSP: char = '\u{202F}';
+
+/// Écriture des nombres du rapport texte (`--locale`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum Locale {
+    /// Point décimal, sans séparateur de milliers (sans --locale)
+    #[default]
+    #[value(skip)]
+    Plain,
+    /// Virgule décimale, milliers séparés par une espace fine
+    #[value(name = "fr-FR", alias = "fr")]
+    FrFr,
+    /// Point décimal, milliers séparés par une virgule
+    #[value(name = "en-US", alias = "en")]
+    EnUs,
+}
+
+impl Locale {
+    fn thousands(self) -> Option<char> {
+        match self {
+            Locale::Plain => None,
+            Locale::FrFr => Some(NARROW_NBSP),
+            Locale::EnUs => Some(','),
+        }
+    }
+
+    fn decimal_point(self) -> char {
+        match self {
+            Locale::FrFr => ',',
+            Locale::Plain | Locale::EnUs => '.',
+        }
+    }
+
+    /// Sépare les milliers d'une suite de chiffres, signe éventuel compris
+    fn group(self, digits: &str) -> String {
+        let Some(sep) = self.thousands() else {
+            return digits.to_string();
+        };
+        let (sign, digits) = match digits.strip_prefix(['-', '+']) {
+            Some(rest) => (&digits[..1], rest),
+            None => ("", digits),
+        };
+        let mut out = String::from(sign);
+        for (i, c) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i) % 3 == 0 {
+                out.push(sep);
+            }
+            out.push(c);
+        }
+        out
+    }
+
+    pub fn int(self, n: impl Display) -> String {
+        self.group(&n.to_string())
+    }
+
+    pub fn dec(self, x: f64, precision: usize) -> String {
+        self.with_point(format!("{x:.precision$}"))
+    }
+
+    /// Avec signe explicite (`+12.5`), pour une variation
+    pub fn signed(self, x: f64, precision: usize) -> String {
+        self.with_point(format!("{x:+.precision$}"))
+    }
+
+    fn with_point(self, raw: String) -> String {
+        match raw.split_once('.') {
+            Some((int, frac)) => format!("{}{}{frac}", self.group(int), self.decimal_point()),
+            None => self.group(&raw),
+        }
+    }
+
+    /// Pourcentage déjà formaté (`12.5` devient `12,5 %` en français)
+    pub fn percent(self, number: String) -> String {
+        match self {
+            Locale::FrFr => format!("{number}{NARROW_NBSP}%"),
+            Locale::Plain | Locale::EnUs => format!("{number}%"),
+        }
+    }
+
+    pub fn pct(self, x: f64, precision: usize) -> String {
+        self.percent(self.dec(x, precision))
+    }
+
+    /// Durée lisible, en trois unités au plus à partir de la plus grande
+    /// (`2 j 3 h 14 min`, `2 d 3 h 14 min` hors français)
+    pub fn duration(self, seconds: i64) -> String {
+        let day = if self == Locale::FrFr { "j" } else { "d" };
+        let units = [(86_400, day), (3_600, "h"), (60, "min"), (1, "s")];
+        let mut rest = seconds.max(0);
+        let Some(first) = units.iter().position(|(size, _)| rest >= *size) else {
+            return "0 s".to_string();
+        };
+        let mut parts = Vec::new();
+        for (size, unit) in &units[first..(first + 3).min(units.len())] {
+            let n = rest / size;
+            rest %= size;
+            if n > 0 {
+                parts.push(format!("{} {unit}", self.int(n)));
+            }
+        }
+        parts.join(" ")
+    }
+
+    /// Taille lisible en base 1024, symétrique de `parse_size`
+    pub fn bytes(self, bytes: u64) -> String {
+        const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+        let mut value = bytes as f64;
+        let mut unit = 0;
+        while value >= 1024.0 && unit < UNITS.len() - 1 {
+            value /= 1024.0;
+            unit += 1;
+        }
+        if unit == 0 {
+            format!("{} B", self.int(bytes))
+        } else {
+            format!("{} {}", self.dec(value, 1), UNITS[unit])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_numbers_per_locale() {
+        assert_eq!(Locale::Plain.int(1234567), "1234567");
+        assert_eq!(Locale::EnUs.int(1234567), "1,234,567");
+        assert_eq!(Locale::FrFr.int(1234567), "1\u{202F}234\u{202F}567");
+        assert_eq!(Locale::EnUs.int(-1234), "-1,234");
+        assert_eq!(Locale::EnUs.int(999), "999");
+
+        assert_eq!(Locale::Plain.pct(12.345, 1), "12.3%");
+        assert_eq!(Locale::FrFr.pct(12.345, 1), "12,3\u{202F}%");
+        assert_eq!(Locale::EnUs.dec(12345.5, 2), "12,345.50");
+        assert_eq!(Locale::FrFr.signed(1500.0, 1), "+1\u{202F}500,0");
+        assert_eq!(Locale::FrFr.bytes(1536), "1,5 KB");
+        assert_eq!(Locale::EnUs.bytes(1000), "1,000 B");
+
+        assert_eq!(
+            Locale::FrFr.duration(2 * 86_400 + 3 * 3_600 + 14 * 60 + 5),
+            "2 j 3 h 14 min"
+        );
+        assert_eq!(Locale::Plain.duration(86_400 + 65), "1 d 1 min");
+        assert_eq!(Locale::EnUs.duration(3_725), "1 h 2 min 5 s");
+        assert_eq!(Locale::Plain.duration(0), "0 s");
+    }
+}
@@ -0,0 +1,43 @@
+use crate::LogEntry;
+use crate::fields;
+use crate::jsonl::{InputKeys, parse_level, parse_time};
+
+/// Une ligne logfmt (`level=error ts=2024-01-15T10:30:45Z msg="..."`); les
+/// autres paires `clé=valeur` deviennent des champs de l'entrée
+pub fn parse_line(line: &str, keys: &InputKeys) -> Option<LogEntry> {
+    let mut pairs = fields::extract(line);
+    let datetime = parse_time(&pairs.remove(&keys.timestamp)?)?;
+    let level = parse_level(&pairs.remove(&keys.level)?)?;
+    let message = pairs.remove(&keys.message)?;
+    Some(LogEntry {
+        timestamp: datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
+        datetime,
+        level,
+        message,
+        tags: Vec::new(),
+        fields: pairs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LogLevel;
+
+    #[test]
+    fn parses_logfmt_lines() {
+        let keys = InputKeys::default();
+        let entry = parse_line(
+            r#"level=error ts=2024-01-15T10:30:45Z msg="db \"main\" timeout" host=db1 retries=3"#,
+            &keys,
+        )
+        .unwrap();
+        assert_eq!(entry.timestamp, "2024-01-15 10:30:45");
+        assert_eq!(entry.level, LogLevel::Error);
+        assert_eq!(entry.message, r#"db "main" timeout"#);
+        assert_eq!(entry.fields.keys().collect::<Vec<_>>(), ["host", "retries"]);
+
+        assert!(parse_line("level=info msg=ok", &keys).is_none());
+        assert!(parse_line("2024-01-15 10:30:45 [ERROR] text", &keys).is_none());
+    }
+}
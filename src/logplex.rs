@@ -0,0 +1,175 @@
+use crate::follow::{CHANNEL_CAPACITY, LineSource};
+use chrono::DateTime;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::thread;
+
+/// Ligne convertie au format `TIMESTAMP [LEVEL] message`, avec l'origine
+/// `dyno=APP/PROC` en étiquette.
+type DrainLine = (String, Option<String>);
+
+/// Découpe un corps `application/logplex-1` en trames à comptage d'octets
+/// (`LONGUEUR trame...`).
+pub fn split_frames(body: &[u8]) -> Vec<String> {
+    let mut frames = Vec::new();
+    let mut rest = body;
+    loop {
+        rest = rest.trim_ascii_start();
+        let Some(space) = rest.iter().position(|b| *b == b' ') else {
+            break;
+        };
+        let Some(len) = std::str::from_utf8(&rest[..space])
+            .ok()
+            .and_then(|l| l.parse::<usize>().ok())
+        else {
+            break;
+        };
+        let frame = &rest[space + 1..];
+        let len = len.min(frame.len());
+        frames.push(String::from_utf8_lossy(frame[..len].trim_ascii_end()).into_owned());
+        rest = &frame[len..];
+    }
+    frames
+}
+
+/// Sévérité syslog vers niveau; les logs du routeur Heroku sont tous
+/// `info`, on se fie alors à leur champ `at=`.
+fn level(pri: u8, message: &str) -> &'static str {
+    if message.starts_with("at=error") {
+        return "ERROR";
+    }
+    if message.starts_with("at=warning") {
+        return "WARNING";
+    }
+    match pri % 8 {
+        0..=3 => "ERROR",
+        4 => "WARNING",
+        5 | 6 => "INFO",
+        _ => "DEBUG",
+    }
+}
+
+/// Convertit une trame `<PRI>1 TIMESTAMP HOST APP PROC - MESSAGE`.
+pub fn parse_frame(frame: &str) -> Option<DrainLine> {
+    let mut parts = frame.splitn(7, ' ');
+    let pri = parts
+        .next()?
+        .strip_prefix('<')?
+        .split_once('>')?
+        .0
+        .parse()
+        .ok()?;
+    let timestamp = DateTime::parse_from_rfc3339(parts.next()?).ok()?;
+    let _host = parts.next()?;
+    let app = parts.next()?;
+    let proc_id = parts.next()?;
+    let _msg_id = parts.next()?;
+    let message = parts.next().unwrap_or_default();
+    Some((
+        format!(
+            "{} [{}] {message}",
+            timestamp.naive_utc().format("%Y-%m-%d %H:%M:%S"),
+            level(pri, message)
+        ),
+        Some(format!("dyno={app}/{proc_id}")),
+    ))
+}
+
+/// Traite les requêtes successives d'une connexion (keep-alive) et répond
+/// 204 à chacune, comme l'attend Logplex.
+fn serve(stream: TcpStream, tx: &SyncSender<DrainLine>) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+    loop {
+        let mut request_line = String::new();
+        if reader.read_line(&mut request_line)? == 0 {
+            return Ok(());
+        }
+        let mut content_length = 0;
+        loop {
+            let mut header = String::new();
+            reader.read_line(&mut header)?;
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':')
+                && name.eq_ignore_ascii_case("content-length")
+            {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body)?;
+
+        for line in split_frames(&body).iter().filter_map(|f| parse_frame(f)) {
+            if tx.send(line).is_err() {
+                return Ok(());
+            }
+        }
+        writer.write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n")?;
+    }
+}
+
+/// Point de réception d'un drain HTTP Heroku. Le TLS exigé par Heroku est
+/// à terminer en amont (reverse proxy).
+pub struct DrainListener {
+    lines: Receiver<DrainLine>,
+}
+
+impl DrainListener {
+    pub fn bind(addr: &str) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let (tx, lines) = mpsc::sync_channel(CHANNEL_CAPACITY);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let tx = tx.clone();
+                thread::spawn(move || {
+                    if let Err(err) = serve(stream, &tx) {
+                        eprintln!("Connexion du drain interrompue: {err}");
+                    }
+                });
+            }
+        });
+        Ok(DrainListener { lines })
+    }
+}
+
+impl LineSource for DrainListener {
+    fn poll_lines(&mut self) -> io::Result<Vec<DrainLine>> {
+        Ok(self.lines.try_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BODY: &[u8] = b"84 <190>1 2012-11-30T06:45:29+00:00 host app web.3 - State changed from starting to up\n\
+113 <158>1 2012-11-30T06:45:26+00:00 host heroku router - at=error code=H12 desc=\"Request timeout\" method=GET path=/\n";
+
+    #[test]
+    fn splits_octet_counted_frames() {
+        let frames = split_frames(BODY);
+        assert_eq!(frames.len(), 2);
+        assert!(frames[0].ends_with("State changed from starting to up"));
+        assert!(frames[1].starts_with("<158>1"));
+    }
+
+    #[test]
+    fn converts_frames_to_log_lines() {
+        let frames = split_frames(BODY);
+        assert_eq!(
+            parse_frame(&frames[0]).unwrap(),
+            (
+                "2012-11-30 06:45:29 [INFO] State changed from starting to up".to_string(),
+                Some("dyno=app/web.3".to_string())
+            )
+        );
+        let (line, origin) = parse_frame(&frames[1]).unwrap();
+        assert!(line.starts_with("2012-11-30 06:45:26 [ERROR] at=error code=H12"));
+        assert_eq!(origin.as_deref(), Some("dyno=heroku/router"));
+        assert!(parse_frame("not syslog").is_none());
+    }
+}
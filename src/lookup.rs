@@ -0,0 +1,150 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+
+/// `csv:FICHIER:CHAMP=COLONNE`: joint le champ extrait `CHAMP` à la colonne
+/// `COLONNE` d'une table CSV dont la première ligne nomme les colonnes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LookupSpec {
+    pub path: PathBuf,
+    pub field: String,
+    pub column: String,
+}
+
+pub fn parse_lookup(input: &str) -> Result<LookupSpec, String> {
+    let invalid =
+        || format!("Table de correspondance invalide: {input} (ex: csv:hosts.csv:host=hostname)");
+    let rest = input.strip_prefix("csv:").ok_or_else(invalid)?;
+    let (path, join) = rest.rsplit_once(':').ok_or_else(invalid)?;
+    let (field, column) = join.split_once('=').ok_or_else(invalid)?;
+    if path.is_empty() || field.is_empty() || column.is_empty() {
+        return Err(invalid());
+    }
+    Ok(LookupSpec {
+        path: PathBuf::from(path),
+        field: field.to_string(),
+        column: column.to_string(),
+    })
+}
+
+/// Découpe une ligne CSV; les valeurs entre guillemets peuvent contenir des
+/// virgules et des guillemets doublés.
+pub fn split_csv_line(line: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => values.push(std::mem::take(&mut current).trim().to_string()),
+            _ => current.push(c),
+        }
+    }
+    values.push(current.trim().to_string());
+    values
+}
+
+/// Table chargée en mémoire, indexée par la colonne de jointure
+#[derive(Debug)]
+pub struct LookupTable {
+    field: String,
+    rows: HashMap<String, Vec<(String, String)>>,
+}
+
+impl LookupTable {
+    pub fn load(spec: &LookupSpec) -> Result<Self, String> {
+        let raw = fs::read_to_string(&spec.path)
+            .map_err(|e| format!("Impossible de lire la table {}: {e}", spec.path.display()))?;
+        Self::parse(&raw, spec)
+    }
+
+    fn parse(raw: &str, spec: &LookupSpec) -> Result<Self, String> {
+        let mut lines = raw.lines().filter(|l| !l.trim().is_empty());
+        let header = split_csv_line(lines.next().unwrap_or_default());
+        let key = header
+            .iter()
+            .position(|h| *h == spec.column)
+            .ok_or_else(|| {
+                format!(
+                    "Colonne '{}' absente de {}",
+                    spec.column,
+                    spec.path.display()
+                )
+            })?;
+        let mut rows = HashMap::new();
+        for line in lines {
+            let values = split_csv_line(line);
+            let Some(id) = values.get(key) else {
+                continue;
+            };
+            let columns = header
+                .iter()
+                .zip(&values)
+                .enumerate()
+                .filter(|(i, _)| *i != key)
+                .map(|(_, (name, value))| (name.clone(), value.clone()))
+                .collect();
+            // Première occurrence retenue en cas de doublon
+            rows.entry(id.clone()).or_insert(columns);
+        }
+        Ok(LookupTable {
+            field: spec.field.clone(),
+            rows,
+        })
+    }
+
+    /// Ajoute les colonnes de la ligne correspondante aux champs de l'entrée,
+    /// sans écraser ceux déjà extraits du message.
+    pub fn enrich(&self, fields: &mut BTreeMap<String, String>) {
+        let Some(row) = fields.get(&self.field).and_then(|v| self.rows.get(v)) else {
+            return;
+        };
+        for (name, value) in row {
+            fields.entry(name.clone()).or_insert_with(|| value.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn joins_extracted_field_against_csv_rows() {
+        let spec = parse_lookup("csv:C:\\data\\hosts.csv:host=hostname").unwrap();
+        assert_eq!(spec.path, PathBuf::from("C:\\data\\hosts.csv"));
+        assert_eq!(
+            (spec.field.as_str(), spec.column.as_str()),
+            ("host", "hostname")
+        );
+        assert!(parse_lookup("hosts.csv:host=hostname").is_err());
+
+        let table = LookupTable::parse(
+            "hostname,datacenter,owner\nweb-1,par1,\"team, web\"\nweb-1,ams1,x\ndb-1,ams1,\"dba \"\"core\"\"\"\n",
+            &spec,
+        )
+        .unwrap();
+        let mut fields = BTreeMap::from([
+            ("host".to_string(), "web-1".to_string()),
+            ("owner".to_string(), "oncall".to_string()),
+        ]);
+        table.enrich(&mut fields);
+        assert_eq!(fields["datacenter"], "par1");
+        assert_eq!(fields["owner"], "oncall");
+
+        let mut fields = BTreeMap::from([("host".to_string(), "db-1".to_string())]);
+        table.enrich(&mut fields);
+        assert_eq!(fields["owner"], "dba \"core\"");
+
+        let missing = LookupSpec {
+            column: "ip".to_string(),
+            ..spec
+        };
+        assert!(LookupTable::parse("hostname,datacenter\n", &missing).is_err());
+    }
+}
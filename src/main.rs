@@ -1,32 +1,177 @@
-use chrono::NaiveDateTime;
-use clap::{ArgAction, Parser, ValueEnum};
+use chrono::{NaiveDateTime, Timelike};
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
+use colored::Colorize;
 use indicatif::{ProgressBar, ProgressStyle};
 use once_cell::sync::Lazy;
 use prettytable::{Cell, Row, Table};
 use rayon::prelude::*;
 use regex::Regex;
 use serde::Serialize;
-use std::collections::HashMap;
-use std::fs::{self, File};
-use std::io::{BufRead, BufReader};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
 use std::path::{Path, PathBuf};
-use std::time::Instant;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+mod access;
+mod budget;
+mod callers;
+mod chart;
+mod checksums;
+#[cfg(feature = "clickhouse")]
+mod clickhouse_out;
+mod compressed;
+mod cri;
+mod docker;
+#[cfg(feature = "duckdb")]
+mod duckdb_out;
+mod error;
+mod expect;
+mod explain;
+mod export;
+mod fields;
+mod fluent;
+mod follow;
+mod forecast;
+mod forward;
+mod groups;
+mod hints;
+mod incident;
+mod inputs;
+mod journald;
+mod jsonl;
+#[cfg(feature = "k8s")]
+mod k8s;
+#[cfg(feature = "kafka")]
+mod kafka_out;
+mod limits;
+mod locale;
+mod logfmt;
+mod logplex;
+mod lookup;
+mod markers;
+mod metadata;
+#[cfg(feature = "nats")]
+mod nats_source;
+mod nice;
+mod noise;
+#[cfg(feature = "opensearch")]
+mod opensearch_out;
+mod outfile;
+mod pattern;
+mod platform;
+mod presets;
+mod prune;
+mod queries;
+#[cfg(feature = "redis")]
+mod redis_source;
+mod remote;
+mod rotate;
+mod rules;
+#[cfg(feature = "s3")]
+mod s3;
+mod series;
+mod state;
+mod suppress;
+mod syslog;
+mod syslog_out;
+mod theme;
+mod throughput;
+mod timing;
+mod tuning;
+mod verify;
+mod weekly;
+
+use access::HttpStats;
+use budget::{BudgetReport, ErrorBudget};
+use callers::CallSite;
+use checksums::{Digester, ReadDigest};
+use compressed::Codec;
+use error::LoglyzerError;
+use fields::{FieldTop, Pivot};
+use forecast::ErrorForecast;
+use groups::{Dimension, GroupStats};
+use locale::Locale;
+use markers::{Marker, MarkerImpact};
+use metadata::{InputMeta, ReportMetadata};
+use noise::{
+    FirstOccurrence, GrowingError, NoiseTemplate, ResolvedError, SeverityDrift, TemplateScore,
+};
+use rotate::RotatePolicy;
+use rules::{Categorizer, Config, Reclassifier, Tagger};
+use state::Checkpoints;
+use theme::{Theme, ThemeName};
+use timing::{Correlation, GapStats, Span};
+use weekly::WeekOverWeek;
 
 static LOG_RE: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"^(\d{4}-\d{2}-\d{2}\s+\d{2}:\d{2}:\d{2})\s+\[(\w+)\]\s+(.+)$").unwrap()
 });
 
-static LEVEL_COLOR_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?m)\b(ERROR|WARNING)\b").unwrap());
+static LEVEL_COLOR_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)\b(ERROR|WARNING|INFO|DEBUG)\b").unwrap());
+
+/// Codes de sortie stables, rappelés dans `--help`
+const EXIT_ERROR: i32 = 1;
+const EXIT_MISSING_FILE: i32 = 2;
+const EXIT_THRESHOLD: i32 = 3;
+const EXIT_INTERRUPTED: i32 = 130;
+
+const NO_MATCH_MESSAGE: &str = "Aucune entrée ne correspond aux filtres fournis.";
 
-const PARALLEL_THRESHOLD: u64 = 10 * 1024 * 1024; // 10 MB
+/// Clé de groupe des entrées sans aucun tag
+const UNTAGGED: &str = "untagged";
 const PROGRESS_THRESHOLD: u64 = 5 * 1024 * 1024; // 5 MB
 
+/// Levé par le premier Ctrl-C: la lecture s'arrête et l'analyse porte sur
+/// les lignes déjà lues
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::Relaxed)
+}
+
+/// Un premier Ctrl-C interrompt la lecture, un second quitte immédiatement.
+fn install_interrupt_handler() {
+    let _ = ctrlc::set_handler(|| {
+        if INTERRUPTED.swap(true, Ordering::Relaxed) {
+            std::process::exit(EXIT_INTERRUPTED);
+        }
+        eprintln!("\nInterruption: analyse des lignes déjà lues (Ctrl-C à nouveau pour quitter)");
+    });
+}
+
 #[derive(Debug, Parser)]
-#[command(name = "loglyzer", about = "Analyse et filtre des fichiers de logs")]
+#[command(
+    name = "loglyzer",
+    about = "Analyse et filtre des fichiers de logs",
+    subcommand_negates_reqs = true,
+    args_conflicts_with_subcommands = true,
+    after_help = "Codes de sortie:\n  \
+                  0    succès\n  \
+                  1    erreur (arguments, configuration, lecture, sortie)\n  \
+                  2    fichier introuvable\n  \
+                  3    seuil franchi: budget d'erreurs (--error-budget) dépassé, attente\n       \
+                       --expect non tenue ou gabarit d'erreur en hausse (--growth-alert)\n  \
+                  4    aucune entrée retenue, avec --no-match-exit-code 4 (sinon 0)\n  \
+                  130  interrompu par Ctrl-C (résultats partiels)"
+)]
 struct Cli {
-    /// Fichier de log à analyser
-    #[arg(value_name = "LOG_FILE")]
-    input: PathBuf,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Fichiers de log à analyser, réunis avant filtrage et analyse; un répertoire
+    /// désigne tous les fichiers qu'il contient, sous-répertoires compris, `-`
+    /// l'entrée standard (ex: cat app.log | loglyzer -) et une URL http(s)://
+    /// (--features http) ou s3://bucket/clé (--features s3) un log lu au fil
+    /// du téléchargement
+    #[arg(value_name = "LOG_FILE", required_unless_present_any = ["source", "glob", "docker", "journal"])]
+    input: Vec<PathBuf>,
+
+    /// Ajoute les fichiers correspondant au motif, ex: 'logs/**/*.log' (répétable)
+    #[arg(long, value_name = "PATTERN")]
+    glob: Vec<String>,
 
     /// Ne garder que les entrées de niveau ERROR
     #[arg(long, action = ArgAction::SetTrue)]
@@ -48,28 +193,780 @@ struct Cli {
     #[arg(long, value_name = "DATETIME", value_parser = parse_datetime)]
     until: Option<NaiveDateTime>,
 
-    /// Format de sortie (text, json, csv)
+    /// Exclut une plage horaire (maintenance planifiée) des statistiques et des alertes,
+    /// ex: "2024-01-15 02:00..2024-01-15 03:00"; répétable, s'ajoute à exclude_windows de la configuration
+    #[arg(long = "exclude-window", value_name = "START..END", value_parser = parse_exclude_window)]
+    exclude_window: Vec<ExcludeWindow>,
+
+    /// Format de sortie (text, json, csv, vega, jsonl, arrow)
     #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
     format: OutputFormat,
 
-    /// Écrit le résultat dans un fichier au lieu de stdout
+    /// Format des lignes lues: texte `DATE HEURE [NIVEAU] message`, JSON (un objet par
+    /// ligne), logfmt (`clé=valeur`), syslog BSD (`Jan 15 10:30:45 hôte app[pid]: ...`,
+    /// année déduite de la date de modification du fichier) ou RFC 5424
+    /// (`<PRI>1 horodatage hôte app ...`, données structurées en champs), journal d'accès Apache/Nginx
+    /// (Common/Combined Log Format, avec statuts HTTP et chemins les plus demandés),
+    /// fichier `*-json.log` du pilote json-file de Docker, journal CRI des nœuds
+    /// Kubernetes (`/var/log/pods`, lignes partielles recollées) ou export
+    /// `journalctl -o json`
+    #[arg(long, value_enum, value_name = "FORMAT", default_value_t = InputFormat::Text)]
+    input_format: InputFormat,
+
+    /// Clés de l'horodatage, du niveau et du message, avec --input-format json ou logfmt
+    /// (défaut: ts=ts,level=level,msg=msg; `a.b` pour une clé JSON imbriquée)
+    #[arg(long, alias = "json-keys", value_name = "ts=KEY,level=KEY,msg=KEY", value_parser = jsonl::parse_keys)]
+    input_keys: Option<jsonl::InputKeys>,
+
+    /// Regex des lignes, à la place du format texte intégré, avec les groupes nommés
+    /// `ts`, `level` et `msg`; les autres groupes nommés deviennent des champs
+    /// (ex: '^(?P<ts>\S+ \S+) (?P<level>\w+) (?P<msg>.*)$')
+    #[arg(long, value_name = "REGEX", value_parser = pattern::parse_pattern)]
+    pattern: Option<Regex>,
+
+    /// Format strftime du groupe `ts` de --pattern (ex: '%d/%m/%Y %H:%M:%S'); par défaut
+    /// ISO 8601, RFC 3339 ou epoch
+    #[arg(long, value_name = "FORMAT", requires = "pattern")]
+    timestamp_format: Option<String>,
+
+    /// Valeurs les plus fréquentes d'un champ parmi les entrées retenues (N par défaut: --top)
+    #[arg(long, num_args = 1..=2, value_names = ["FIELD", "N"])]
+    top_field: Option<Vec<String>>,
+
+    /// Extrait du message les groupes nommés d'une regex comme champs des entrées,
+    /// ex: 'tenant=(?P<tenant>\w+)', et ajoute au rapport les valeurs les plus
+    /// fréquentes de chacun (répétable)
+    #[arg(long, value_name = "REGEX", value_parser = fields::parse_capture)]
+    capture: Vec<Regex>,
+
+    /// Matrice de comptes: colonnes selon la 1re dimension, lignes selon la 2e
+    /// (level, hour, tag ou nom de champ), ex. `level,component`
+    #[arg(long, value_name = "COL,ROW", value_delimiter = ',')]
+    pivot: Option<Vec<String>>,
+
+    /// Enrichit les champs extraits par jointure sur une table CSV, ex.
+    /// `csv:hosts.csv:host=hostname` ajoute les autres colonnes (datacenter...)
+    /// aux entrées dont le champ host vaut hostname; répétable
+    #[arg(long, value_name = "csv:FILE:FIELD=COLUMN", value_parser = lookup::parse_lookup)]
+    lookup: Vec<lookup::LookupSpec>,
+
+    /// Champs à conserver dans les entrées exportées (jsonl, arrow), séparés par des virgules
+    #[arg(long, value_name = "FIELDS", value_delimiter = ',')]
+    select: Vec<String>,
+
+    /// Écrit le résultat dans un fichier au lieu de stdout (refusé s'il existe déjà)
     #[arg(long, value_name = "FILE")]
     output: Option<PathBuf>,
 
-    /// Force le mode parallèle quel que soit la taille du fichier
+    /// Remplace le fichier --output s'il existe déjà
+    #[arg(long, action = ArgAction::SetTrue, requires = "output", conflicts_with = "append")]
+    force: bool,
+
+    /// Ajoute le résultat à la fin du fichier --output existant
+    #[arg(long, action = ArgAction::SetTrue, requires = "output")]
+    append: bool,
+
+    /// Tient à jour un manifeste SHA256SUMS (format de sha256sum -c) à côté de
+    /// --output et de --chart
+    #[arg(long, action = ArgAction::SetTrue, requires = "output")]
+    checksums: bool,
+
+    /// Force le mode parallèle, choisi sinon d'après le débit d'analyse mesuré sur les
+    /// premiers Mo de chaque fichier
     #[arg(long, action = ArgAction::SetTrue)]
     parallel: bool,
 
+    /// Taille des lectures sur les entrées (ex: 1MB pour NFS, 64KB pour NVMe); 8KB par
+    /// défaut
+    #[arg(long, value_name = "SIZE", value_parser = tuning::parse_buffer_size)]
+    buffer_size: Option<usize>,
+
+    /// Lignes par bloc en mode parallèle, entre deux vérifications de Ctrl-C; choisi
+    /// sinon d'après le débit mesuré
+    #[arg(long, value_name = "N", value_parser = tuning::parse_chunk_size)]
+    chunk_size: Option<usize>,
+
+    /// Rattache les lignes non reconnues (traces de pile Java/Python) au message de
+    /// l'entrée qui les précède au lieu de les compter comme ignorées
+    #[arg(long, action = ArgAction::SetTrue, conflicts_with = "live")]
+    multiline: bool,
+
+    /// Ménage la machine analysée: priorité abaissée, quart des cœurs au plus pour
+    /// l'analyse parallèle et lecture limitée à 16 MB/s
+    #[arg(long, action = ArgAction::SetTrue)]
+    nice: bool,
+
     /// Affiche des informations de performance
     #[arg(long, action = ArgAction::SetTrue)]
     verbose: bool,
+
+    /// Fichier de configuration TOML (règles de catégorisation, ...)
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// N'ajoute pas les presets de ~/.config/loglyzer/presets.d (ou $LOGLYZER_PRESETS_DIR)
+    #[arg(long, action = ArgAction::SetTrue)]
+    no_presets: bool,
+
+    /// Ventile les entrées par dimensions imbriquées: level, hour, tag, category ou
+    /// champ extrait (ex: level,component,hour)
+    #[arg(long, value_name = "DIMENSIONS", value_delimiter = ',', value_parser = groups::parse_dimension)]
+    group_by: Vec<Dimension>,
+
+    /// Suit le fichier et traite les nouvelles lignes au fil de l'eau (comme tail -f)
+    #[arg(long, action = ArgAction::SetTrue, group = "live")]
+    follow: bool,
+
+    /// Reçoit un drain HTTP Heroku (logplex) sur ADDR au lieu de lire un fichier
+    /// (TLS à terminer par un reverse proxy)
+    #[arg(long, value_name = "ADDR", groups = ["live", "source"], conflicts_with = "every")]
+    drain: Option<String>,
+
+    /// Écoute le protocole Forward de Fluentd/Fluent Bit (msgpack sur TCP) sur ADDR
+    #[arg(long, value_name = "ADDR", groups = ["live", "source"], conflicts_with = "every")]
+    fluent: Option<String>,
+
+    /// Consomme un stream Redis (ex: redis://localhost/) au lieu de lire un fichier
+    #[cfg(feature = "redis")]
+    #[arg(
+        long,
+        value_name = "URL",
+        groups = ["live", "source"],
+        conflicts_with = "every",
+        requires = "stream"
+    )]
+    redis: Option<String>,
+
+    /// Nom du stream Redis lu par --redis
+    #[cfg(feature = "redis")]
+    #[arg(long, value_name = "KEY", requires = "redis")]
+    stream: Option<String>,
+
+    /// Consomme un sujet NATS (ex: nats://localhost:4222) au lieu de lire un fichier
+    #[cfg(feature = "nats")]
+    #[arg(
+        long,
+        value_name = "URL",
+        groups = ["live", "source"],
+        conflicts_with = "every",
+        requires = "subject"
+    )]
+    nats: Option<String>,
+
+    /// Sujet NATS lu par --nats (jokers * et > acceptés)
+    #[cfg(feature = "nats")]
+    #[arg(long, value_name = "SUBJECT", requires = "nats")]
+    subject: Option<String>,
+
+    /// Lit --subject via un consommateur ordonné sur ce stream JetStream
+    #[cfg(feature = "nats")]
+    #[arg(long, value_name = "STREAM", requires = "nats")]
+    jetstream: Option<String>,
+
+    /// Plafonne le débit lu sur une source suivie (drain HTTP, Fluentd, Redis, NATS,
+    /// Kubernetes ou fichier) ou sur une entrée distante (http(s)://, s3://), ex: 50MB/s
+    #[arg(long, value_name = "RATE", value_parser = throughput::parse_throughput)]
+    max_throughput: Option<u64>,
+
+    /// Analyse le journal d'un conteneur lu auprès du démon Docker (DOCKER_HOST, socket
+    /// Unix par défaut), comme le fichier de `docker logs` (répétable)
+    #[arg(long, value_name = "CONTAINER")]
+    docker: Vec<String>,
+
+    /// Analyse le journal systemd local, ou les seules entrées de l'unité donnée (ex:
+    /// nginx), à partir de --since en heure locale. Lu en lançant journalctl plutôt
+    /// qu'avec libsystemd: la commande doit être installée
+    #[arg(long, value_name = "UNIT", num_args = 0..=1, default_missing_value = "")]
+    journal: Option<String>,
+
+    /// Sans valeur, suit les logs des pods Kubernetes sélectionnés au lieu d'un fichier;
+    /// avec NAMESPACE/POD[/CONTAINER], analyse le journal de ce pod, demandé à partir
+    /// de --since
+    #[cfg(feature = "k8s")]
+    #[arg(
+        long,
+        value_name = "NAMESPACE/POD[/CONTAINER]",
+        num_args = 0..=1,
+        value_parser = k8s::parse_target,
+        groups = ["live", "source"],
+        conflicts_with = "every"
+    )]
+    k8s: Option<Option<String>>,
+
+    /// Namespace des pods suivis par --k8s sans valeur
+    #[cfg(feature = "k8s")]
+    #[arg(
+        long,
+        value_name = "NAMESPACE",
+        default_value = "default",
+        requires = "k8s"
+    )]
+    namespace: String,
+
+    /// Sélecteur de labels des pods suivis par --k8s sans valeur (ex: app=checkout)
+    #[cfg(feature = "k8s")]
+    #[arg(long, value_name = "SELECTOR", requires = "k8s")]
+    selector: Option<String>,
+
+    /// En mode --follow, affiche une vue rafraîchie en continu (taux d'erreurs, top erreurs)
+    #[arg(long, action = ArgAction::SetTrue, requires = "live")]
+    top_view: bool,
+
+    /// Intervalle de rafraîchissement du mode --follow, en secondes
+    #[arg(long, value_name = "SECONDS", default_value_t = 2)]
+    refresh: u64,
+
+    /// En mode --follow, alerte quand le nombre d'erreurs sur la dernière minute atteint N
+    #[arg(long, value_name = "N", requires = "live")]
+    alert_threshold: Option<usize>,
+
+    /// Nombre d'entrées récentes conservées et restituées avec une alerte
+    #[arg(long, value_name = "N", default_value_t = 20)]
+    recent: usize,
+
+    /// Ajoute les alertes et leurs entrées récentes à ce fichier au lieu de stdout
+    #[arg(long, value_name = "FILE", requires = "alert_threshold")]
+    recent_out: Option<PathBuf>,
+
+    /// En mode --follow, fait tourner le fichier --output par taille ou durée (ex: 100MB, 1h)
+    #[arg(long, value_name = "SIZE|DURATION", value_parser = rotate::parse_rotate, requires = "output")]
+    output_rotate: Option<RotatePolicy>,
+
+    /// Nombre d'anciennes générations de --output conservées lors d'une rotation
+    #[arg(long, value_name = "N", default_value_t = 5)]
+    output_keep: usize,
+
+    /// Réémet les entrées filtrées: syslog RFC 5424 (syslog://hôte:514 en UDP,
+    /// syslog+tcp://hôte:601), JSON vers Kafka (kafka://broker:9092/topic) ou vers un
+    /// data stream OpenSearch (opensearch[+https]://user:pass@hôte:9200/logs-app-default),
+    /// ou insertion par lots dans ClickHouse (clickhouse[+https]://user:pass@hôte:8123/base.table)
+    #[arg(long, value_name = "URL", conflicts_with = "every")]
+    forward: Option<String>,
+
+    /// Avec --forward, au plus N entrées par empreinte (niveau et gabarit du message)
+    /// et par période, ex: 10/min
+    #[arg(long, value_name = "N/PERIOD", value_parser = forward::parse_rate_limit, requires = "forward")]
+    forward_max: Option<forward::RateLimit>,
+
+    /// En suivi continu, enregistre la position de lecture de chaque source dans FILE
+    /// après chaque lot réémis, et la reprend au redémarrage; sinon, y conserve les
+    /// comptes et niveaux par gabarit pour --growth-alert et --severity-drift
+    #[arg(long, value_name = "FILE")]
+    state: Option<PathBuf>,
+
+    /// Signale les gabarits d'erreur en hausse de plus de PCT % depuis l'exécution
+    /// précédente enregistrée dans --state (code de sortie 3)
+    #[arg(long, value_name = "PCT", requires = "state", conflicts_with = "live")]
+    growth_alert: Option<f64>,
+
+    /// Signale les messages passés d'ERROR à WARNING/INFO/DEBUG (ou l'inverse) dans la
+    /// fenêtre, ou depuis l'exécution précédente avec --state
+    #[arg(long, action = ArgAction::SetTrue, conflicts_with = "live")]
+    severity_drift: bool,
+
+    /// Classe les sites d'appel les plus bavards, d'après les jetons `fichier.rs:123`
+    /// des messages ou le champ `caller` des journaux structurés
+    #[arg(long, action = ArgAction::SetTrue)]
+    call_sites: bool,
+
+    /// Affiche les entrées écartées par chaque filtre, dans leur ordre d'application,
+    /// au lieu du rapport (pour comprendre une requête qui ne retient rien)
+    #[arg(long, action = ArgAction::SetTrue, conflicts_with_all = ["live", "every"])]
+    explain: bool,
+
+    /// Affiche sur stdout une seule ligne `clé=valeur` (total, errors, warn, skipped, window)
+    /// au lieu du rapport, quel que soit le format; --output reçoit toujours le rapport
+    #[arg(long, action = ArgAction::SetTrue, conflicts_with_all = ["live", "every", "explain"])]
+    summary_line: bool,
+
+    /// Relance l'analyse complète à intervalle régulier (ex: 15m), rapports horodatés
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration, conflicts_with = "follow")]
+    every: Option<Duration>,
+
+    /// Avec --every, n'émet un rapport que si un niveau a varié d'au moins PCT %
+    #[arg(long, value_name = "PCT", requires = "every")]
+    min_change: Option<f64>,
+
+    /// Au-delà de ce pourcentage de lignes ignorées, suggère le format probable des lignes rejetées
+    #[arg(long, value_name = "PCT", default_value_t = 20.0)]
+    hint_threshold: f64,
+
+    /// Écriture des nombres du rapport texte: virgule décimale et espaces fines (fr-FR)
+    /// ou séparateur de milliers anglo-saxon (en-US); sans option, nombres bruts
+    #[arg(long, value_enum, value_name = "LOCALE")]
+    locale: Option<Locale>,
+
+    /// Sections du rapport texte: full (toutes), standard (sans les séries horaires
+    /// détaillées, le bruit ni les intervalles) ou brief (totaux et 3 premières erreurs)
+    #[arg(long, value_enum, value_name = "LEVEL", default_value = "full")]
+    report: ReportDetail,
+
+    /// Thème de couleurs des niveaux (prioritaire sur la section [colors] de la configuration)
+    #[arg(long, value_enum, value_name = "THEME")]
+    theme: Option<ThemeName>,
+
+    /// Budget d'erreurs toléré, en nombre par période (ex: 500/30d) ou en taux (ex: 0.5%):
+    /// rapporte sa consommation, sa date d'épuisement et les principaux contributeurs
+    #[arg(long, value_name = "N/PERIOD|PCT%", value_parser = budget::parse_error_budget)]
+    error_budget: Option<ErrorBudget>,
+
+    /// Profil attendu, vérifié après l'analyse (ex: 'ERROR<1%,WARNING<5%,INFO>100'):
+    /// bilan sur stderr et code de sortie 3 si une attente n'est pas tenue
+    #[arg(long, value_name = "RULES", value_delimiter = ',', value_parser = expect::parse_expectation)]
+    expect: Vec<expect::Expectation>,
+
+    /// Apparie requêtes et réponses par la clé capturée (premier groupe) de chaque regex
+    /// et rapporte la distribution des temps de réponse et les requêtes orphelines
+    #[arg(long, num_args = 2, value_names = ["REQUEST_RE", "RESPONSE_RE"], value_parser = parse_correlate)]
+    correlate: Vec<Regex>,
+
+    /// Liste les erreurs absentes depuis cette durée à la fin de la fenêtre (--until ou
+    /// dernière entrée), probablement résolues (ex: 30m)
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    resolved_after: Option<Duration>,
+
+    /// Plafonne chaque classement du rapport (erreurs, gabarits, groupes, pivot, chemins...)
+    /// à N éléments, quel que soit le format
+    #[arg(long, value_name = "N", value_parser = parse_limit)]
+    max_top: Option<usize>,
+
+    /// Ne garde que les N derniers seaux de temps (heures, semaines) du rapport
+    #[arg(long, value_name = "N", value_parser = parse_limit)]
+    max_buckets: Option<usize>,
+
+    /// Plafonne les listes d'exemples (indices de format, nouvelles erreurs des marqueurs,
+    /// clés orphelines) à N éléments
+    #[arg(long, value_name = "N", value_parser = parse_limit)]
+    max_examples: Option<usize>,
+
+    /// Code de sortie quand aucune entrée ne correspond aux filtres (4 par convention)
+    #[arg(long, value_name = "N")]
+    no_match_exit_code: Option<i32>,
+
+    /// Trace les erreurs par heure et l'empilement des niveaux dans un fichier .svg ou .png
+    #[arg(long, value_name = "FILE")]
+    chart: Option<PathBuf>,
+
+    /// Écrit les comptes par heure et par niveau dans un CSV long `bucket,level,count`
+    /// (heures vides comprises), à importer tel quel dans un tableur ou R; suit --force
+    /// et --append comme --output
+    #[arg(long, value_name = "FILE")]
+    series_out: Option<PathBuf>,
+
+    /// Écrit les métadonnées du rapport (version, commande, entrées, durée) en une
+    /// ligne JSON, pour les exports --format jsonl qui ne contiennent que des entrées;
+    /// suit --force et --append comme --output
+    #[arg(long, value_name = "FILE")]
+    metadata_out: Option<PathBuf>,
+
+    /// Événements datés (CSV timestamp,label, ex: déploiements) annotés sur les séries
+    /// et encadrés de statistiques avant/après
+    #[arg(long, value_name = "FILE")]
+    markers: Option<PathBuf>,
+
+    /// Durée des fenêtres comparées avant et après chaque marqueur
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration, default_value = "1h", requires = "markers")]
+    marker_window: Duration,
+}
+
+impl Cli {
+    /// Disposition des lignes de `path`, `None` pour une source suivie en continu
+    fn line_format(&self, path: Option<&Path>) -> LineFormat {
+        // Conteneurs et pods arrivent toujours au format json-file, quel que soit --input-format
+        if path.is_some_and(is_container) {
+            return LineFormat::Docker;
+        }
+        if path.and_then(journald::unit).is_some() {
+            return LineFormat::Journal;
+        }
+        if let Some(regex) = &self.pattern {
+            return LineFormat::Pattern(regex.clone(), self.timestamp_format.clone());
+        }
+        match self.input_format {
+            InputFormat::Text => LineFormat::Text,
+            InputFormat::Json => LineFormat::Json(self.input_keys.clone().unwrap_or_default()),
+            InputFormat::Logfmt => LineFormat::Logfmt(self.input_keys.clone().unwrap_or_default()),
+            // En suivi continu, les lignes arrivent au présent
+            InputFormat::Syslog => {
+                LineFormat::Syslog(path.filter(|_| !self.follow).and_then(modified_utc))
+            }
+            InputFormat::Access => LineFormat::Access,
+            InputFormat::Docker => LineFormat::Docker,
+            InputFormat::Cri => LineFormat::Cri,
+            InputFormat::Journal => LineFormat::Journal,
+        }
+    }
+
+    /// Premier fichier d'entrée, le seul en suivi continu
+    fn input(&self) -> &Path {
+        self.input
+            .first()
+            .expect("LOG_FILE est requis hors sous-commande")
+    }
+
+    fn top_field(&self) -> Result<Option<(&str, usize)>, String> {
+        match self.top_field.as_deref() {
+            None => Ok(None),
+            Some([field]) => Ok(Some((field, self.top.max(1)))),
+            Some([field, n]) => {
+                let n = n.parse().ok().filter(|n| *n > 0).ok_or_else(|| {
+                    format!("La valeur de --top-field doit être un entier positif: {n}")
+                })?;
+                Ok(Some((field, n)))
+            }
+            Some(_) => unreachable!("--top-field prend 1 ou 2 valeurs"),
+        }
+    }
+
+    fn pivot(&self) -> Result<Option<(&str, &str)>, String> {
+        match self.pivot.as_deref() {
+            None => Ok(None),
+            Some([columns, rows]) => Ok(Some((columns, rows))),
+            Some(_) => Err("--pivot attend deux dimensions: COL,ROW".to_string()),
+        }
+    }
+
+    /// Vrai si les entrées sont suivies au fil de l'eau plutôt que lues une fois
+    fn live(&self) -> bool {
+        #[cfg(feature = "redis")]
+        if self.redis.is_some() {
+            return true;
+        }
+        #[cfg(feature = "nats")]
+        if self.nats.is_some() {
+            return true;
+        }
+        #[cfg(feature = "k8s")]
+        if let Some(None) = self.k8s {
+            return true;
+        }
+        self.follow || self.drain.is_some() || self.fluent.is_some()
+    }
+
+    /// Valide les options composées que clap ne sait pas vérifier seul
+    fn validate(&self) -> Result<(), String> {
+        self.top_field()?;
+        self.pivot()?;
+        if matches!(self.format, OutputFormat::Duckdb) && self.output.is_none() {
+            return Err("--format duckdb requiert --output (ex: --output logs.duckdb)".to_string());
+        }
+        if matches!(self.format, OutputFormat::Duckdb) && self.append {
+            return Err("--append ne s'applique pas à --format duckdb".to_string());
+        }
+        if self.input_keys.is_some()
+            && !matches!(self.input_format, InputFormat::Json | InputFormat::Logfmt)
+        {
+            return Err("--input-keys s'utilise avec --input-format json ou logfmt".to_string());
+        }
+        if self.pattern.is_some() && self.input_format != InputFormat::Text {
+            return Err("--pattern remplace le format texte: sans --input-format".to_string());
+        }
+        if self.follow && self.input.len() > 1 {
+            return Err("--follow ne suit qu'un seul fichier".to_string());
+        }
+        let stdin = self.input.iter().filter(|p| is_stdin(p)).count();
+        if stdin > 1 {
+            return Err("L'entrée standard (-) ne peut être lue qu'une fois".to_string());
+        }
+        if stdin == 1 {
+            // Lue une seule fois jusqu'à sa fin: ni suivie, ni relue, ni empreinte
+            let refused = [(self.follow, "--follow"), (self.every.is_some(), "--every")];
+            if let Some((_, flag)) = refused.iter().find(|(set, _)| *set) {
+                return Err(format!("{flag} ne s'applique pas à l'entrée standard (-)"));
+            }
+        }
+        if self.max_throughput.is_some()
+            && !self.live()
+            && !self.input.iter().any(|p| remote::is_url(p))
+        {
+            return Err(
+                "--max-throughput s'applique à une source suivie ou à une entrée distante"
+                    .to_string(),
+            );
+        }
+        if self.follow
+            && let Some(url) = self.input.iter().find(|p| remote::is_url(p))
+        {
+            return Err(format!(
+                "--follow ne s'applique pas à l'URL {}",
+                url.display()
+            ));
+        }
+        #[cfg(feature = "k8s")]
+        if matches!(self.k8s, Some(Some(_))) && self.selector.is_some() {
+            return Err("--selector s'applique au suivi des pods (--k8s sans valeur)".to_string());
+        }
+        if self.follow
+            && let Some(container) = self.input.iter().find_map(|p| docker::container(p))
+        {
+            return Err(format!(
+                "--follow ne s'applique pas au conteneur {container}"
+            ));
+        }
+        if self.follow && self.journal.is_some() {
+            return Err("--follow ne s'applique pas au journal systemd".to_string());
+        }
+        if let Some(output) = &self.output
+            && self
+                .input
+                .iter()
+                .any(|input| outfile::same_file(input, output))
+        {
+            return Err(format!(
+                "--output désigne le fichier analysé lui-même: {}",
+                output.display()
+            ));
+        }
+        if let Some(series) = &self.series_out
+            && self
+                .input
+                .iter()
+                .any(|input| outfile::same_file(input, series))
+        {
+            return Err(format!(
+                "--series-out désigne le fichier analysé lui-même: {}",
+                series.display()
+            ));
+        }
+        if let Some(metadata) = &self.metadata_out
+            && self
+                .input
+                .iter()
+                .any(|input| outfile::same_file(input, metadata))
+        {
+            return Err(format!(
+                "--metadata-out désigne le fichier analysé lui-même: {}",
+                metadata.display()
+            ));
+        }
+        Ok(())
+    }
+
+    fn existing_output(&self) -> outfile::Existing {
+        if self.append {
+            outfile::Existing::Append
+        } else if self.force {
+            outfile::Existing::Overwrite
+        } else {
+            outfile::Existing::Refuse
+        }
+    }
+
+    /// Vrai si les champs `clé=valeur` doivent être extraits des messages
+    fn needs_fields(&self) -> bool {
+        self.format.exports_entries()
+            || matches!(self.format, OutputFormat::Duckdb)
+            || self.top_field.is_some()
+            || self.pivot.is_some()
+            || !self.lookup.is_empty()
+            || self.forward.is_some()
+            || self.group_by.iter().any(Dimension::is_field)
+    }
+
+    /// Enrichissement des entrées selon --capture, --lookup et les rendus à champs
+    fn enricher(&self) -> Result<fields::Enricher<'_>, LoglyzerError> {
+        let lookups = self
+            .lookup
+            .iter()
+            .map(lookup::LookupTable::load)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(LoglyzerError::Invalid)?;
+        Ok(fields::Enricher {
+            captures: &self.capture,
+            extract: self.needs_fields(),
+            lookups,
+        })
+    }
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Archive les entrées antérieures à une date et tronque le fichier d'autant
+    Prune {
+        /// Fichier de log à élaguer (réécrit en place)
+        #[arg(value_name = "LOG_FILE")]
+        file: PathBuf,
+
+        /// Archive les entrées strictement antérieures à cette date/heure (YYYY-MM-DD HH:MM:SS)
+        #[arg(long, value_name = "DATETIME", value_parser = parse_datetime)]
+        before: NaiveDateTime,
+
+        /// Fichier d'archive (compressé en gzip si le nom se termine par .gz)
+        #[arg(long, value_name = "FILE")]
+        archive_to: PathBuf,
+    },
+    /// Rapport d'incident: chronologie, groupes d'erreurs et composants touchés
+    Incident {
+        /// Fichier de log couvrant l'incident
+        #[arg(value_name = "LOG_FILE")]
+        file: PathBuf,
+
+        /// Début de la fenêtre de l'incident (YYYY-MM-DD HH:MM:SS)
+        #[arg(long, value_name = "DATETIME", value_parser = parse_datetime)]
+        from: Option<NaiveDateTime>,
+
+        /// Fin de la fenêtre de l'incident (YYYY-MM-DD HH:MM:SS)
+        #[arg(long, value_name = "DATETIME", value_parser = parse_datetime)]
+        to: Option<NaiveDateTime>,
+
+        /// Nombre de groupes d'erreurs détaillés
+        #[arg(long, value_name = "N", default_value_t = 5, value_parser = parse_top)]
+        top: usize,
+    },
+    /// Audit d'intégrité: entrées antidatées, trous de séquence, empreinte signée (JSON)
+    Verify {
+        /// Fichier de log à vérifier
+        #[arg(value_name = "LOG_FILE")]
+        file: PathBuf,
+
+        /// Regex dont le premier groupe capture un numéro de séquence (ex: 'seq=(\d+)')
+        #[arg(long, value_name = "REGEX")]
+        sequence_regex: Option<String>,
+
+        /// Recul toléré avant de signaler une entrée antidatée, en secondes
+        #[arg(long, value_name = "SECONDS", default_value_t = 0)]
+        tolerance: i64,
+
+        /// Clé HMAC de signature du résumé (sinon variable LOGLYZER_VERIFY_KEY)
+        #[arg(long, value_name = "FILE")]
+        key_file: Option<PathBuf>,
+    },
+    /// Enregistre des options d'analyse sous un nom, dans la section [queries] de la configuration
+    SaveQuery {
+        /// Nom de la requête (lettres, chiffres, - et _)
+        name: String,
+
+        /// Fichier de configuration TOML où l'enregistrer (créé au besoin)
+        #[arg(long, value_name = "FILE")]
+        config: PathBuf,
+
+        /// Options d'analyse, sans le fichier de log (ex: --errors-only --search timeout)
+        #[arg(value_name = "ARGS", trailing_var_arg = true, allow_hyphen_values = true, num_args = 1..)]
+        args: Vec<String>,
+    },
+    /// Classe les instructions DEBUG/INFO/WARNING répétitives à rétrograder ou supprimer,
+    /// avec leur site d'appel et le volume économisé
+    SuggestSuppressions {
+        /// Fichier de log à examiner
+        #[arg(value_name = "LOG_FILE")]
+        file: PathBuf,
+
+        /// Nombre d'instructions proposées
+        #[arg(long, value_name = "N", default_value_t = 10, value_parser = parse_top)]
+        top: usize,
+    },
+    /// Lance une requête enregistrée avec save-query sur un fichier
+    Run {
+        /// Nom de la requête
+        name: String,
+
+        /// Fichier de log à analyser
+        #[arg(value_name = "LOG_FILE")]
+        file: PathBuf,
+
+        /// Fichier de configuration contenant la requête
+        #[arg(long, value_name = "FILE")]
+        config: PathBuf,
+
+        /// Options ajoutées à celles de la requête
+        #[arg(
+            value_name = "ARGS",
+            trailing_var_arg = true,
+            allow_hyphen_values = true
+        )]
+        args: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum InputFormat {
+    Text,
+    Json,
+    Logfmt,
+    Syslog,
+    #[value(alias = "combined", alias = "clf")]
+    Access,
+    Docker,
+    Cri,
+    #[value(alias = "journald")]
+    Journal,
+}
+
+/// Analyse d'une ligne selon `--input-format`
+#[derive(Debug, Clone, Default)]
+enum LineFormat {
+    #[default]
+    Text,
+    Json(jsonl::InputKeys),
+    Logfmt(jsonl::InputKeys),
+    /// Date de référence pour l'année des lignes RFC 3164, l'instant présent
+    /// si absente
+    Syslog(Option<NaiveDateTime>),
+    Access,
+    /// Motif de l'utilisateur (`--pattern`) et format de son horodatage
+    Pattern(Regex, Option<String>),
+    Docker,
+    /// Lignes CRI, recollées au préalable par `cri::Reassemble` en lecture de fichier
+    Cri,
+    /// Entrées de `journalctl -o json`
+    Journal,
+}
+
+impl LineFormat {
+    fn parse(&self, line: &str) -> Option<LogEntry> {
+        match self {
+            LineFormat::Text => parse_log_line(line),
+            LineFormat::Json(keys) => jsonl::parse_line(line, keys),
+            LineFormat::Logfmt(keys) => logfmt::parse_line(line, keys),
+            LineFormat::Syslog(reference) => {
+                syslog::parse_line(line, reference.unwrap_or_else(now_utc))
+            }
+            LineFormat::Access => access::parse_line(line),
+            LineFormat::Pattern(regex, timestamp_format) => {
+                pattern::parse_line(line, regex, timestamp_format.as_deref())
+            }
+            LineFormat::Docker => docker::parse_line(line),
+            LineFormat::Cri => cri::parse_line(line),
+            LineFormat::Journal => journald::parse_line(line),
+        }
+    }
+}
+
+/// Niveau de détail du rapport texte (`--report`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ReportDetail {
+    Full,
+    Standard,
+    /// Totaux et erreurs principales, pour un courriel de cron
+    Brief,
 }
 
+/// Erreurs listées par `--report brief`
+const BRIEF_TOP_ERRORS: usize = 3;
+
 #[derive(Debug, Clone, Copy, ValueEnum)]
 enum OutputFormat {
     Text,
     Json,
     Csv,
+    /// Spécification Vega-Lite des séries horaires et par niveau
+    Vega,
+    /// Entrées filtrées, un objet JSON par ligne, champs structurés compris
+    /// (métadonnées du rapport: --metadata-out)
+    Jsonl,
+    /// Flux Arrow IPC des entrées filtrées (pandas, polars, DuckDB),
+    /// métadonnées du rapport dans le schéma
+    Arrow,
+    /// Base DuckDB (tables entries, stats, metadata et inputs) écrite dans
+    /// --output
+    Duckdb,
+}
+
+impl OutputFormat {
+    /// Formats qui exportent les entrées elles-mêmes plutôt qu'un rapport
+    fn exports_entries(self) -> bool {
+        matches!(self, OutputFormat::Jsonl | OutputFormat::Arrow)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
@@ -101,12 +998,22 @@ impl LogLevel {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 struct LogEntry {
     timestamp: String,
     datetime: NaiveDateTime,
     level: LogLevel,
     message: String,
+    tags: Vec<String>,
+    /// Champs structurés (`clé=valeur`), extraits seulement si l'export en a besoin
+    fields: BTreeMap<String, String>,
+}
+
+impl LogEntry {
+    /// Taille de la ligne `TIMESTAMP [LEVEL] message\n` reconstituée, en octets
+    fn line_bytes(&self) -> u64 {
+        (self.timestamp.len() + self.level.as_str().len() + self.message.len() + 5) as u64
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -119,21 +1026,208 @@ struct ErrorFrequency {
 struct LogStats {
     total_entries: usize,
     by_level: HashMap<String, usize>,
+    /// Avec plusieurs fichiers d'entrée, dans l'ordre de la ligne de commande
+    #[serde(skip_serializing_if = "Option::is_none")]
+    by_file: Option<Vec<FileStats>>,
+    /// Version, commande, entrées et durée, pour un rapport qui se suffit à lui-même
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metadata: Option<ReportMetadata>,
+    total_bytes: u64,
+    bytes_by_level: HashMap<String, u64>,
+    bytes_by_hour: HashMap<String, u64>,
+    /// Comptes par heure puis par niveau, tous niveaux confondus
+    entries_by_bucket_by_level: HashMap<String, HashMap<String, usize>>,
     top_errors: Vec<ErrorFrequency>,
     errors_by_hour: HashMap<String, usize>,
     error_rate_by_hour: HashMap<String, f64>,
+    errors_by_category: HashMap<String, usize>,
+    errors_by_category_by_hour: HashMap<String, HashMap<String, usize>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    group_by: Vec<Dimension>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    groups: BTreeMap<String, GroupStats>,
+    /// Période couverte par les entrées retenues
+    time_span: Option<Span>,
+    longest_gap: Option<Span>,
+    inter_arrival: Option<GapStats>,
+    inter_arrival_by_level: HashMap<String, GapStats>,
+    forecast: Option<ErrorForecast>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    week_over_week: Option<WeekOverWeek>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error_budget: Option<BudgetReport>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    markers: Vec<MarkerImpact>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_field: Option<FieldTop>,
+    /// Un relevé par groupe nommé de --capture
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    captured: Vec<FieldTop>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pivot: Option<Pivot>,
+    /// Avec --input-format access
+    #[serde(skip_serializing_if = "Option::is_none")]
+    http: Option<HttpStats>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    correlation: Option<Correlation>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    noise: Vec<NoiseTemplate>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    noise_scores: Vec<TemplateScore>,
+    /// Avec --call-sites
+    #[serde(skip_serializing_if = "Option::is_none")]
+    call_sites: Option<Vec<CallSite>>,
+    /// Types d'erreur apparus le plus récemment, par ordre chronologique
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    first_occurrences: Vec<FirstOccurrence>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resolved: Option<Vec<ResolvedError>>,
+    /// Absent au premier relevé de --state, sans exécution précédente
+    #[serde(skip_serializing_if = "Option::is_none")]
+    growth: Option<GrowthReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    severity_drift: Option<Vec<SeverityDrift>>,
     since: Option<String>,
     until: Option<String>,
+    search: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    excluded_windows: Vec<String>,
     skipped_lines: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    parse_hints: Vec<String>,
+    /// Sections raccourcies par --max-top, --max-buckets ou --max-examples
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    truncated: Vec<&'static str>,
+    /// Lecture interrompue par Ctrl-C: statistiques sur le début du fichier
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    partial: bool,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 struct ParsedLogs {
     entries: Vec<LogEntry>,
     skipped: usize,
+    skipped_samples: Vec<String>,
+    /// Lignes valides écartées par `prepare` pendant la lecture
+    dropped: usize,
+    /// Lecture arrêtée par Ctrl-C avant la fin du fichier
+    interrupted: bool,
+    /// Empreinte de l'entrée lue jusqu'au bout, `None` après une interruption
+    digest: Option<ReadDigest>,
+}
+
+impl ParsedLogs {
+    /// Ajoute la lecture d'un autre fichier d'entrée
+    fn merge(&mut self, other: ParsedLogs) {
+        self.entries.extend(other.entries);
+        self.skipped += other.skipped;
+        let room = hints::SAMPLE_SIZE.saturating_sub(self.skipped_samples.len());
+        self.skipped_samples
+            .extend(other.skipped_samples.into_iter().take(room));
+        self.dropped += other.dropped;
+        self.interrupted |= other.interrupted;
+    }
+}
+
+/// Contribution d'un fichier d'entrée, quand plusieurs sont analysés ensemble
+#[derive(Debug, Serialize)]
+struct FileStats {
+    file: String,
+    entries: usize,
+    errors: usize,
+    warnings: usize,
+    skipped: usize,
+    /// Première erreur du fichier, pour repérer la source qui a flanché en premier
+    #[serde(skip_serializing_if = "Option::is_none")]
+    first_error: Option<String>,
+    errors_by_hour: BTreeMap<String, usize>,
+}
+
+impl FileStats {
+    fn new(path: &Path, parsed: &ParsedLogs) -> Self {
+        let count = |level: LogLevel| parsed.entries.iter().filter(|e| e.level == level).count();
+        let errors = || parsed.entries.iter().filter(|e| e.level == LogLevel::Error);
+        let mut errors_by_hour = BTreeMap::new();
+        for hour in errors().filter_map(|e| extract_hour(&e.timestamp)) {
+            *errors_by_hour.entry(hour).or_insert(0) += 1;
+        }
+        FileStats {
+            file: path.display().to_string(),
+            entries: parsed.entries.len(),
+            errors: count(LogLevel::Error),
+            warnings: count(LogLevel::Warning),
+            skipped: parsed.skipped,
+            first_error: errors()
+                .min_by_key(|e| e.datetime)
+                .map(|e| e.timestamp.clone()),
+            errors_by_hour,
+        }
+    }
+}
+
+/// Traitement appliqué à chaque entrée dès sa lecture; `false` l'écarte
+/// sans qu'elle soit conservée en mémoire.
+type Prepare<'a> = dyn Fn(&mut LogEntry) -> bool + Sync + 'a;
+
+/// Chemin rapide pour la disposition usuelle `AAAA-MM-JJ HH:MM:SS [NIVEAU] message`:
+/// contrôle des positions fixes et conversion des chiffres sans regex ni
+/// `parse_from_str`. `None` renvoie la ligne vers la regex, qui tranche.
+fn parse_fixed_layout(line: &str) -> Option<LogEntry> {
+    let b = line.as_bytes();
+    if b.len() < 23
+        || b[4] != b'-'
+        || b[7] != b'-'
+        || b[10] != b' '
+        || b[13] != b':'
+        || b[16] != b':'
+    {
+        return None;
+    }
+    let num = |range: std::ops::Range<usize>| {
+        b[range].iter().try_fold(0u32, |n, &d| {
+            d.is_ascii_digit().then(|| n * 10 + u32::from(d - b'0'))
+        })
+    };
+    let datetime = chrono::NaiveDate::from_ymd_opt(num(0..4)? as i32, num(5..7)?, num(8..10)?)?
+        .and_hms_opt(num(11..13)?, num(14..16)?, num(17..19)?)?;
+
+    let rest = line[19..].trim_start();
+    if rest.len() == line.len() - 19 {
+        return None;
+    }
+    let rest = rest.strip_prefix('[')?;
+    let close = memchr::memchr(b']', rest.as_bytes())?;
+    let level = &rest[..close];
+    if level.is_empty()
+        || !level
+            .bytes()
+            .all(|c| c.is_ascii_alphanumeric() || c == b'_')
+    {
+        return None;
+    }
+    let after = &rest[close + 1..];
+    let message = after.trim_start();
+    if message.len() == after.len()
+        || message.is_empty()
+        || memchr::memchr(b'\n', message.as_bytes()).is_some()
+    {
+        return None;
+    }
+    Some(LogEntry {
+        timestamp: line[..19].to_string(),
+        datetime,
+        level: LogLevel::from_str(level)?,
+        message: message.to_string(),
+        tags: Vec::new(),
+        fields: BTreeMap::new(),
+    })
 }
 
 fn parse_log_line(line: &str) -> Option<LogEntry> {
+    parse_fixed_layout(line).or_else(|| parse_with_regex(line))
+}
+
+fn parse_with_regex(line: &str) -> Option<LogEntry> {
     LOG_RE.captures(line).and_then(|caps| {
         let ts = caps.get(1)?.as_str();
         let datetime = NaiveDateTime::parse_from_str(ts, "%Y-%m-%d %H:%M:%S").ok()?;
@@ -142,61 +1236,265 @@ fn parse_log_line(line: &str) -> Option<LogEntry> {
             datetime,
             level: LogLevel::from_str(caps.get(2)?.as_str())?,
             message: caps.get(3)?.as_str().to_string(),
+            tags: Vec::new(),
+            fields: BTreeMap::new(),
         })
     })
 }
 
-fn read_logs(path: &Path, pb: Option<&ProgressBar>) -> Result<ParsedLogs, std::io::Error> {
-    let file = File::open(path)?;
-    let mut reader = BufReader::new(file);
+/// Passe une entrée complète à `prepare`: conservée, ou comptée comme écartée
+fn admit(mut entry: LogEntry, prepare: &Prepare, entries: &mut Vec<LogEntry>, dropped: &mut usize) {
+    if prepare(&mut entry) {
+        entries.push(entry);
+    } else {
+        *dropped += 1;
+    }
+}
+
+/// Ligne de continuation (`multiline`): ajoutée au message en attente
+fn append_line(entry: &mut LogEntry, line: &str) {
+    entry.message.push('\n');
+    entry.message.push_str(line);
+}
+
+/// Chemin d'entrée désignant l'entrée standard, comme pour `cat`
+const STDIN: &str = "-";
+
+fn is_stdin(path: &Path) -> bool {
+    path == Path::new(STDIN)
+}
+
+/// Vrai si l'entrée est un journal au format `json-file` lu auprès de Docker
+/// ou de Kubernetes, dont la taille n'est pas connue d'avance
+fn is_container(path: &Path) -> bool {
+    #[cfg(feature = "k8s")]
+    if k8s::pod(path).is_some() {
+        return true;
+    }
+    docker::container(path).is_some()
+}
+
+/// Octets bruts d'une entrée, fichier, entrée standard, URL, conteneur, pod ou
+/// journal systemd, au débit de `--nice` (et de `--max-throughput` pour une URL)
+fn open_input(path: &Path) -> Result<Box<dyn Read>, std::io::Error> {
+    if is_stdin(path) {
+        return Ok(nice::throttle(std::io::stdin().lock()));
+    }
+    if remote::is_url(path) {
+        return Ok(nice::throttle(throughput::throttle_remote(remote::open(
+            path,
+        )?)));
+    }
+    if let Some(container) = docker::container(path) {
+        return Ok(nice::throttle(docker::open(container)?));
+    }
+    #[cfg(feature = "k8s")]
+    if let Some(pod) = k8s::pod(path) {
+        return Ok(nice::throttle(k8s::open(&pod)?));
+    }
+    if let Some(unit) = journald::unit(path) {
+        return Ok(nice::throttle(journald::open(unit)?));
+    }
+    Ok(nice::throttle(platform::open_shared(path)?))
+}
+
+/// Ouvre un fichier de log, décompressé au fil de la lecture s'il commence
+/// par une signature gzip, zstd, bzip2 ou xz, quelle que soit son extension.
+/// La barre de progression suit les octets lus sur le disque, compressés le
+/// cas échéant.
+fn open_log(
+    path: &Path,
+    pb: Option<&ProgressBar>,
+    digester: &Digester,
+) -> Result<Box<dyn BufRead>, std::io::Error> {
+    let file = digester.wrap(open_input(path)?);
+    let raw: Box<dyn Read> = match pb {
+        Some(bar) => Box::new(bar.wrap_read(file)),
+        None => file,
+    };
+    let capacity = tuning::buffer_size();
+    let mut reader = BufReader::with_capacity(capacity, raw);
+    match Codec::detect(reader.fill_buf()?) {
+        Some(codec) => Ok(Box::new(BufReader::with_capacity(
+            capacity,
+            codec.decoder(reader)?,
+        ))),
+        None => Ok(Box::new(reader)),
+    }
+}
+
+fn read_logs(
+    path: &Path,
+    pb: Option<&ProgressBar>,
+    format: &LineFormat,
+    multiline: bool,
+    prepare: &Prepare,
+) -> Result<ParsedLogs, std::io::Error> {
+    let digester = Digester::default();
+    let mut reader = open_log(path, pb, &digester)?;
+    if matches!(format, LineFormat::Cri) {
+        let reassembled = cri::Reassemble::new(reader);
+        reader = Box::new(BufReader::with_capacity(tuning::buffer_size(), reassembled));
+    }
     let mut buf = String::new();
     let mut entries = Vec::new();
     let mut skipped = 0usize;
+    let mut skipped_samples = Vec::new();
+    let mut dropped = 0usize;
+    let mut stopped = false;
+    // Avec `multiline`, entrée en attente de ses lignes de continuation
+    let mut pending: Option<LogEntry> = None;
 
     while reader.read_line(&mut buf)? != 0 {
-        if let Some(entry) = parse_log_line(buf.trim_end_matches(['\n', '\r'])) {
-            entries.push(entry);
-        } else {
-            skipped += 1;
+        if interrupted() {
+            stopped = true;
+            break;
         }
-        if let Some(bar) = pb {
-            bar.inc(buf.len() as u64);
+        let line = buf.trim_end_matches(['\n', '\r']);
+        match (format.parse(line), pending.as_mut()) {
+            (Some(entry), _) if multiline => {
+                if let Some(previous) = pending.replace(entry) {
+                    admit(previous, prepare, &mut entries, &mut dropped);
+                }
+            }
+            (Some(entry), _) => admit(entry, prepare, &mut entries, &mut dropped),
+            (None, Some(entry)) if !line.trim().is_empty() => append_line(entry, line),
+            (None, _) => {
+                skipped += 1;
+                if skipped_samples.len() < hints::SAMPLE_SIZE {
+                    skipped_samples.push(line.to_string());
+                }
+            }
         }
         buf.clear();
     }
-
-    if let Some(bar) = pb {
-        bar.finish_and_clear();
+    if let Some(entry) = pending {
+        admit(entry, prepare, &mut entries, &mut dropped);
     }
 
-    Ok(ParsedLogs { entries, skipped })
+    Ok(ParsedLogs {
+        entries,
+        skipped,
+        skipped_samples,
+        dropped,
+        interrupted: stopped,
+        digest: (!stopped).then(|| digester.digest()),
+    })
 }
 
-fn read_logs_parallel(path: &Path, pb: Option<&ProgressBar>) -> Result<ParsedLogs, std::io::Error> {
-    let file = File::open(path)?;
-    let reader = BufReader::new(file);
+fn read_logs_parallel(
+    path: &Path,
+    pb: Option<&ProgressBar>,
+    format: &LineFormat,
+    multiline: bool,
+    chunk_lines: usize,
+    prepare: &Prepare,
+) -> Result<ParsedLogs, std::io::Error> {
+    let mut raw = Vec::new();
+    tuning::read_all(open_input(path)?, &mut raw)?;
+    let digest = ReadDigest::of(&raw);
+    if let Some(bar) = pb {
+        bar.inc(raw.len() as u64);
+    }
+    if let Some(codec) = Codec::detect(&raw) {
+        let mut text = Vec::new();
+        codec.decoder(raw.as_slice())?.read_to_end(&mut text)?;
+        raw = text;
+    }
+    if matches!(format, LineFormat::Cri) {
+        let mut joined = Vec::new();
+        cri::Reassemble::new(raw.as_slice()).read_to_end(&mut joined)?;
+        raw = joined;
+    }
+    let text = std::str::from_utf8(&raw)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
 
+    // Découpage par memchr (vectorisé), sans allouer une String par ligne
     let mut lines = Vec::new();
+    let mut start = 0;
+    for end in memchr::memchr_iter(b'\n', raw.as_slice()) {
+        lines.push(text[start..end].trim_end_matches('\r'));
+        start = end + 1;
+    }
+    if start < text.len() {
+        lines.push(text[start..].trim_end_matches('\r'));
+    }
+
+    let mut entries = Vec::new();
     let mut skipped = 0usize;
-    for line in reader.lines() {
-        let line = line?;
-        if let Some(bar) = pb {
-            bar.inc(line.len() as u64 + 1);
+    let mut skipped_samples = Vec::new();
+    let mut dropped = 0usize;
+    let mut stopped = false;
+    let mut pending: Option<LogEntry> = None;
+    // Par blocs, pour s'arrêter sur Ctrl-C en gardant un début de fichier
+    for chunk in lines.chunks(chunk_lines) {
+        if interrupted() {
+            stopped = true;
+            break;
+        }
+        if multiline {
+            // Analyse en parallèle, mais les continuations se rattachent dans
+            // l'ordre, éventuellement à une entrée du bloc précédent
+            let parsed: Vec<_> = chunk.par_iter().map(|line| format.parse(line)).collect();
+            let mut complete = Vec::new();
+            for (line, entry) in chunk.iter().zip(parsed) {
+                match (entry, pending.as_mut()) {
+                    (Some(entry), _) => complete.extend(pending.replace(entry)),
+                    (None, Some(entry)) if !line.trim().is_empty() => append_line(entry, line),
+                    (None, _) => {
+                        skipped += 1;
+                        if skipped_samples.len() < hints::SAMPLE_SIZE {
+                            skipped_samples.push(line.to_string());
+                        }
+                    }
+                }
+            }
+            let kept: Vec<_> = complete
+                .into_par_iter()
+                .map(|mut entry| prepare(&mut entry).then_some(entry))
+                .collect();
+            for entry in kept {
+                match entry {
+                    Some(entry) => entries.push(entry),
+                    None => dropped += 1,
+                }
+            }
+            continue;
+        }
+        // None: ligne non reconnue; Some(None): entrée écartée par `prepare`
+        let parsed: Vec<_> = chunk
+            .par_iter()
+            .map(|line| {
+                format
+                    .parse(line)
+                    .map(|mut entry| prepare(&mut entry).then_some(entry))
+            })
+            .collect();
+        for (line, entry) in chunk.iter().zip(parsed) {
+            match entry {
+                Some(Some(entry)) => entries.push(entry),
+                Some(None) => dropped += 1,
+                None => {
+                    skipped += 1;
+                    if skipped_samples.len() < hints::SAMPLE_SIZE {
+                        skipped_samples.push(line.to_string());
+                    }
+                }
+            }
         }
-        lines.push(line);
     }
-
-    if let Some(bar) = pb {
-        bar.finish_and_clear();
+    if let Some(entry) = pending {
+        admit(entry, prepare, &mut entries, &mut dropped);
     }
 
-    let entries: Vec<_> = lines
-        .par_iter()
-        .filter_map(|line| parse_log_line(line))
-        .collect();
-    skipped += lines.len().saturating_sub(entries.len());
-
-    Ok(ParsedLogs { entries, skipped })
+    Ok(ParsedLogs {
+        entries,
+        skipped,
+        skipped_samples,
+        dropped,
+        interrupted: stopped,
+        digest: (!stopped).then_some(digest),
+    })
 }
 
 fn analyze_logs(
@@ -205,18 +1503,47 @@ fn analyze_logs(
     since: Option<NaiveDateTime>,
     until: Option<NaiveDateTime>,
     skipped: usize,
+    categorizer: &Categorizer,
+    group_by: &[Dimension],
 ) -> LogStats {
     let mut by_level = HashMap::new();
+    let mut total_bytes = 0;
+    let mut bytes_by_level = HashMap::new();
+    let mut bytes_by_hour = HashMap::new();
+    let mut entries_by_bucket_by_level: HashMap<String, HashMap<String, usize>> = HashMap::new();
     let mut error_messages = HashMap::new();
     let mut errors_by_hour = HashMap::new();
+    let mut errors_by_category = HashMap::new();
+    let mut errors_by_category_by_hour: HashMap<String, HashMap<String, usize>> = HashMap::new();
+    let mut groups = BTreeMap::new();
 
     for entry in entries {
         let level_name = entry.level.as_str().to_string();
         *by_level.entry(level_name.clone()).or_insert(0) += 1;
+        let bytes = entry.line_bytes();
+        total_bytes += bytes;
+        *bytes_by_level.entry(level_name.clone()).or_insert(0) += bytes;
+        if let Some(hour) = extract_hour(&entry.timestamp) {
+            *entries_by_bucket_by_level
+                .entry(hour.clone())
+                .or_default()
+                .entry(level_name.clone())
+                .or_insert(0) += 1;
+            *bytes_by_hour.entry(hour).or_insert(0) += bytes;
+        }
+
+        groups::add(&mut groups, entry, group_by, categorizer);
 
         if entry.level == LogLevel::Error {
             *error_messages.entry(entry.message.clone()).or_insert(0) += 1;
+            let category = categorizer.categorize(&entry.message);
+            *errors_by_category.entry(category.to_string()).or_insert(0) += 1;
             if let Some(hour) = extract_hour(&entry.timestamp) {
+                *errors_by_category_by_hour
+                    .entry(category.to_string())
+                    .or_default()
+                    .entry(hour.clone())
+                    .or_insert(0) += 1;
                 *errors_by_hour.entry(hour).or_insert(0) += 1;
             }
         }
@@ -227,7 +1554,7 @@ fn analyze_logs(
         .map(|(message, count)| ErrorFrequency { message, count })
         .collect();
 
-    top_errors.sort_by(|a, b| b.count.cmp(&a.count));
+    top_errors.sort_by_key(|e| std::cmp::Reverse(e.count));
     top_errors.truncate(top_n.max(1));
 
     let error_rate_by_hour = if entries.is_empty() {
@@ -239,18 +1566,63 @@ fn analyze_logs(
             .collect()
     };
 
+    let (inter_arrival, inter_arrival_by_level) = timing::inter_arrival(entries);
+    let top_messages: Vec<&str> = top_errors.iter().map(|e| e.message.as_str()).collect();
+    let week_over_week = weekly::week_over_week(entries, &top_messages);
+
     LogStats {
         total_entries: entries.len(),
         by_level,
+        by_file: None,
+        metadata: None,
+        total_bytes,
+        bytes_by_level,
+        bytes_by_hour,
+        entries_by_bucket_by_level,
         top_errors,
         errors_by_hour,
         error_rate_by_hour,
+        errors_by_category,
+        errors_by_category_by_hour,
+        group_by: group_by.to_vec(),
+        groups,
+        time_span: timing::time_span(entries),
+        longest_gap: timing::longest_gap(entries),
+        inter_arrival,
+        inter_arrival_by_level,
+        forecast: forecast::forecast_errors(entries),
+        week_over_week,
+        error_budget: None,
+        markers: Vec::new(),
+        top_field: None,
+        captured: Vec::new(),
+        pivot: None,
+        http: None,
+        correlation: None,
+        noise: noise::noisy_templates(entries, top_n),
+        noise_scores: noise::scored_templates(entries, top_n),
+        call_sites: None,
+        first_occurrences: noise::first_occurrences(entries, top_n),
+        resolved: None,
+        growth: None,
+        severity_drift: None,
         since: since.map(|d| d.format("%Y-%m-%d %H:%M:%S").to_string()),
         until: until.map(|d| d.format("%Y-%m-%d %H:%M:%S").to_string()),
+        search: None,
+        excluded_windows: Vec::new(),
         skipped_lines: skipped,
+        truncated: Vec::new(),
+        parse_hints: Vec::new(),
+        partial: false,
     }
 }
 
+fn truncate_to_hour(dt: NaiveDateTime) -> NaiveDateTime {
+    dt.with_minute(0)
+        .and_then(|d| d.with_second(0))
+        .unwrap_or(dt)
+}
+
 fn extract_hour(ts: &str) -> Option<String> {
     let mut parts = ts.split_whitespace();
     let _date = parts.next()?;
@@ -259,23 +1631,119 @@ fn extract_hour(ts: &str) -> Option<String> {
     Some(format!("{hour}:00"))
 }
 
-fn render_text(stats: &LogStats, top_n: usize) -> String {
+/// Section « Report metadata » du rendu texte, présente à tous les niveaux de rapport
+fn render_metadata(output: &mut String, meta: &ReportMetadata, locale: Locale) {
+    use std::fmt::Write;
+
+    writeln!(output, "\nReport metadata:").unwrap();
+    writeln!(output, "  loglyzer {}", meta.version).unwrap();
+    writeln!(output, "  Command: {}", meta.command_line).unwrap();
+    let host = meta.host.as_deref().unwrap_or("?");
+    writeln!(
+        output,
+        "  Generated: {} UTC on {host} in {} ms",
+        meta.generated_at,
+        locale.int(meta.wall_time_ms)
+    )
+    .unwrap();
+    let mut input_table = Table::new();
+    input_table.add_row(Row::new(vec![
+        Cell::new("Input"),
+        Cell::new("Size"),
+        Cell::new("SHA-256"),
+    ]));
+    for input in &meta.inputs {
+        input_table.add_row(Row::new(vec![
+            Cell::new(&input.file),
+            Cell::new(&input.bytes.map(|b| locale.bytes(b)).unwrap_or_default()),
+            Cell::new(input.sha256.as_deref().unwrap_or("-")),
+        ]));
+    }
+    write!(output, "{input_table}").unwrap();
+}
+
+fn render_text(
+    stats: &LogStats,
+    top_n: usize,
+    theme: &Theme,
+    locale: Locale,
+    detail: ReportDetail,
+) -> String {
     use std::fmt::Write;
 
+    let brief = detail == ReportDetail::Brief;
+    let full = detail == ReportDetail::Full;
+    let top_n = if brief {
+        top_n.min(BRIEF_TOP_ERRORS)
+    } else {
+        top_n
+    };
+
     let mut output = String::new();
     writeln!(output, "\n Log Analysis Results").unwrap();
     writeln!(output, "========================\n").unwrap();
-    writeln!(output, "Total entries: {}\n", stats.total_entries).unwrap();
+    writeln!(
+        output,
+        "Total entries: {} ({})\n",
+        locale.int(stats.total_entries),
+        locale.bytes(stats.total_bytes)
+    )
+    .unwrap();
+    for (label, span) in [
+        ("Time span", &stats.time_span),
+        ("Longest gap", &stats.longest_gap),
+    ] {
+        if let Some(span) = span {
+            writeln!(
+                output,
+                "{label}: {} -> {} ({})",
+                span.start,
+                span.end,
+                locale.duration(span.seconds)
+            )
+            .unwrap();
+        }
+    }
+    if stats.time_span.is_some() {
+        writeln!(output).unwrap();
+    }
+    if stats.partial {
+        writeln!(
+            output,
+            "⚠️  Résultats partiels: lecture interrompue (Ctrl-C) avant la fin du fichier\n"
+        )
+        .unwrap();
+    }
     if stats.skipped_lines > 0 {
         writeln!(
             output,
             "Lignes ignorées (format invalide): {}\n",
-            stats.skipped_lines
+            locale.int(stats.skipped_lines)
+        )
+        .unwrap();
+    }
+    if !stats.parse_hints.is_empty() {
+        writeln!(output, "Formats probables des lignes ignorées:").unwrap();
+        for hint in &stats.parse_hints {
+            writeln!(output, "- {hint}").unwrap();
+        }
+        writeln!(output).unwrap();
+    }
+
+    if !stats.truncated.is_empty() {
+        writeln!(
+            output,
+            "Sections tronquées (--max-top, --max-buckets, --max-examples): {}\n",
+            stats.truncated.join(", ")
         )
         .unwrap();
     }
 
-    if stats.since.is_some() || stats.until.is_some() {
+    if stats.since.is_some()
+        || stats.until.is_some()
+        || stats.search.is_some()
+        || !stats.excluded_windows.is_empty()
+    {
         writeln!(output, "Filtres appliqués:").unwrap();
         if let Some(s) = &stats.since {
             writeln!(output, "- Depuis : {s}").unwrap();
@@ -283,6 +1751,12 @@ fn render_text(stats: &LogStats, top_n: usize) -> String {
         if let Some(u) = &stats.until {
             writeln!(output, "- Jusqu'à : {u}").unwrap();
         }
+        if let Some(term) = &stats.search {
+            writeln!(output, "- Recherche : {term}").unwrap();
+        }
+        for window in &stats.excluded_windows {
+            writeln!(output, "- Exclu : {window}").unwrap();
+        }
         writeln!(output).unwrap();
     }
 
@@ -292,6 +1766,8 @@ fn render_text(stats: &LogStats, top_n: usize) -> String {
         Cell::new("Level"),
         Cell::new("Count"),
         Cell::new("Percentage"),
+        Cell::new("Bytes"),
+        Cell::new("Bytes %"),
     ]));
 
     let mut levels: Vec<_> = stats.by_level.iter().collect();
@@ -303,16 +1779,82 @@ fn render_text(stats: &LogStats, top_n: usize) -> String {
         } else {
             0.0
         };
+        let bytes = stats.bytes_by_level.get(level).copied().unwrap_or(0);
         table.add_row(Row::new(vec![
             Cell::new(level),
-            Cell::new(&count.to_string()),
-            Cell::new(&format!("{:.1}%", percentage)),
+            Cell::new(&locale.int(count)),
+            Cell::new(&locale.pct(percentage, 1)),
+            Cell::new(&locale.bytes(bytes)),
+            Cell::new(&locale.pct(bytes as f64 / stats.total_bytes.max(1) as f64 * 100.0, 1)),
         ]));
     }
     let table_str = table.to_string();
-    let table_str = colorize_levels(&table_str);
+    let table_str = colorize_levels(&table_str, theme);
     writeln!(output, "{table_str}").unwrap();
 
+    if let Some(files) = &stats.by_file
+        && !brief
+    {
+        writeln!(output, "\nBy file:").unwrap();
+        let mut file_table = Table::new();
+        file_table.add_row(Row::new(vec![
+            Cell::new("File"),
+            Cell::new("Entries"),
+            Cell::new("Share"),
+            Cell::new("Errors"),
+            Cell::new("Error share"),
+            Cell::new("Warnings"),
+            Cell::new("Skipped"),
+            Cell::new("First error"),
+        ]));
+        let total = |count: fn(&FileStats) -> usize| files.iter().map(count).sum::<usize>();
+        let (all_entries, all_errors) = (total(|f| f.entries), total(|f| f.errors));
+        let share = |n: usize, of: usize| locale.pct(n as f64 / of.max(1) as f64 * 100.0, 1);
+        for f in files {
+            file_table.add_row(Row::new(vec![
+                Cell::new(&f.file),
+                Cell::new(&locale.int(f.entries)),
+                Cell::new(&share(f.entries, all_entries)),
+                Cell::new(&locale.int(f.errors)),
+                Cell::new(&share(f.errors, all_errors)),
+                Cell::new(&locale.int(f.warnings)),
+                Cell::new(&locale.int(f.skipped)),
+                Cell::new(f.first_error.as_deref().unwrap_or("-")),
+            ]));
+        }
+        let first_error = files.iter().filter_map(|f| f.first_error.as_deref()).min();
+        file_table.add_row(Row::new(vec![
+            Cell::new("Total"),
+            Cell::new(&locale.int(all_entries)),
+            Cell::new(""),
+            Cell::new(&locale.int(all_errors)),
+            Cell::new(""),
+            Cell::new(&locale.int(total(|f| f.warnings))),
+            Cell::new(&locale.int(total(|f| f.skipped))),
+            Cell::new(first_error.unwrap_or("-")),
+        ]));
+        write!(output, "{file_table}").unwrap();
+
+        let mut hours: Vec<_> = files.iter().flat_map(|f| f.errors_by_hour.keys()).collect();
+        hours.sort();
+        hours.dedup();
+        if full && !hours.is_empty() {
+            writeln!(output, "\nErrors by file and hour:").unwrap();
+            let mut header = vec![Cell::new("File")];
+            header.extend(hours.iter().map(|h| Cell::new(h)));
+            let mut series_table = Table::new();
+            series_table.add_row(Row::new(header));
+            for f in files {
+                let mut row = vec![Cell::new(&f.file)];
+                row.extend(hours.iter().map(|h| {
+                    Cell::new(&locale.int(f.errors_by_hour.get(*h).copied().unwrap_or(0)))
+                }));
+                series_table.add_row(Row::new(row));
+            }
+            write!(output, "{series_table}").unwrap();
+        }
+    }
+
     if !stats.top_errors.is_empty() {
         writeln!(output, "\nTop errors (max {top_n}):").unwrap();
         let mut error_table = Table::new();
@@ -321,35 +1863,216 @@ fn render_text(stats: &LogStats, top_n: usize) -> String {
             Cell::new("Occurrences"),
         ]));
 
-        for err in &stats.top_errors {
+        for err in stats.top_errors.iter().take(top_n) {
             error_table.add_row(Row::new(vec![
                 Cell::new(&err.message),
-                Cell::new(&err.count.to_string()),
+                Cell::new(&locale.int(err.count)),
+            ]));
+        }
+
+        let error_table = error_table.to_string();
+        match stats.search.as_deref().map(search_regex) {
+            Some(re) => {
+                // Surligne le terme recherché hors en-tête, après rendu pour
+                // ne pas fausser la largeur des colonnes
+                let (header, rows) = split_header(&error_table);
+                let rows = highlight(rows, &re, |m| m.black().on_yellow().to_string());
+                writeln!(output, "{header}{rows}").unwrap();
+            }
+            None => writeln!(output, "{error_table}").unwrap(),
+        }
+    }
+    if brief {
+        if let Some(meta) = &stats.metadata {
+            render_metadata(&mut output, meta, locale);
+        }
+        return output;
+    }
+
+    let field_table = |top: &FieldTop| {
+        let mut field_table = Table::new();
+        field_table.add_row(Row::new(vec![
+            Cell::new("Value"),
+            Cell::new("Count"),
+            Cell::new("Percentage"),
+        ]));
+
+        for v in &top.values {
+            field_table.add_row(Row::new(vec![
+                Cell::new(&v.value),
+                Cell::new(&locale.int(v.count)),
+                Cell::new(&locale.pct(v.count as f64 / top.matched.max(1) as f64 * 100.0, 1)),
+            ]));
+        }
+        field_table
+    };
+    if let Some(top) = &stats.top_field {
+        writeln!(
+            output,
+            "\nTop values of field {} ({} of {} entries):",
+            top.field,
+            locale.int(top.matched),
+            locale.int(stats.total_entries)
+        )
+        .unwrap();
+        writeln!(output, "{}", field_table(top)).unwrap();
+    }
+    for top in &stats.captured {
+        writeln!(
+            output,
+            "\nCaptured field {} ({} of {} entries):",
+            top.field,
+            locale.int(top.matched),
+            locale.int(stats.total_entries)
+        )
+        .unwrap();
+        writeln!(output, "{}", field_table(top)).unwrap();
+    }
+
+    if let Some(pivot) = &stats.pivot {
+        writeln!(output, "\nPivot {} x {}:", pivot.rows, pivot.columns).unwrap();
+        let mut header = vec![Cell::new(&pivot.rows)];
+        header.extend(pivot.column_keys.iter().map(|c| Cell::new(c)));
+        header.push(Cell::new("Total"));
+        let mut pivot_table = Table::new();
+        pivot_table.add_row(Row::new(header));
+
+        for (row_key, cells) in &pivot.counts {
+            let mut row = vec![Cell::new(row_key)];
+            row.extend(
+                pivot
+                    .column_keys
+                    .iter()
+                    .map(|c| Cell::new(&locale.int(cells.get(c).copied().unwrap_or(0)))),
+            );
+            row.push(Cell::new(&locale.int(cells.values().sum::<usize>())));
+            pivot_table.add_row(Row::new(row));
+        }
+
+        writeln!(
+            output,
+            "{}",
+            colorize_levels(&pivot_table.to_string(), theme)
+        )
+        .unwrap();
+    }
+
+    if let Some(http) = &stats.http {
+        let (status_table, path_table) = access::render_tables(http, locale);
+        writeln!(output, "\nHTTP status codes:").unwrap();
+        write!(output, "{status_table}").unwrap();
+        writeln!(output, "\nTop request paths (max {top_n}):").unwrap();
+        write!(output, "{path_table}").unwrap();
+    }
+
+    if !stats.errors_by_category.is_empty() {
+        writeln!(output, "\nErrors by category:").unwrap();
+        let mut category_table = Table::new();
+        category_table.add_row(Row::new(vec![Cell::new("Category"), Cell::new("Count")]));
+
+        let mut categories: Vec<_> = stats.errors_by_category.iter().collect();
+        categories.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+
+        for (category, count) in categories {
+            category_table.add_row(Row::new(vec![
+                Cell::new(category),
+                Cell::new(&locale.int(count)),
             ]));
         }
 
-        writeln!(output, "{error_table}").unwrap();
+        writeln!(output, "{category_table}").unwrap();
+    }
+
+    if full && !stats.errors_by_category_by_hour.is_empty() {
+        writeln!(output, "\nErrors by category and hour:").unwrap();
+        let mut hours: Vec<_> = stats.errors_by_hour.keys().collect();
+        hours.sort();
+
+        let mut header = vec![Cell::new("Category")];
+        header.extend(hours.iter().map(|h| Cell::new(h)));
+        let mut series_table = Table::new();
+        series_table.add_row(Row::new(header));
+
+        let mut categories: Vec<_> = stats.errors_by_category_by_hour.iter().collect();
+        categories.sort_by(|a, b| a.0.cmp(b.0));
+
+        for (category, by_hour) in categories {
+            let mut row = vec![Cell::new(category)];
+            row.extend(
+                hours
+                    .iter()
+                    .map(|h| Cell::new(&locale.int(by_hour.get(*h).copied().unwrap_or(0)))),
+            );
+            series_table.add_row(Row::new(row));
+        }
+
+        writeln!(output, "{series_table}").unwrap();
+    }
+
+    if !stats.group_by.is_empty() {
+        let names: Vec<&str> = stats.group_by.iter().map(Dimension::name).collect();
+        writeln!(output, "\nBreakdown by {}:", names.join(", ")).unwrap();
+        let mut level_names: Vec<_> = stats.by_level.keys().collect();
+        level_names.sort();
+        let group_table =
+            groups::render_table(&stats.groups, &stats.group_by, &level_names, locale);
+
+        writeln!(
+            output,
+            "{}",
+            colorize_levels(&group_table.to_string(), theme)
+        )
+        .unwrap();
     }
 
     if !stats.errors_by_hour.is_empty() {
         writeln!(output, "\nErrors by hour:").unwrap();
         let mut hour_table = Table::new();
-        hour_table.add_row(Row::new(vec![Cell::new("Hour"), Cell::new("Count")]));
+        let mut header = vec![Cell::new("Hour"), Cell::new("Count")];
+        if !stats.markers.is_empty() {
+            header.push(Cell::new("Markers"));
+        }
+        hour_table.add_row(Row::new(header));
 
         let mut hours: Vec<_> = stats.errors_by_hour.iter().collect();
         hours.sort_by(|a, b| a.0.cmp(b.0));
 
         for (hour, count) in hours {
-            hour_table.add_row(Row::new(vec![
+            let mut row = vec![Cell::new(hour), Cell::new(&locale.int(count))];
+            if !stats.markers.is_empty() {
+                let labels: Vec<&str> = stats
+                    .markers
+                    .iter()
+                    .filter(|m| extract_hour(&m.at).as_deref() == Some(hour.as_str()))
+                    .map(|m| m.label.as_str())
+                    .collect();
+                row.push(Cell::new(&labels.join(", ")));
+            }
+            hour_table.add_row(Row::new(row));
+        }
+
+        writeln!(output, "{hour_table}").unwrap();
+    }
+
+    if full && !stats.bytes_by_hour.is_empty() {
+        writeln!(output, "\nVolume by hour:").unwrap();
+        let mut volume_table = Table::new();
+        volume_table.add_row(Row::new(vec![Cell::new("Hour"), Cell::new("Bytes")]));
+
+        let mut hours: Vec<_> = stats.bytes_by_hour.iter().collect();
+        hours.sort_by(|a, b| a.0.cmp(b.0));
+
+        for (hour, bytes) in hours {
+            volume_table.add_row(Row::new(vec![
                 Cell::new(hour),
-                Cell::new(&count.to_string()),
+                Cell::new(&locale.bytes(*bytes)),
             ]));
         }
 
-        writeln!(output, "{hour_table}").unwrap();
+        writeln!(output, "{volume_table}").unwrap();
     }
 
-    if !stats.error_rate_by_hour.is_empty() {
+    if full && !stats.error_rate_by_hour.is_empty() {
         writeln!(output, "\nError rate by hour:").unwrap();
         let mut rate_table = Table::new();
         rate_table.add_row(Row::new(vec![Cell::new("Hour"), Cell::new("Error %")]));
@@ -360,13 +2083,385 @@ fn render_text(stats: &LogStats, top_n: usize) -> String {
         for (hour, rate) in hours {
             rate_table.add_row(Row::new(vec![
                 Cell::new(hour),
-                Cell::new(&format!("{:.2}%", rate)),
+                Cell::new(&locale.pct(*rate, 2)),
             ]));
         }
 
         writeln!(output, "{rate_table}").unwrap();
     }
 
+    if let Some(wow) = &stats.week_over_week {
+        writeln!(output, "\nWeek-over-week:").unwrap();
+        let mut header = vec![Cell::new("Level / Error")];
+        header.extend(wow.weeks.iter().map(|w| Cell::new(w)));
+        header.push(Cell::new("Change"));
+        let mut wow_table = Table::new();
+        wow_table.add_row(Row::new(header));
+
+        for series in wow.by_level.iter().chain(&wow.by_error) {
+            let mut row = vec![Cell::new(&series.key)];
+            row.extend(series.counts.iter().map(|c| Cell::new(&locale.int(c))));
+            row.push(Cell::new(
+                &series
+                    .change_pct
+                    .map(|p| locale.percent(locale.signed(p, 1)))
+                    .unwrap_or_else(|| "-".to_string()),
+            ));
+            wow_table.add_row(Row::new(row));
+        }
+
+        writeln!(output, "{}", colorize_levels(&wow_table.to_string(), theme)).unwrap();
+    }
+
+    if full && !stats.noise.is_empty() {
+        writeln!(output, "\nDEBUG/INFO noise (estimated bytes):").unwrap();
+        let mut noise_table = Table::new();
+        noise_table.add_row(Row::new(vec![
+            Cell::new("Level"),
+            Cell::new("Template"),
+            Cell::new("Count"),
+            Cell::new("Bytes"),
+            Cell::new("Share"),
+        ]));
+
+        for t in &stats.noise {
+            noise_table.add_row(Row::new(vec![
+                Cell::new(&t.level),
+                Cell::new(&t.template),
+                Cell::new(&locale.int(t.count)),
+                Cell::new(&locale.int(t.bytes)),
+                Cell::new(&locale.pct(t.share_pct, 1)),
+            ]));
+        }
+
+        writeln!(
+            output,
+            "{}",
+            colorize_levels(&noise_table.to_string(), theme)
+        )
+        .unwrap();
+    }
+
+    if full && !stats.noise_scores.is_empty() {
+        writeln!(output, "\nNoise score by template:").unwrap();
+        let mut score_table = Table::new();
+        score_table.add_row(Row::new(vec![
+            Cell::new("Level"),
+            Cell::new("Template"),
+            Cell::new("Count"),
+            Cell::new("Variability"),
+            Cell::new("Score"),
+        ]));
+
+        for s in &stats.noise_scores {
+            score_table.add_row(Row::new(vec![
+                Cell::new(&s.level),
+                Cell::new(&s.template),
+                Cell::new(&locale.int(s.count)),
+                Cell::new(&locale.dec(s.variability, 2)),
+                Cell::new(&locale.dec(s.score, 1)),
+            ]));
+        }
+
+        writeln!(
+            output,
+            "{}",
+            colorize_levels(&score_table.to_string(), theme)
+        )
+        .unwrap();
+    }
+
+    if let Some(sites) = &stats.call_sites {
+        writeln!(output, "\nNoisiest call sites (max {top_n}):").unwrap();
+        if sites.is_empty() {
+            writeln!(output, "None: no `file:line` caller found in messages.").unwrap();
+        } else {
+            let mut site_table = Table::new();
+            site_table.add_row(Row::new(vec![
+                Cell::new("Call site"),
+                Cell::new("Count"),
+                Cell::new("Share"),
+                Cell::new("Errors"),
+                Cell::new("Bytes"),
+            ]));
+            for s in sites {
+                site_table.add_row(Row::new(vec![
+                    Cell::new(&s.site),
+                    Cell::new(&locale.int(s.count)),
+                    Cell::new(&locale.pct(s.share_pct, 1)),
+                    Cell::new(&locale.int(s.errors)),
+                    Cell::new(&locale.bytes(s.bytes)),
+                ]));
+            }
+            write!(output, "{site_table}").unwrap();
+        }
+    }
+
+    if full && !stats.first_occurrences.is_empty() {
+        writeln!(output, "\nNew error types (first occurrence):").unwrap();
+        let mut timeline_table = Table::new();
+        timeline_table.add_row(Row::new(vec![
+            Cell::new("First seen"),
+            Cell::new("Count"),
+            Cell::new("Error template"),
+        ]));
+        for f in &stats.first_occurrences {
+            timeline_table.add_row(Row::new(vec![
+                Cell::new(&f.first_seen),
+                Cell::new(&locale.int(f.count)),
+                Cell::new(&f.template),
+            ]));
+        }
+        write!(output, "{timeline_table}").unwrap();
+    }
+
+    if let Some(resolved) = &stats.resolved {
+        writeln!(output, "\nLikely resolved errors (no longer occurring):").unwrap();
+        if resolved.is_empty() {
+            writeln!(output, "None: every error type occurred recently.").unwrap();
+        } else {
+            let mut resolved_table = Table::new();
+            resolved_table.add_row(Row::new(vec![
+                Cell::new("Last seen"),
+                Cell::new("First seen"),
+                Cell::new("Count"),
+                Cell::new("Error template"),
+            ]));
+            for r in resolved {
+                resolved_table.add_row(Row::new(vec![
+                    Cell::new(&r.last_seen),
+                    Cell::new(&r.first_seen),
+                    Cell::new(&locale.int(r.count)),
+                    Cell::new(&r.template),
+                ]));
+            }
+            write!(output, "{resolved_table}").unwrap();
+        }
+    }
+
+    if let Some(growth) = &stats.growth {
+        if growth.groups.is_empty() {
+            writeln!(
+                output,
+                "\nError groups growing since the previous run: none above {}.",
+                locale.percent(growth.threshold_pct.to_string())
+            )
+            .unwrap();
+        } else {
+            writeln!(
+                output,
+                "\n⚠️  Error groups growing more than {} since the previous run:",
+                locale.percent(growth.threshold_pct.to_string())
+            )
+            .unwrap();
+            let mut growth_table = Table::new();
+            growth_table.add_row(Row::new(vec![
+                Cell::new("Change"),
+                Cell::new("Previous"),
+                Cell::new("Current"),
+                Cell::new("Error template"),
+            ]));
+            for g in &growth.groups {
+                growth_table.add_row(Row::new(vec![
+                    Cell::new(&format_change(g.change_pct, locale)),
+                    Cell::new(&locale.int(g.previous)),
+                    Cell::new(&locale.int(g.current)),
+                    Cell::new(&g.template),
+                ]));
+            }
+            write!(output, "{growth_table}").unwrap();
+        }
+    }
+
+    if let Some(drifts) = &stats.severity_drift {
+        writeln!(
+            output,
+            "\nSeverity changes (same message, level crossed ERROR):"
+        )
+        .unwrap();
+        if drifts.is_empty() {
+            writeln!(output, "None: every message kept its level.").unwrap();
+        } else {
+            let mut drift_table = Table::new();
+            drift_table.add_row(Row::new(vec![
+                Cell::new("Change"),
+                Cell::new("Levels"),
+                Cell::new("Since"),
+                Cell::new("Count"),
+                Cell::new("Template"),
+            ]));
+            for d in drifts {
+                drift_table.add_row(Row::new(vec![
+                    Cell::new(d.direction),
+                    Cell::new(&format!("{} -> {}", d.from, d.to)),
+                    Cell::new(&d.since),
+                    Cell::new(&locale.int(d.count)),
+                    Cell::new(&d.template),
+                ]));
+            }
+            write!(output, "{drift_table}").unwrap();
+        }
+    }
+
+    if let Some(f) = &stats.forecast {
+        writeln!(
+            output,
+            "\nError forecast ({} h of history):",
+            locale.int(f.history_hours)
+        )
+        .unwrap();
+        writeln!(
+            output,
+            "- Next hour : {} errors (range {} - {})",
+            locale.dec(f.next_hour, 1),
+            locale.dec(f.next_hour_low, 1),
+            locale.dec(f.next_hour_high, 1)
+        )
+        .unwrap();
+        writeln!(output, "- Next 24 h : {} errors", locale.dec(f.next_day, 0)).unwrap();
+        writeln!(output, "  {}", f.caveat).unwrap();
+    }
+
+    if !stats.markers.is_empty() {
+        writeln!(output, "\nMarkers (before / after):").unwrap();
+        let mut marker_table = Table::new();
+        marker_table.add_row(Row::new(vec![
+            Cell::new("Marker"),
+            Cell::new("At"),
+            Cell::new("Entries"),
+            Cell::new("Errors"),
+            Cell::new("Error %"),
+            Cell::new("New errors"),
+        ]));
+        for m in &stats.markers {
+            marker_table.add_row(Row::new(vec![
+                Cell::new(&m.label),
+                Cell::new(&m.at),
+                Cell::new(&format!(
+                    "{} / {}",
+                    locale.int(m.before.entries),
+                    locale.int(m.after.entries)
+                )),
+                Cell::new(&format!(
+                    "{} / {}",
+                    locale.int(m.before.errors),
+                    locale.int(m.after.errors)
+                )),
+                Cell::new(&format!(
+                    "{} / {}",
+                    locale.pct(m.before.error_rate, 1),
+                    locale.pct(m.after.error_rate, 1)
+                )),
+                Cell::new(&m.new_errors.join("\n")),
+            ]));
+        }
+        write!(output, "{marker_table}").unwrap();
+    }
+
+    if let Some(b) = &stats.error_budget {
+        writeln!(output, "\nError budget ({}):", b.budget).unwrap();
+        writeln!(
+            output,
+            "- Consumed : {} / {} errors ({})",
+            locale.int(b.consumed),
+            locale.dec(b.allowed, 1),
+            locale.pct(b.consumed_pct, 1)
+        )
+        .unwrap();
+        writeln!(output, "- Burn rate : {}x", locale.dec(b.burn_rate, 2)).unwrap();
+        if let Some(at) = &b.exhausted_at {
+            let when = if b.projected {
+                "Projected exhaustion"
+            } else {
+                "Exhausted at"
+            };
+            writeln!(output, "- {when} : {at}").unwrap();
+        }
+        if !b.top_contributors.is_empty() {
+            let mut budget_table = Table::new();
+            budget_table.add_row(Row::new(vec![
+                Cell::new("Contributor"),
+                Cell::new("Errors"),
+                Cell::new("Budget %"),
+            ]));
+            for c in &b.top_contributors {
+                budget_table.add_row(Row::new(vec![
+                    Cell::new(&c.template),
+                    Cell::new(&locale.int(c.count)),
+                    Cell::new(&locale.pct(c.budget_pct, 1)),
+                ]));
+            }
+            write!(output, "{budget_table}").unwrap();
+        }
+    }
+
+    if let Some(overall) = &stats.inter_arrival
+        && full
+    {
+        writeln!(output, "\nInter-arrival time (seconds):").unwrap();
+        let mut gap_table = Table::new();
+        gap_table.add_row(Row::new(vec![
+            Cell::new("Scope"),
+            Cell::new("Gaps"),
+            Cell::new("p50"),
+            Cell::new("p95"),
+            Cell::new("p99"),
+        ]));
+
+        let mut levels: Vec<_> = stats.inter_arrival_by_level.iter().collect();
+        levels.sort_by(|a, b| a.0.cmp(b.0));
+
+        for (scope, gaps) in std::iter::once(("all", overall))
+            .chain(levels.into_iter().map(|(l, g)| (l.as_str(), g)))
+        {
+            gap_table.add_row(Row::new(vec![
+                Cell::new(scope),
+                Cell::new(&locale.int(gaps.samples)),
+                Cell::new(&locale.dec(gaps.p50, 1)),
+                Cell::new(&locale.dec(gaps.p95, 1)),
+                Cell::new(&locale.dec(gaps.p99, 1)),
+            ]));
+        }
+
+        writeln!(output, "{}", colorize_levels(&gap_table.to_string(), theme)).unwrap();
+    }
+
+    if let Some(c) = &stats.correlation {
+        writeln!(output, "\nRequest/response latency (seconds):").unwrap();
+        let mut latency_table = Table::new();
+        latency_table.add_row(Row::new(vec![
+            Cell::new("Pairs"),
+            Cell::new("p50"),
+            Cell::new("p95"),
+            Cell::new("p99"),
+            Cell::new("Max"),
+            Cell::new("Orphan requests"),
+            Cell::new("Orphan responses"),
+        ]));
+        latency_table.add_row(Row::new(vec![
+            Cell::new(&locale.int(c.pairs)),
+            Cell::new(&locale.dec(c.p50, 1)),
+            Cell::new(&locale.dec(c.p95, 1)),
+            Cell::new(&locale.dec(c.p99, 1)),
+            Cell::new(&locale.dec(c.max, 1)),
+            Cell::new(&locale.int(c.orphan_requests)),
+            Cell::new(&locale.int(c.orphan_responses)),
+        ]));
+        write!(output, "{latency_table}").unwrap();
+        if !c.orphan_keys.is_empty() {
+            writeln!(
+                output,
+                "Oldest unanswered requests: {}",
+                c.orphan_keys.join(", ")
+            )
+            .unwrap();
+        }
+    }
+
+    if let Some(meta) = &stats.metadata {
+        render_metadata(&mut output, meta, locale);
+    }
+
     output
 }
 
@@ -374,24 +2469,129 @@ fn render_json(stats: &LogStats) -> String {
     serde_json::to_string_pretty(stats).unwrap_or_else(|_| "{}".to_string())
 }
 
+/// Spécification Vega-Lite autonome (données incluses), à coller dans
+/// l'éditeur Vega ou à intégrer dans un notebook.
+fn render_vega(stats: &LogStats) -> String {
+    let mut hours: Vec<_> = stats.errors_by_hour.iter().collect();
+    hours.sort();
+    let hourly: Vec<_> = hours
+        .into_iter()
+        .map(|(hour, count)| serde_json::json!({ "hour": hour, "errors": count }))
+        .collect();
+
+    let mut levels: Vec<_> = stats.by_level.iter().collect();
+    levels.sort();
+    let by_level: Vec<_> = levels
+        .into_iter()
+        .map(|(level, count)| serde_json::json!({ "level": level, "count": count }))
+        .collect();
+
+    let spec = serde_json::json!({
+        "$schema": "https://vega.github.io/schema/vega-lite/v5.json",
+        "description": "loglyzer: erreurs par heure et entrées par niveau",
+        "usermeta": { "loglyzer": stats.metadata },
+        "vconcat": [
+            {
+                "title": "Errors by hour",
+                "data": { "values": hourly },
+                "mark": { "type": "line", "point": true, "color": "#d22828" },
+                "encoding": {
+                    "x": { "field": "hour", "type": "ordinal", "title": "Hour" },
+                    "y": { "field": "errors", "type": "quantitative", "title": "Errors" }
+                }
+            },
+            {
+                "title": "Entries by level",
+                "data": { "values": by_level },
+                "mark": "bar",
+                "encoding": {
+                    "x": { "field": "level", "type": "nominal", "title": "Level" },
+                    "y": { "field": "count", "type": "quantitative", "title": "Count" },
+                    "color": {
+                        "field": "level",
+                        "type": "nominal",
+                        "scale": {
+                            "domain": ["DEBUG", "ERROR", "INFO", "WARNING"],
+                            "range": ["#969696", "#d22828", "#3ca05a", "#e6aa1e"]
+                        }
+                    }
+                }
+            }
+        ]
+    });
+    serde_json::to_string_pretty(&spec).unwrap_or_else(|_| "{}".to_string())
+}
+
 fn render_csv(stats: &LogStats) -> String {
     let mut output = String::from("metric,key,value\n");
     output.push_str(&format!("total,,{}\n", stats.total_entries));
+    if stats.partial {
+        output.push_str("partial,,true\n");
+    }
     if stats.skipped_lines > 0 {
         output.push_str(&format!("skipped,,{}\n", stats.skipped_lines));
     }
+    for section in &stats.truncated {
+        output.push_str(&format!("truncated,{section},1\n"));
+    }
     if let Some(s) = &stats.since {
         output.push_str(&format!("filter,since,{s}\n"));
     }
     if let Some(u) = &stats.until {
         output.push_str(&format!("filter,until,{u}\n"));
     }
+    for window in &stats.excluded_windows {
+        output.push_str(&format!("filter,exclude_window,{window}\n"));
+    }
+    if let Some(term) = &stats.search {
+        output.push_str(&format!(
+            "filter,search,\"{}\"\n",
+            term.replace('"', "\"\"")
+        ));
+    }
 
     let mut levels: Vec<_> = stats.by_level.iter().collect();
     levels.sort_by(|a, b| a.0.cmp(b.0));
     for (level, count) in levels {
         output.push_str(&format!("level,{level},{count}\n"));
     }
+    for f in stats.by_file.iter().flatten() {
+        let file = f.file.replace('"', "\"\"");
+        output.push_str(&format!("file_entries,\"{file}\",{}\n", f.entries));
+        output.push_str(&format!("file_errors,\"{file}\",{}\n", f.errors));
+        if let Some(first) = &f.first_error {
+            output.push_str(&format!("file_first_error,\"{file}\",{first}\n"));
+        }
+        for (hour, count) in &f.errors_by_hour {
+            output.push_str(&format!("file_error_by_hour,\"{file} {hour}\",{count}\n"));
+        }
+    }
+
+    output.push_str(&format!("bytes,,{}\n", stats.total_bytes));
+    let mut levels: Vec<_> = stats.bytes_by_level.iter().collect();
+    levels.sort();
+    for (level, bytes) in levels {
+        output.push_str(&format!("bytes_by_level,{level},{bytes}\n"));
+    }
+    let mut hours: Vec<_> = stats.bytes_by_hour.iter().collect();
+    hours.sort();
+    for (hour, bytes) in hours {
+        output.push_str(&format!("bytes_by_hour,{hour},{bytes}\n"));
+    }
+
+    let mut stacked: Vec<_> = stats
+        .entries_by_bucket_by_level
+        .iter()
+        .flat_map(|(hour, by_level)| {
+            by_level
+                .iter()
+                .map(move |(level, count)| (hour, level, count))
+        })
+        .collect();
+    stacked.sort();
+    for (hour, level, count) in stacked {
+        output.push_str(&format!("entries_by_bucket_level,{hour} {level},{count}\n"));
+    }
 
     for err in &stats.top_errors {
         let msg = err.message.replace('"', "\"\"");
@@ -404,25 +2604,270 @@ fn render_csv(stats: &LogStats) -> String {
         output.push_str(&format!("error_by_hour,{hour},{count}\n"));
     }
 
-    let mut rates: Vec<_> = stats.error_rate_by_hour.iter().collect();
-    rates.sort_by(|a, b| a.0.cmp(b.0));
-    for (hour, rate) in rates {
-        output.push_str(&format!("error_rate_by_hour,{hour},{:.4}\n", rate));
+    let tops = stats.top_field.iter().map(|top| ("top_field", top));
+    for (metric, top) in tops.chain(stats.captured.iter().map(|top| ("captured", top))) {
+        for v in &top.values {
+            output.push_str(&format!(
+                "{metric},\"{}={}\",{}\n",
+                top.field.replace('"', "\"\""),
+                v.value.replace('"', "\"\""),
+                v.count
+            ));
+        }
     }
 
-    output
-}
+    if let Some(pivot) = &stats.pivot {
+        for (row_key, cells) in &pivot.counts {
+            for (column_key, count) in cells {
+                output.push_str(&format!(
+                    "pivot,\"{} {}\",{count}\n",
+                    row_key.replace('"', "\"\""),
+                    column_key.replace('"', "\"\"")
+                ));
+            }
+        }
+    }
+
+    if let Some(http) = &stats.http {
+        for (status, count) in &http.by_status {
+            output.push_str(&format!("http_status,{status},{count}\n"));
+        }
+        for p in &http.top_paths {
+            output.push_str(&format!(
+                "http_path,\"{}\",{}\n",
+                p.path.replace('"', "\"\""),
+                p.requests
+            ));
+        }
+    }
 
-fn colorize_levels(table: &str) -> String {
-    use colored::Colorize;
+    let mut categories: Vec<_> = stats.errors_by_category.iter().collect();
+    categories.sort_by(|a, b| a.0.cmp(b.0));
+    for (category, count) in categories {
+        output.push_str(&format!("error_by_category,{category},{count}\n"));
+    }
 
-    LEVEL_COLOR_RE
-        .replace_all(table, |caps: &regex::Captures<'_>| match &caps[1] {
-            "ERROR" => "ERROR".red().bold().to_string(),
-            "WARNING" => "WARNING".yellow().bold().to_string(),
-            other => other.to_string(),
+    let mut series: Vec<_> = stats
+        .errors_by_category_by_hour
+        .iter()
+        .flat_map(|(category, by_hour)| {
+            by_hour
+                .iter()
+                .map(move |(hour, count)| (category, hour, count))
         })
-        .to_string()
+        .collect();
+    series.sort();
+    for (category, hour, count) in series {
+        output.push_str(&format!(
+            "error_by_category_hour,{category} {hour},{count}\n"
+        ));
+    }
+
+    groups::csv_rows(&stats.groups, "", &mut output);
+
+    let mut rates: Vec<_> = stats.error_rate_by_hour.iter().collect();
+    rates.sort_by(|a, b| a.0.cmp(b.0));
+    for (hour, rate) in rates {
+        output.push_str(&format!("error_rate_by_hour,{hour},{:.4}\n", rate));
+    }
+
+    if let Some(wow) = &stats.week_over_week {
+        for series in wow.by_level.iter().chain(&wow.by_error) {
+            let key = series.key.replace('"', "\"\"");
+            for (week, count) in wow.weeks.iter().zip(&series.counts) {
+                output.push_str(&format!("week_over_week,\"{key} {week}\",{count}\n"));
+            }
+        }
+    }
+
+    for t in &stats.noise {
+        output.push_str(&format!(
+            "noise_bytes,\"{} {}\",{}\n",
+            t.level,
+            t.template.replace('"', "\"\""),
+            t.bytes
+        ));
+    }
+
+    for s in &stats.noise_scores {
+        output.push_str(&format!(
+            "noise_score,\"{} {}\",{:.3}\n",
+            s.level,
+            s.template.replace('"', "\"\""),
+            s.score
+        ));
+    }
+
+    for s in stats.call_sites.iter().flatten() {
+        output.push_str(&format!(
+            "call_site,\"{}\",{}\n",
+            s.site.replace('"', "\"\""),
+            s.count
+        ));
+    }
+
+    for f in &stats.first_occurrences {
+        output.push_str(&format!(
+            "first_seen,\"{}\",{}\n",
+            f.template.replace('"', "\"\""),
+            f.first_seen
+        ));
+    }
+    for r in stats.resolved.iter().flatten() {
+        output.push_str(&format!(
+            "resolved_last_seen,\"{}\",{}\n",
+            r.template.replace('"', "\"\""),
+            r.last_seen
+        ));
+    }
+    for g in stats.growth.iter().flat_map(|g| &g.groups) {
+        output.push_str(&format!(
+            "growth,\"{}\",{}\n",
+            g.template.replace('"', "\"\""),
+            format_change(g.change_pct, Locale::Plain)
+        ));
+    }
+    for d in stats.severity_drift.iter().flatten() {
+        output.push_str(&format!(
+            "severity_{},\"{}\",{}>{}\n",
+            d.direction,
+            d.template.replace('"', "\"\""),
+            d.from,
+            d.to
+        ));
+    }
+
+    if let Some(f) = &stats.forecast {
+        output.push_str(&format!("forecast,next_hour,{:.3}\n", f.next_hour));
+        output.push_str(&format!("forecast,next_hour_low,{:.3}\n", f.next_hour_low));
+        output.push_str(&format!(
+            "forecast,next_hour_high,{:.3}\n",
+            f.next_hour_high
+        ));
+        output.push_str(&format!("forecast,next_day,{:.3}\n", f.next_day));
+    }
+
+    for m in &stats.markers {
+        let key = format!("\"{} {}\"", m.at, m.label.replace('"', "\"\""));
+        output.push_str(&format!("marker_errors_before,{key},{}\n", m.before.errors));
+        output.push_str(&format!("marker_errors_after,{key},{}\n", m.after.errors));
+        output.push_str(&format!(
+            "marker_error_rate_before,{key},{:.3}\n",
+            m.before.error_rate
+        ));
+        output.push_str(&format!(
+            "marker_error_rate_after,{key},{:.3}\n",
+            m.after.error_rate
+        ));
+    }
+
+    if let Some(b) = &stats.error_budget {
+        output.push_str(&format!("error_budget,allowed,{:.3}\n", b.allowed));
+        output.push_str(&format!("error_budget,consumed,{}\n", b.consumed));
+        output.push_str(&format!(
+            "error_budget,consumed_pct,{:.3}\n",
+            b.consumed_pct
+        ));
+        output.push_str(&format!("error_budget,burn_rate,{:.3}\n", b.burn_rate));
+        if let Some(at) = &b.exhausted_at {
+            output.push_str(&format!("error_budget,exhausted_at,{at}\n"));
+        }
+        for c in &b.top_contributors {
+            output.push_str(&format!(
+                "error_budget_contributor,\"{}\",{}\n",
+                c.template.replace('"', "\"\""),
+                c.count
+            ));
+        }
+    }
+
+    if let Some(span) = &stats.time_span {
+        output.push_str(&format!("time_span,{},{}\n", span.start, span.seconds));
+    }
+    if let Some(gap) = &stats.longest_gap {
+        output.push_str(&format!("longest_gap,{},{}\n", gap.start, gap.seconds));
+    }
+    let mut gaps: Vec<_> = stats
+        .inter_arrival
+        .iter()
+        .map(|g| ("all", g))
+        .chain(
+            stats
+                .inter_arrival_by_level
+                .iter()
+                .map(|(l, g)| (l.as_str(), g)),
+        )
+        .collect();
+    gaps.sort_by(|a, b| a.0.cmp(b.0));
+    for (scope, g) in gaps {
+        output.push_str(&format!("inter_arrival_p50,{scope},{:.3}\n", g.p50));
+        output.push_str(&format!("inter_arrival_p95,{scope},{:.3}\n", g.p95));
+        output.push_str(&format!("inter_arrival_p99,{scope},{:.3}\n", g.p99));
+    }
+    if let Some(c) = &stats.correlation {
+        output.push_str(&format!("latency_pairs,,{}\n", c.pairs));
+        output.push_str(&format!("latency_p50,,{:.3}\n", c.p50));
+        output.push_str(&format!("latency_p95,,{:.3}\n", c.p95));
+        output.push_str(&format!("latency_p99,,{:.3}\n", c.p99));
+        output.push_str(&format!("latency_max,,{:.3}\n", c.max));
+        output.push_str(&format!("orphan_requests,,{}\n", c.orphan_requests));
+        output.push_str(&format!("orphan_responses,,{}\n", c.orphan_responses));
+    }
+    if let Some(meta) = &stats.metadata {
+        let quoted = |s: &str| format!("\"{}\"", s.replace('"', "\"\""));
+        output.push_str(&format!("meta,version,{}\n", meta.version));
+        output.push_str(&format!(
+            "meta,command_line,{}\n",
+            quoted(&meta.command_line)
+        ));
+        output.push_str(&format!("meta,generated_at,{}\n", meta.generated_at));
+        output.push_str(&format!("meta,wall_time_ms,{}\n", meta.wall_time_ms));
+        if let Some(host) = &meta.host {
+            output.push_str(&format!("meta,host,{}\n", quoted(host)));
+        }
+        for input in &meta.inputs {
+            if let Some(bytes) = input.bytes {
+                output.push_str(&format!("input_bytes,{},{bytes}\n", quoted(&input.file)));
+            }
+            if let Some(sha256) = &input.sha256 {
+                output.push_str(&format!("input_sha256,{},{sha256}\n", quoted(&input.file)));
+            }
+        }
+    }
+    output
+}
+
+/// Regex insensible à la casse trouvant le terme de `--search` tel quel
+fn search_regex(term: &str) -> Regex {
+    Regex::new(&format!("(?i){}", regex::escape(term))).unwrap()
+}
+
+/// Entoure chaque occurrence du terme recherché avec `mark` (ANSI, `<mark>`...)
+fn highlight(text: &str, re: &Regex, mark: impl Fn(&str) -> String) -> String {
+    re.replace_all(text, |caps: &regex::Captures<'_>| mark(&caps[0]))
+        .into_owned()
+}
+
+/// Sépare les trois premières lignes d'un tableau rendu (bordure, en-tête, bordure)
+fn split_header(table: &str) -> (&str, &str) {
+    let cut = table
+        .match_indices('\n')
+        .nth(2)
+        .map(|(i, _)| i + 1)
+        .unwrap_or(table.len());
+    table.split_at(cut)
+}
+
+fn colorize_levels(text: &str, theme: &Theme) -> String {
+    LEVEL_COLOR_RE
+        .replace_all(
+            text,
+            |caps: &regex::Captures<'_>| match LogLevel::from_str(&caps[1]) {
+                Some(level) => theme.style(&level).apply(&caps[1]),
+                None => caps[1].to_string(),
+            },
+        )
+        .to_string()
 }
 
 fn make_progress_bar(size: u64) -> ProgressBar {
@@ -437,6 +2882,19 @@ fn make_progress_bar(size: u64) -> ProgressBar {
     pb
 }
 
+/// Progression sans total, pour l'entrée standard dont la taille est inconnue
+fn make_spinner() -> ProgressBar {
+    let pb = ProgressBar::new_spinner();
+    pb.set_style(
+        ProgressStyle::with_template(
+            "[{elapsed_precise}] {spinner:.cyan} {bytes} lus ({bytes_per_sec})",
+        )
+        .unwrap(),
+    );
+    pb.enable_steady_tick(Duration::from_millis(100));
+    pb
+}
+
 fn should_use_progress(size: u64) -> bool {
     size >= PROGRESS_THRESHOLD
 }
@@ -446,6 +2904,120 @@ fn parse_datetime(input: &str) -> Result<NaiveDateTime, String> {
         .map_err(|e| format!("Format attendu: YYYY-MM-DD HH:MM:SS ({e})"))
 }
 
+/// Plage horaire exclue de l'analyse, bornes incluses
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ExcludeWindow {
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+}
+
+impl ExcludeWindow {
+    fn contains(&self, at: NaiveDateTime) -> bool {
+        self.start <= at && at <= self.end
+    }
+}
+
+impl std::fmt::Display for ExcludeWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}..{}",
+            self.start.format("%Y-%m-%d %H:%M:%S"),
+            self.end.format("%Y-%m-%d %H:%M:%S")
+        )
+    }
+}
+
+/// Regex de `--correlate`, qui doit capturer la clé de corrélation
+fn parse_correlate(input: &str) -> Result<Regex, String> {
+    let re = Regex::new(input).map_err(|e| format!("Regex --correlate invalide: {e}"))?;
+    if re.captures_len() < 2 {
+        return Err(format!(
+            "Regex --correlate sans groupe capturant la clé: {input} (ex: 'request (\\w+)')"
+        ));
+    }
+    Ok(re)
+}
+
+/// `START..END`, chaque borne en `YYYY-MM-DD HH:MM[:SS]`
+fn parse_exclude_window(input: &str) -> Result<ExcludeWindow, String> {
+    let bound = |s: &str| {
+        let s = s.trim();
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S")
+            .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M"))
+            .map_err(|_| {
+                format!("Plage invalide: {input} (ex: 2024-01-15 02:00..2024-01-15 03:00)")
+            })
+    };
+    let (start, end) = input.split_once("..").ok_or_else(|| {
+        format!("Plage invalide: {input} (ex: 2024-01-15 02:00..2024-01-15 03:00)")
+    })?;
+    let window = ExcludeWindow {
+        start: bound(start)?,
+        end: bound(end)?,
+    };
+    if window.end < window.start {
+        return Err(format!("Plage invalide: {input} (fin avant le début)"));
+    }
+    Ok(window)
+}
+
+fn parse_size(input: &str) -> Result<u64, String> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (digits, unit) = input.split_at(split);
+    let value: u64 = digits
+        .parse()
+        .map_err(|_| format!("Taille invalide: {input}"))?;
+    let factor = match unit.trim().to_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" => 1024,
+        "M" | "MB" => 1024 * 1024,
+        "G" | "GB" => 1024 * 1024 * 1024,
+        _ => return Err(format!("Unité de taille inconnue: {input}")),
+    };
+    Ok(value * factor)
+}
+
+fn parse_duration(input: &str) -> Result<Duration, String> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (digits, unit) = input.split_at(split);
+    let value: u64 = digits
+        .parse()
+        .map_err(|_| format!("Durée invalide: {input}"))?;
+    let seconds = match unit {
+        "s" => value,
+        "m" | "min" => value * 60,
+        "h" => value * 3600,
+        "d" | "j" => value * 86400,
+        _ => return Err(format!("Unité de durée inconnue: {input} (s, m, h, d)")),
+    };
+    Ok(Duration::from_secs(seconds))
+}
+
+/// Période d'un débit `N/PERIOD`: une unité seule vaut une unité (`min` = `1min`)
+fn parse_period(input: &str) -> Result<Duration, String> {
+    let input = input.trim();
+    if input.starts_with(|c: char| c.is_ascii_digit()) {
+        parse_duration(input)
+    } else {
+        parse_duration(&format!("1{input}"))
+    }
+}
+
+/// Plafond de --max-top, --max-buckets ou --max-examples
+fn parse_limit(input: &str) -> Result<usize, String> {
+    match input.parse() {
+        Ok(0) | Err(_) => Err("Le plafond doit être un entier d'au moins 1".to_string()),
+        Ok(value) => Ok(value),
+    }
+}
+
 fn parse_top(input: &str) -> Result<usize, String> {
     let value: usize = input
         .parse()
@@ -457,148 +3029,437 @@ fn parse_top(input: &str) -> Result<usize, String> {
     }
 }
 
+/// Étapes de `EntryFilter`, dans leur ordre d'application
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FilterStage {
+    ErrorsOnly,
+    Since,
+    Until,
+    Exclude,
+    Search,
+}
+
+/// Critères de sélection des entrées (niveau, période, recherche)
+struct EntryFilter<'a> {
+    errors_only: bool,
+    search_lower: Option<&'a str>,
+    since: Option<NaiveDateTime>,
+    until: Option<NaiveDateTime>,
+    exclude: &'a [ExcludeWindow],
+}
+
+impl EntryFilter<'_> {
+    fn matches(&self, e: &LogEntry) -> bool {
+        self.rejected_by(e).is_none()
+    }
+
+    /// Première étape qui écarte l'entrée, `None` si elle est retenue
+    fn rejected_by(&self, e: &LogEntry) -> Option<FilterStage> {
+        if self.errors_only && e.level != LogLevel::Error {
+            return Some(FilterStage::ErrorsOnly);
+        }
+        if self.since.is_some_and(|since| e.datetime < since) {
+            return Some(FilterStage::Since);
+        }
+        if self.until.is_some_and(|until| e.datetime > until) {
+            return Some(FilterStage::Until);
+        }
+        if self.exclude.iter().any(|w| w.contains(e.datetime)) {
+            return Some(FilterStage::Exclude);
+        }
+        let term = self.search_lower?;
+        let haystack = format!("{} [{}] {}", e.timestamp, e.level.as_str(), e.message);
+        (!haystack.to_lowercase().contains(term)).then_some(FilterStage::Search)
+    }
+
+    /// Étapes actives et leur description, pour `--explain`
+    fn stages(&self) -> Vec<(FilterStage, String)> {
+        let format = |d: NaiveDateTime| d.format("%Y-%m-%d %H:%M:%S");
+        let mut stages = Vec::new();
+        if self.errors_only {
+            stages.push((
+                FilterStage::ErrorsOnly,
+                "--errors-only (niveau ERROR)".to_string(),
+            ));
+        }
+        if let Some(since) = self.since {
+            stages.push((FilterStage::Since, format!("--since {}", format(since))));
+        }
+        if let Some(until) = self.until {
+            stages.push((FilterStage::Until, format!("--until {}", format(until))));
+        }
+        if !self.exclude.is_empty() {
+            let windows: Vec<String> = self.exclude.iter().map(|w| w.to_string()).collect();
+            stages.push((
+                FilterStage::Exclude,
+                format!("--exclude-window {}", windows.join(", ")),
+            ));
+        }
+        if let Some(term) = self.search_lower {
+            stages.push((
+                FilterStage::Search,
+                format!("--search \"{term}\" (horodatage, niveau et message, sans casse)"),
+            ));
+        }
+        stages
+    }
+}
+
 fn filter_entries(
     entries: Vec<LogEntry>,
     errors_only: bool,
     search_lower: Option<&str>,
     since: Option<NaiveDateTime>,
     until: Option<NaiveDateTime>,
+    exclude: &[ExcludeWindow],
 ) -> Vec<LogEntry> {
-    entries
-        .into_iter()
-        .filter(|e| !errors_only || e.level == LogLevel::Error)
-        .filter(|e| {
-            if let Some(since) = since {
-                e.datetime >= since
-            } else {
-                true
-            }
-        })
-        .filter(|e| {
-            if let Some(until) = until {
-                e.datetime <= until
-            } else {
-                true
+    let filter = EntryFilter {
+        errors_only,
+        search_lower,
+        since,
+        until,
+        exclude,
+    };
+    entries.into_iter().filter(|e| filter.matches(e)).collect()
+}
+
+/// Statistiques et entrées filtrées dont elles sont issues
+struct Analysis {
+    stats: LogStats,
+    entries: Vec<LogEntry>,
+}
+
+/// Bilan de lecture, connu même quand aucune entrée ne correspond aux filtres
+#[derive(Debug, Clone, Copy)]
+struct ReadCounts {
+    skipped: usize,
+    partial: bool,
+}
+
+/// Gabarits d'erreur en hausse avec --growth-alert
+#[derive(Debug, Serialize)]
+struct GrowthReport {
+    threshold_pct: f64,
+    groups: Vec<GrowingError>,
+}
+
+/// `+150.0%`, ou `new` pour un gabarit absent de l'exécution précédente
+fn format_change(change_pct: Option<f64>, locale: Locale) -> String {
+    change_pct.map_or_else(
+        || "new".to_string(),
+        |pct| locale.percent(locale.signed(pct, 1)),
+    )
+}
+
+/// Hausses (--growth-alert) et changements de niveau (--severity-drift) par
+/// gabarit; avec `--state`, comparés aux relevés de l'exécution précédente,
+/// que ceux-ci remplacent ensuite.
+fn track_runs(
+    cli: &Cli,
+    entries: &[LogEntry],
+    top_n: usize,
+) -> Result<(Option<GrowthReport>, Option<Vec<SeverityDrift>>), LoglyzerError> {
+    let Some(path) = &cli.state else {
+        let drift = cli
+            .severity_drift
+            .then(|| noise::severity_drift(entries, None, top_n));
+        return Ok((None, drift));
+    };
+    let mut checkpoints =
+        Checkpoints::load(path).map_err(|err| LoglyzerError::reading(path, err))?;
+    let current = noise::error_counts(entries);
+    let growth = match (cli.growth_alert, checkpoints.error_groups()) {
+        (Some(threshold_pct), Some(previous)) => Some(GrowthReport {
+            threshold_pct,
+            groups: noise::growing(previous, &current, threshold_pct, top_n),
+        }),
+        _ => None,
+    };
+    let drift = cli
+        .severity_drift
+        .then(|| noise::severity_drift(entries, checkpoints.template_levels(), top_n));
+    checkpoints
+        .commit_run(current, noise::template_levels(entries))
+        .map_err(|err| LoglyzerError::writing(path, err))?;
+    Ok((growth, drift))
+}
+
+/// Lit, filtre et analyse le fichier d'entrée; `None` si aucune entrée ne
+/// correspond aux filtres, avec le bilan de lecture dans tous les cas.
+fn run_analysis(
+    cli: &Cli,
+    categorizer: &Categorizer,
+    tagger: &Tagger,
+    reclassifier: &Reclassifier,
+    top_n: usize,
+) -> Result<(Option<Analysis>, ReadCounts), Box<dyn std::error::Error>> {
+    // Taille inconnue (`None`) pour l'entrée standard et les URL sans Content-Length
+    let sizes = cli
+        .input
+        .iter()
+        .map(|path| {
+            if is_stdin(path) || is_container(path) || journald::unit(path).is_some() {
+                return Ok(None);
             }
-        })
-        .filter(|e| {
-            if let Some(term) = search_lower {
-                let haystack =
-                    format!("{} [{}] {}", e.timestamp, e.level.as_str(), e.message).to_lowercase();
-                haystack.contains(term)
-            } else {
-                true
+            if remote::is_url(path) {
+                return remote::content_length(path)
+                    .map_err(|err| LoglyzerError::reading(path, err));
             }
+            platform::metadata(path)
+                .map(|m| Some(m.len()))
+                .map_err(|err| LoglyzerError::reading(path, err))
         })
-        .collect()
-}
+        .collect::<Result<Vec<Option<u64>>, _>>()?;
+    let file_size: Option<u64> = sizes.iter().copied().sum();
+    let start = Instant::now();
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let cli = Cli::parse();
-    let top_n = cli.top.max(1);
+    let progress = match file_size {
+        Some(size) if should_use_progress(size) => Some(make_progress_bar(size)),
+        Some(_) => None,
+        None => Some(make_spinner()),
+    };
 
-    let meta = match fs::metadata(&cli.input) {
-        Ok(m) => m,
-        Err(err) => {
-            use std::io::ErrorKind;
-            match err.kind() {
-                ErrorKind::NotFound => {
-                    eprintln!("Fichier introuvable: {}", cli.input.display());
-                    std::process::exit(2);
-                }
-                _ => {
-                    eprintln!(
-                        "Impossible de lire le fichier {}: {}",
-                        cli.input.display(),
-                        err
-                    );
-                    std::process::exit(1);
-                }
+    let enricher = cli.enricher()?;
+    let search_lower = cli.search.as_ref().map(|s| s.to_lowercase());
+    let filter = EntryFilter {
+        errors_only: cli.errors_only,
+        search_lower: search_lower.as_deref(),
+        since: cli.since,
+        until: cli.until,
+        exclude: &cli.exclude_window,
+    };
+    // Comptes de --explain: entrées reclassées, puis écartées par étape
+    let reclassified = AtomicUsize::new(0);
+    let removed: [AtomicUsize; 5] = Default::default();
+    // Reclassement, filtrage et enrichissement en une passe, pendant la
+    // lecture: les entrées écartées ne sont jamais conservées.
+    let prepare = |entry: &mut LogEntry| {
+        if !reclassifier.is_empty()
+            && let Some(level) = reclassifier.level(&entry.level, &entry.message)
+        {
+            if cli.explain && level != entry.level {
+                reclassified.fetch_add(1, Ordering::Relaxed);
+            }
+            entry.level = level;
+        }
+        if let Some(stage) = filter.rejected_by(entry) {
+            if cli.explain {
+                removed[stage as usize].fetch_add(1, Ordering::Relaxed);
             }
+            return false;
         }
+        // --explain ne garde que les comptes: rien n'est conservé en mémoire
+        if cli.explain {
+            return false;
+        }
+        if !tagger.is_empty() {
+            entry.tags = tagger.tags(&entry.message);
+        }
+        enricher.enrich(entry);
+        true
     };
-    let file_size = meta.len();
-
-    let use_parallel = cli.parallel || file_size > PARALLEL_THRESHOLD;
-    let start = Instant::now();
 
-    if cli.verbose {
-        eprintln!(
-            "Lecture de {} ({} octets) en mode {}",
-            cli.input.display(),
-            file_size,
-            if use_parallel {
+    let mut parsed = ParsedLogs::default();
+    let mut by_file = Vec::new();
+    let mut read_inputs = Vec::new();
+    for (path, size) in cli.input.iter().zip(sizes) {
+        let format = cli.line_format(Some(path));
+        let mut plan = tuning::plan(path, size, &format, cli.parallel);
+        if let Some(chunk) = cli.chunk_size {
+            plan.chunk = chunk;
+        }
+        if cli.verbose {
+            let mode = if plan.parallel {
                 "parallèle"
             } else {
                 "séquentiel"
+            };
+            match size {
+                Some(size) => eprintln!(
+                    "Lecture de {} ({size} octets) en mode {mode}",
+                    path.display()
+                ),
+                None if is_stdin(path) => {
+                    eprintln!("Lecture de l'entrée standard en mode {mode}")
+                }
+                None => eprintln!(
+                    "Lecture de {} (taille inconnue) en mode {mode}",
+                    path.display()
+                ),
             }
-        );
+            if let Some(m) = plan.measure {
+                const MB: f64 = 1024.0 * 1024.0;
+                let chunk = if plan.parallel {
+                    format!(", blocs de {} lignes", plan.chunk)
+                } else {
+                    String::new()
+                };
+                eprintln!(
+                    "  débit mesuré sur {:.1} Mo: {:.1} Mo/s sur un fil, {:.1} Mo/s sur le pool de {} fil(s){chunk}",
+                    m.sample as f64 / MB,
+                    m.single / MB,
+                    m.parallel / MB,
+                    m.threads,
+                );
+            }
+        }
+        let file = if plan.parallel {
+            read_logs_parallel(
+                path,
+                progress.as_ref(),
+                &format,
+                cli.multiline,
+                plan.chunk,
+                &prepare,
+            )
+        } else {
+            read_logs(path, progress.as_ref(), &format, cli.multiline, &prepare)
+        };
+        let mut file = file.map_err(|err| LoglyzerError::reading(path, err))?;
+        by_file.push(FileStats::new(path, &file));
+        read_inputs.push(InputMeta::new(path, size, file.digest.take()));
+        parsed.merge(file);
+        if parsed.interrupted {
+            break;
+        }
+    }
+    if let Some(bar) = &progress {
+        bar.finish_and_clear();
+    }
+    // Fichiers lus l'un après l'autre: les entrées sont remises dans l'ordre
+    // chronologique (tri stable, l'ordre des fichiers départage)
+    if by_file.len() > 1 {
+        parsed.entries.sort_by_key(|e| e.datetime);
     }
 
-    let progress = if should_use_progress(file_size) {
-        Some(make_progress_bar(file_size))
-    } else {
-        None
-    };
+    let parse_time = start.elapsed();
 
-    let parsed = if use_parallel {
-        read_logs_parallel(&cli.input, progress.as_ref())
+    let total_lines = parsed.entries.len() + parsed.dropped + parsed.skipped;
+    let counts = ReadCounts {
+        skipped: parsed.skipped,
+        partial: parsed.interrupted,
+    };
+    if cli.explain {
+        let stages = filter
+            .stages()
+            .into_iter()
+            .map(|(stage, label)| explain::Stage {
+                label,
+                removed: removed[stage as usize].load(Ordering::Relaxed),
+            })
+            .collect();
+        let report = explain::Explain {
+            lines: total_lines,
+            skipped: parsed.skipped,
+            reclassified: (!reclassifier.is_empty()).then(|| reclassified.load(Ordering::Relaxed)),
+            stages,
+            partial: parsed.interrupted,
+        };
+        let inputs: Vec<String> = cli.input.iter().map(|p| p.display().to_string()).collect();
+        print!("{}", explain::render(&report, &inputs.join(", ")));
+        return Ok((None, counts));
+    }
+    let parse_hints = if total_lines > 0
+        && parsed.skipped as f64 / total_lines as f64 * 100.0 > cli.hint_threshold
+    {
+        hints::suggest(&parsed.skipped_samples)
     } else {
-        read_logs(&cli.input, progress.as_ref())
+        Vec::new()
     };
 
-    let parsed = match parsed {
-        Ok(list) => list,
-        Err(err) => {
-            use std::io::ErrorKind;
-            match err.kind() {
-                ErrorKind::NotFound => {
-                    eprintln!("Fichier introuvable: {}", cli.input.display());
-                    std::process::exit(2);
-                }
-                _ => return Err(Box::new(err)),
-            }
+    let filtered = parsed.entries;
+
+    let (growth, severity_drift) = track_runs(cli, &filtered, top_n)?;
+
+    if filtered.is_empty() {
+        for hint in &parse_hints {
+            eprintln!("Indice: {hint}");
         }
+        return Ok((None, counts));
+    }
+
+    let markers: Vec<Marker> = match cli.markers.as_deref().map(markers::load).transpose() {
+        Ok(markers) => markers.unwrap_or_default(),
+        Err(err) => return Err(LoglyzerError::Invalid(err).into()),
     };
 
-    let parse_time = start.elapsed();
+    if let Some(path) = &cli.chart {
+        chart::render_chart(path, &filtered, &markers)?;
+        if cli.verbose {
+            eprintln!("Graphique écrit dans {}", path.display());
+        }
+    }
+    if let Some(path) = &cli.series_out {
+        series::write(path, &filtered, cli.existing_output())
+            .map_err(|err| LoglyzerError::writing(path, err))?;
+        if cli.verbose {
+            eprintln!("Série horaire écrite dans {}", path.display());
+        }
+    }
 
-    let search_lower = cli.search.as_ref().map(|s| s.to_lowercase());
-    let filtered = filter_entries(
-        parsed.entries,
-        cli.errors_only,
-        search_lower.as_deref(),
+    let mut stats = analyze_logs(
+        &filtered,
+        top_n,
         cli.since,
         cli.until,
+        parsed.skipped,
+        categorizer,
+        &cli.group_by,
     );
-
-    if filtered.is_empty() {
-        let msg = "Aucune entrée ne correspond aux filtres fournis.";
-        if let Some(path) = cli.output {
-            fs::write(&path, msg)?;
-            println!("Résultats écrits dans {}", path.display());
-        } else {
-            println!("{msg}");
+    stats.parse_hints = parse_hints;
+    stats.partial = parsed.interrupted;
+    stats.by_file = (by_file.len() > 1).then_some(by_file);
+    stats.search = cli.search.clone();
+    stats.excluded_windows = cli.exclude_window.iter().map(|w| w.to_string()).collect();
+    stats.markers = markers::impacts(&filtered, &markers, cli.marker_window);
+    if let Some(budget) = cli.error_budget {
+        stats.error_budget = budget::consumption(&filtered, budget, top_n);
+    }
+    if let Ok(Some((field, n))) = cli.top_field() {
+        stats.top_field = Some(fields::top_values(&filtered, field, n));
+    }
+    stats.captured = fields::capture_names(&cli.capture)
+        .into_iter()
+        .map(|name| fields::top_values(&filtered, name, top_n))
+        .collect();
+    if let Ok(Some((columns, rows))) = cli.pivot() {
+        stats.pivot = Some(fields::pivot(&filtered, columns, rows));
+    }
+    if cli.input_format == InputFormat::Access {
+        stats.http = Some(access::http_stats(&filtered, top_n));
+    }
+    if cli.call_sites {
+        stats.call_sites = Some(callers::noisiest(&filtered, top_n));
+    }
+    if let Some(quiet) = cli.resolved_after {
+        stats.resolved = Some(noise::resolved(&filtered, quiet, cli.until, top_n));
+    }
+    stats.growth = growth;
+    stats.severity_drift = severity_drift;
+    if let [request, response] = cli.correlate.as_slice() {
+        stats.correlation = Some(timing::correlate(&filtered, request, response, top_n));
+    }
+    stats.truncated = limits::apply(
+        &mut stats,
+        limits::Limits {
+            top: cli.max_top,
+            buckets: cli.max_buckets,
+            examples: cli.max_examples,
+        },
+    );
+    let metadata = ReportMetadata::new(read_inputs, start.elapsed());
+    if let Some(path) = &cli.metadata_out {
+        let mut line = serde_json::to_vec(&metadata)?;
+        line.push(b'\n');
+        outfile::write(path, &line, cli.existing_output())
+            .map_err(|err| LoglyzerError::writing(path, err))?;
+        if cli.verbose {
+            eprintln!("Métadonnées écrites dans {}", path.display());
         }
-        return Ok(());
     }
-
-    let stats = analyze_logs(&filtered, top_n, cli.since, cli.until, parsed.skipped);
+    stats.metadata = Some(metadata);
     let analysis_time = start.elapsed() - parse_time;
 
-    let rendered = match cli.format {
-        OutputFormat::Text => render_text(&stats, top_n),
-        OutputFormat::Json => render_json(&stats),
-        OutputFormat::Csv => render_csv(&stats),
-    };
-
-    if let Some(path) = cli.output {
-        fs::write(&path, rendered)?;
-        println!("Résultats écrits dans {}", path.display());
-    } else {
-        println!("{rendered}");
-    }
-
     if cli.verbose {
         let total_time = start.elapsed();
         eprintln!(
@@ -607,6 +3468,611 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         );
     }
 
+    Ok((
+        Some(Analysis {
+            stats,
+            entries: filtered,
+        }),
+        counts,
+    ))
+}
+
+fn render(
+    cli: &Cli,
+    analysis: Option<&Analysis>,
+    top_n: usize,
+    theme: &Theme,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let rendered = match (analysis, cli.format) {
+        (analysis, OutputFormat::Arrow) => {
+            // Flux vide (schéma seul) si rien ne correspond, pour rester lisible
+            let mut buf = Vec::new();
+            let entries = analysis.map(|a| a.entries.as_slice()).unwrap_or_default();
+            let metadata = analysis.and_then(|a| a.stats.metadata.as_ref());
+            export::write_arrow(entries, &cli.select, metadata, &mut buf)?;
+            return Ok(buf);
+        }
+        (analysis, OutputFormat::Jsonl) => {
+            let mut buf = Vec::new();
+            let entries = analysis.map(|a| a.entries.as_slice()).unwrap_or_default();
+            export::write_jsonl(entries, &cli.select, &mut buf)?;
+            return Ok(buf);
+        }
+        (_, OutputFormat::Duckdb) => {
+            return Err("--format duckdb s'écrit uniquement dans un fichier (--output)".into());
+        }
+        (None, _) => NO_MATCH_MESSAGE.to_string(),
+        (Some(a), OutputFormat::Text) => render_text(
+            &a.stats,
+            top_n,
+            theme,
+            cli.locale.unwrap_or_default(),
+            cli.report,
+        ),
+        (Some(a), OutputFormat::Json) => render_json(&a.stats),
+        (Some(a), OutputFormat::Csv) => render_csv(&a.stats),
+        (Some(a), OutputFormat::Vega) => render_vega(&a.stats),
+    };
+    Ok(rendered.into_bytes())
+}
+
+/// Écrit le rapport dans `path`: le rendu du format choisi ou, pour
+/// `--format duckdb`, une base contenant les entrées et les statistiques.
+fn write_output(
+    cli: &Cli,
+    analysis: Option<&Analysis>,
+    path: &Path,
+    top_n: usize,
+    theme: &Theme,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if matches!(cli.format, OutputFormat::Duckdb) {
+        #[cfg(feature = "duckdb")]
+        {
+            let (entries, stats, metadata) = match analysis {
+                Some(a) => (
+                    a.entries.as_slice(),
+                    render_csv(&a.stats),
+                    a.stats.metadata.as_ref(),
+                ),
+                None => (&[][..], String::new(), None),
+            };
+            if path.exists() && !cli.force {
+                return Err(LoglyzerError::writing(path, outfile::already_exists(path)).into());
+            }
+            // Base complète préparée à côté puis renommée, comme les autres formats
+            let tmp = outfile::temp_path(path);
+            let written = duckdb_out::write(&tmp, entries, &cli.select, &stats, metadata)
+                .map_err(std::io::Error::other)
+                .and_then(|_| fs::rename(&tmp, path));
+            if let Err(err) = written {
+                let _ = fs::remove_file(&tmp);
+                return Err(LoglyzerError::writing(path, err).into());
+            }
+        }
+        #[cfg(not(feature = "duckdb"))]
+        {
+            let _ = analysis;
+            return Err("Sortie DuckDB non disponible: recompiler avec --features duckdb".into());
+        }
+    } else {
+        let rendered = render(cli, analysis, top_n, theme)?;
+        outfile::write(path, &rendered, cli.existing_output())
+            .map_err(|err| LoglyzerError::writing(path, err))?;
+    }
+    if cli.checksums {
+        let mut written = vec![path];
+        written.extend(cli.chart.as_deref().filter(|chart| chart.exists()));
+        written.extend(cli.series_out.as_deref().filter(|series| series.exists()));
+        written.extend(cli.metadata_out.as_deref().filter(|meta| meta.exists()));
+        let manifests =
+            checksums::record(&written).map_err(|err| LoglyzerError::writing(path, err))?;
+        if cli.verbose {
+            for manifest in manifests {
+                eprintln!("Empreintes inscrites dans {}", manifest.display());
+            }
+        }
+    }
+    if !cli.summary_line {
+        println!("Résultats écrits dans {}", path.display());
+    }
+    Ok(())
+}
+
+/// Ligne de `--summary-line`; les valeurs ne contiennent jamais d'espace,
+/// `window` vaut `-` sans entrée retenue.
+fn summary_line(entries: &[LogEntry], skipped: usize, partial: bool) -> String {
+    let count = |level: LogLevel| entries.iter().filter(|e| e.level == level).count();
+    let first = entries.iter().map(|e| e.datetime).min();
+    let last = entries.iter().map(|e| e.datetime).max();
+    let window = match (first, last) {
+        (Some(first), Some(last)) => format!(
+            "{}/{}",
+            first.format("%Y-%m-%dT%H:%M:%S"),
+            last.format("%Y-%m-%dT%H:%M:%S")
+        ),
+        _ => "-".to_string(),
+    };
+    let mut line = format!(
+        "total={} errors={} warn={} skipped={skipped} window={window}",
+        entries.len(),
+        count(LogLevel::Error),
+        count(LogLevel::Warning),
+    );
+    if partial {
+        line.push_str(" partial=true");
+    }
+    line
+}
+
+/// Écrit le rendu sur la sortie standard, suivi d'un saut de ligne sauf pour
+/// les exports d'entrées, déjà délimités.
+fn print_rendered(cli: &Cli, rendered: &[u8]) -> std::io::Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    stdout.write_all(rendered)?;
+    if !cli.format.exports_entries() {
+        stdout.write_all(b"\n")?;
+    }
+    stdout.flush()
+}
+
+fn now_utc() -> NaiveDateTime {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    chrono::DateTime::from_timestamp(secs, 0)
+        .unwrap_or_default()
+        .naive_utc()
+}
+
+/// Date de dernière modification d'un fichier, en UTC
+fn modified_utc(path: &Path) -> Option<NaiveDateTime> {
+    let secs = platform::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+    chrono::DateTime::from_timestamp(secs as i64, 0).map(|d| d.naive_utc())
+}
+
+/// `report.json` devient `report-20240115T103000.json`
+fn timestamped_path(path: &Path, at: NaiveDateTime) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut name = format!("{stem}-{}", at.format("%Y%m%dT%H%M%S"));
+    if let Some(ext) = path.extension() {
+        name.push('.');
+        name.push_str(&ext.to_string_lossy());
+    }
+    path.with_file_name(name)
+}
+
+/// Vrai si le nombre d'entrées d'au moins un niveau a varié de `pct` % ou plus.
+fn changed_materially(
+    previous: &HashMap<String, usize>,
+    current: &HashMap<String, usize>,
+    pct: f64,
+) -> bool {
+    previous.keys().chain(current.keys()).any(|level| {
+        let before = previous.get(level).copied().unwrap_or(0) as f64;
+        let after = current.get(level).copied().unwrap_or(0) as f64;
+        (after - before).abs() / before.max(1.0) * 100.0 >= pct
+    })
+}
+
+fn run_every(
+    cli: &Cli,
+    period: Duration,
+    categorizer: &Categorizer,
+    tagger: &Tagger,
+    reclassifier: &Reclassifier,
+    top_n: usize,
+    theme: &Theme,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut previous: Option<HashMap<String, usize>> = None;
+
+    loop {
+        let (analysis, _) = run_analysis(cli, categorizer, tagger, reclassifier, top_n)?;
+        let by_level = analysis
+            .as_ref()
+            .map(|a| a.stats.by_level.clone())
+            .unwrap_or_default();
+        let emit = match (&previous, cli.min_change) {
+            (Some(prev), Some(pct)) => changed_materially(prev, &by_level, pct),
+            _ => true,
+        };
+
+        if emit {
+            let now = now_utc();
+            if let Some(path) = &cli.output {
+                let path = timestamped_path(path, now);
+                write_output(cli, analysis.as_ref(), &path, top_n, theme)?;
+            } else {
+                println!(
+                    "=== Analyse du {} (UTC) ===",
+                    now.format("%Y-%m-%d %H:%M:%S")
+                );
+                print_rendered(cli, &render(cli, analysis.as_ref(), top_n, theme)?)?;
+            }
+            previous = Some(by_level);
+        } else if cli.verbose {
+            eprintln!("Pas de changement significatif, rapport non émis");
+        }
+
+        std::thread::sleep(period);
+    }
+}
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("{err}");
+        let code = err
+            .downcast_ref::<LoglyzerError>()
+            .map_or(EXIT_ERROR, LoglyzerError::exit_code);
+        std::process::exit(code);
+    }
+}
+
+/// Analyse la ligne de commande; une erreur d'usage sort en 1 et non avec
+/// le 2 de clap, réservé au fichier introuvable.
+fn parse_cli<I, T>(args: I) -> Cli
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    Cli::try_parse_from(args).unwrap_or_else(|err| {
+        let _ = err.print();
+        std::process::exit(if err.use_stderr() { EXIT_ERROR } else { 0 });
+    })
+}
+
+fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cli = parse_cli(std::env::args_os());
+    if let Some(Command::Run {
+        name,
+        file,
+        config,
+        args,
+    }) = &cli.command
+    {
+        let argv = queries::expand(config, name, file, args).map_err(LoglyzerError::Invalid)?;
+        cli = parse_cli(argv);
+    }
+    let top_n = cli.top.max(1);
+    if let Some(size) = cli.buffer_size {
+        tuning::set_buffer_size(size);
+    }
+    if let Some(rate) = cli.max_throughput {
+        throughput::limit_remote(rate);
+    }
+    if cli.nice
+        && let Err(err) = nice::apply()
+    {
+        eprintln!("Priorité non abaissée: {err}");
+    }
+
+    match &cli.command {
+        Some(Command::Prune {
+            file,
+            before,
+            archive_to,
+        }) => {
+            if outfile::same_file(file, archive_to) {
+                return Err(LoglyzerError::Invalid(
+                    "L'archive ne peut pas être le fichier élagué lui-même".to_string(),
+                )
+                .into());
+            }
+            let summary = prune::prune(file, *before, archive_to)
+                .map_err(|err| LoglyzerError::reading(file, err))?;
+            println!(
+                "{} lignes archivées dans {}, {} conservées dans {}",
+                summary.archived,
+                archive_to.display(),
+                summary.kept,
+                file.display()
+            );
+            return Ok(());
+        }
+        Some(Command::Incident {
+            file,
+            from,
+            to,
+            top,
+        }) => {
+            let filter = EntryFilter {
+                errors_only: false,
+                search_lower: None,
+                since: *from,
+                until: *to,
+                exclude: &[],
+            };
+            let parsed = read_logs(file, None, &LineFormat::Text, false, &|e| filter.matches(e))
+                .map_err(|err| LoglyzerError::reading(file, err))?;
+            match incident::build(&parsed.entries, *top, &Categorizer::default()) {
+                Some(report) => {
+                    print!("{}", incident::render(&report, &file.display().to_string()))
+                }
+                None => println!("Aucune erreur dans la fenêtre demandée."),
+            }
+            return Ok(());
+        }
+        Some(Command::SuggestSuppressions { file, top }) => {
+            let parsed = read_logs(file, None, &LineFormat::Text, false, &|_| true)
+                .map_err(|err| LoglyzerError::reading(file, err))?;
+            let report = suppress::suggest(&parsed.entries, *top);
+            print!("{}", suppress::render(&report, &file.display().to_string()));
+            return Ok(());
+        }
+        Some(Command::Verify {
+            file,
+            sequence_regex,
+            tolerance,
+            key_file,
+        }) => {
+            let sequence = sequence_regex
+                .as_deref()
+                .map(Regex::new)
+                .transpose()
+                .map_err(|err| {
+                    LoglyzerError::Invalid(format!("Regex de séquence invalide: {err}"))
+                })?;
+            let key = match key_file {
+                Some(path) => {
+                    Some(fs::read(path).map_err(|err| LoglyzerError::reading(path, err))?)
+                }
+                None => std::env::var("LOGLYZER_VERIFY_KEY")
+                    .ok()
+                    .map(String::into_bytes),
+            };
+            let mut report = verify::verify(file, sequence.as_ref(), *tolerance)
+                .map_err(|err| LoglyzerError::reading(file, err))?;
+            if let Some(key) = key {
+                report.sign(&key);
+            }
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            return Ok(());
+        }
+        Some(Command::SaveQuery { name, config, args }) => {
+            queries::save(config, name, args).map_err(LoglyzerError::Invalid)?;
+            println!("Requête « {name} » enregistrée dans {}", config.display());
+            return Ok(());
+        }
+        Some(Command::Run { .. }) => unreachable!("run est développé avant l'analyse"),
+        None => {}
+    }
+
+    let config = match &cli.config {
+        Some(path) => Config::load(path),
+        None => Ok(Config::default()),
+    };
+    let config = config.and_then(|mut c| {
+        if !cli.no_presets
+            && let Some(dir) = presets::dir()
+        {
+            presets::apply(&mut c, presets::load(&dir)?);
+        }
+        Ok(c)
+    });
+    let rules = config.and_then(|c| {
+        Ok((
+            Categorizer::from_config(&c)?,
+            Tagger::from_config(&c)?,
+            Reclassifier::from_config(&c)?,
+            Theme::from_config(&c.colors, cli.theme)?,
+            c.exclude_windows
+                .iter()
+                .map(|w| parse_exclude_window(w))
+                .collect::<Result<Vec<_>, _>>()?,
+        ))
+    });
+    let rules = rules.and_then(|r| {
+        let containers = cli.docker.iter().map(|c| format!("{}{c}", docker::SCHEME));
+        cli.input.extend(containers.map(PathBuf::from));
+        if let Some(unit) = &cli.journal {
+            cli.input
+                .push(PathBuf::from(format!("{}{unit}", journald::SCHEME)));
+            if let Some(since) = cli.since {
+                journald::since(since);
+            }
+        }
+        #[cfg(feature = "k8s")]
+        if let Some(Some(target)) = &cli.k8s {
+            cli.input
+                .push(PathBuf::from(format!("{}{target}", k8s::SCHEME)));
+            if let Some(since) = cli.since {
+                k8s::since(since);
+            }
+        }
+        cli.input = inputs::expand(&cli.input, &cli.glob)?;
+        cli.validate().map(|_| r)
+    });
+    let (categorizer, tagger, reclassifier, theme) = match rules {
+        Ok((categorizer, tagger, reclassifier, theme, windows)) => {
+            cli.exclude_window.extend(windows);
+            (categorizer, tagger, reclassifier, theme)
+        }
+        Err(err) => return Err(LoglyzerError::Invalid(err).into()),
+    };
+
+    let enricher = cli.enricher()?;
+    let live = follow::LiveRules {
+        reclassifier: &reclassifier,
+        tagger: &tagger,
+        enricher: &enricher,
+    };
+    if let Some(addr) = &cli.drain {
+        let mut drain = logplex::DrainListener::bind(addr)
+            .map_err(|err| LoglyzerError::connect(format!("écouter sur {addr}"), err))?;
+        follow::run_source(
+            &cli,
+            &mut drain,
+            &format!("drain {addr}"),
+            &live,
+            top_n,
+            &theme,
+        )?;
+        return Ok(());
+    }
+
+    if let Some(addr) = &cli.fluent {
+        let mut listener = fluent::FluentListener::bind(addr)
+            .map_err(|err| LoglyzerError::connect(format!("écouter sur {addr}"), err))?;
+        follow::run_source(
+            &cli,
+            &mut listener,
+            &format!("fluent {addr}"),
+            &live,
+            top_n,
+            &theme,
+        )?;
+        return Ok(());
+    }
+
+    #[cfg(feature = "redis")]
+    if let (Some(url), Some(stream)) = (&cli.redis, &cli.stream) {
+        let mut source = redis_source::StreamSource::connect(url, stream)
+            .map_err(|err| LoglyzerError::connect(format!("se connecter à {url}"), err))?;
+        follow::run_source(
+            &cli,
+            &mut source,
+            &format!("redis {stream}"),
+            &live,
+            top_n,
+            &theme,
+        )?;
+        return Ok(());
+    }
+
+    #[cfg(feature = "nats")]
+    if let (Some(url), Some(subject)) = (&cli.nats, &cli.subject) {
+        let mut source = nats_source::NatsSource::connect(url, subject, cli.jetstream.as_deref())
+            .map_err(|err| {
+            LoglyzerError::connect(format!("consommer {subject} sur {url}"), err)
+        })?;
+        follow::run_source(
+            &cli,
+            &mut source,
+            &format!("nats {subject}"),
+            &live,
+            top_n,
+            &theme,
+        )?;
+        return Ok(());
+    }
+
+    #[cfg(feature = "k8s")]
+    if let Some(None) = cli.k8s {
+        let selector = cli.selector.as_deref().unwrap_or_default();
+        let mut pods = k8s::PodLogs::connect(&cli.namespace, selector).map_err(|err| {
+            LoglyzerError::connect(format!("suivre les pods de {}", cli.namespace), err)
+        })?;
+        let label = format!("k8s {}/{selector}", cli.namespace);
+        follow::run_source(&cli, &mut pods, &label, &live, top_n, &theme)?;
+        return Ok(());
+    }
+
+    for input in cli.input.iter().filter(|p| {
+        !is_stdin(p) && !remote::is_url(p) && !is_container(p) && journald::unit(p).is_none()
+    }) {
+        platform::metadata(input).map_err(|err| LoglyzerError::reading(input, err))?;
+    }
+
+    if cli.follow {
+        follow::run(&cli, &live, top_n, &theme)?;
+        return Ok(());
+    }
+
+    if let Some(period) = cli.every {
+        return run_every(
+            &cli,
+            period,
+            &categorizer,
+            &tagger,
+            &reclassifier,
+            top_n,
+            &theme,
+        );
+    }
+
+    install_interrupt_handler();
+    let (analysis, counts) = run_analysis(&cli, &categorizer, &tagger, &reclassifier, top_n)?;
+    if cli.explain {
+        if interrupted() {
+            std::process::exit(EXIT_INTERRUPTED);
+        }
+        return Ok(());
+    }
+    if let (Some(url), Some(analysis)) = (&cli.forward, &analysis) {
+        let mut forwarder = forward::connect(url, cli.forward_max)
+            .map_err(|err| LoglyzerError::connect(format!("joindre {url}"), err))?;
+        for entry in &analysis.entries {
+            forwarder.send(entry)?;
+        }
+        forwarder.flush()?;
+        if cli.verbose {
+            eprintln!(
+                "{} entrées réémises vers {url}, {} écartées par --forward-max",
+                analysis.entries.len() - forwarder.suppressed(),
+                forwarder.suppressed()
+            );
+        }
+    }
+    if let Some(path) = &cli.output {
+        write_output(&cli, analysis.as_ref(), path, top_n, &theme)?;
+    } else if !cli.summary_line {
+        print_rendered(&cli, &render(&cli, analysis.as_ref(), top_n, &theme)?)?;
+    }
+    if cli.summary_line {
+        let entries = analysis.as_ref().map(|a| a.entries.as_slice());
+        println!(
+            "{}",
+            summary_line(entries.unwrap_or_default(), counts.skipped, counts.partial)
+        );
+    }
+
+    let expectations_met = cli.expect.is_empty() || {
+        let entries = analysis.as_ref().map(|a| a.entries.as_slice());
+        let outcomes = expect::evaluate(&cli.expect, entries.unwrap_or_default());
+        eprintln!("{}", expect::render(&outcomes));
+        outcomes.iter().all(|o| o.passed)
+    };
+
+    if interrupted() {
+        std::process::exit(EXIT_INTERRUPTED);
+    }
+    if !expectations_met {
+        std::process::exit(EXIT_THRESHOLD);
+    }
+    if let Some(growth) = analysis.as_ref().and_then(|a| a.stats.growth.as_ref())
+        && !growth.groups.is_empty()
+    {
+        eprintln!(
+            "⚠️  {} gabarit(s) d'erreur en hausse de plus de {}% depuis l'exécution précédente",
+            growth.groups.len(),
+            growth.threshold_pct
+        );
+        std::process::exit(EXIT_THRESHOLD);
+    }
+    match &analysis {
+        Some(a)
+            if a.stats
+                .error_budget
+                .as_ref()
+                .is_some_and(BudgetReport::exceeded) =>
+        {
+            std::process::exit(EXIT_THRESHOLD);
+        }
+        None => {
+            if let Some(code) = cli.no_match_exit_code {
+                std::process::exit(code);
+            }
+        }
+        Some(_) => {}
+    }
     Ok(())
 }
 
@@ -638,6 +4104,115 @@ mod tests {
         assert!(parse_log_line("2024-01-15 [INFO] missing time").is_none());
     }
 
+    #[test]
+    fn highlight_marks_matches_case_insensitively() {
+        let re = search_regex("api");
+        assert_eq!(
+            highlight("API call to api.example", &re, |m| format!(
+                "<mark>{m}</mark>"
+            )),
+            "<mark>API</mark> call to <mark>api</mark>.example"
+        );
+        assert_eq!(
+            highlight("a.b", &search_regex("."), |m| format!("[{m}]")),
+            "a[.]b"
+        );
+    }
+
+    #[test]
+    fn render_vega_embeds_series() {
+        let entries = vec![
+            entry("2024-01-15 10:30:45 [ERROR] API timeout"),
+            entry("2024-01-15 11:31:45 [INFO] OK"),
+        ];
+        let stats = analyze_logs(&entries, 3, None, None, 0, &Categorizer::default(), &[]);
+        let spec: serde_json::Value = serde_json::from_str(&render_vega(&stats)).unwrap();
+        assert_eq!(
+            spec["vconcat"][0]["data"]["values"][0],
+            serde_json::json!({ "hour": "10:00", "errors": 1 })
+        );
+        assert_eq!(
+            spec["vconcat"][1]["data"]["values"]
+                .as_array()
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn render_text_sections_follow_the_report_level() {
+        // « Failure 0 » cinq fois, « Failure 4 » une seule
+        let entries: Vec<_> = (0..5)
+            .flat_map(|i| (i..5).map(move |j| (i, j)))
+            .map(|(i, j)| entry(&format!("2024-01-15 10:3{j}:45 [ERROR] Failure {i}")))
+            .collect();
+        let mut stats = analyze_logs(&entries, 10, None, None, 0, &Categorizer::default(), &[]);
+        stats.metadata = Some(ReportMetadata::new(Vec::new(), Duration::ZERO));
+        let text = |detail| render_text(&stats, 10, &Theme::default(), Locale::default(), detail);
+
+        let full = text(ReportDetail::Full);
+        assert!(full.contains("Failure 4") && full.contains("Volume by hour"));
+        let standard = text(ReportDetail::Standard);
+        assert!(standard.contains("Errors by hour") && !standard.contains("Volume by hour"));
+        let brief = text(ReportDetail::Brief);
+        assert!(brief.contains("Top errors (max 3)") && brief.contains("Failure 2"));
+        assert!(!brief.contains("Failure 3") && !brief.contains("Errors by hour"));
+        assert!(brief.contains("Report metadata:") && full.contains("Report metadata:"));
+    }
+
+    #[test]
+    fn by_file_shows_shares_and_errors_over_time() {
+        let file = |lines: &[&str]| ParsedLogs {
+            entries: lines.iter().map(|l| entry(l)).collect(),
+            ..ParsedLogs::default()
+        };
+        let quiet = FileStats::new(
+            Path::new("node-a.log"),
+            &file(&[
+                "2024-01-15 10:30:45 [INFO] OK",
+                "2024-01-15 11:10:00 [ERROR] Database down",
+            ]),
+        );
+        let noisy = FileStats::new(
+            Path::new("node-b.log"),
+            &file(&[
+                "2024-01-15 10:06:00 [ERROR] Disk full",
+                "2024-01-15 10:05:00 [ERROR] Disk full",
+            ]),
+        );
+        assert_eq!(noisy.first_error.as_deref(), Some("2024-01-15 10:05:00"));
+        assert_eq!(noisy.errors_by_hour["10:00"], 2);
+        assert_eq!(quiet.errors_by_hour["11:00"], 1);
+
+        let mut stats = analyze_logs(&[], 10, None, None, 0, &Categorizer::default(), &[]);
+        stats.by_file = Some(vec![quiet, noisy]);
+        let text = |detail| render_text(&stats, 10, &Theme::default(), Locale::default(), detail);
+        let full = text(ReportDetail::Full);
+        assert!(full.contains("| node-b.log | 2       | 50.0% | 2      | 66.7%"));
+        assert!(full.contains("Errors by file and hour:"));
+        assert!(!text(ReportDetail::Standard).contains("Errors by file and hour:"));
+    }
+
+    #[test]
+    fn changed_materially_compares_level_counts() {
+        let before = HashMap::from([("ERROR".to_string(), 10), ("INFO".to_string(), 100)]);
+        let mut after = before.clone();
+        after.insert("ERROR".to_string(), 11);
+        assert!(!changed_materially(&before, &after, 20.0));
+        after.insert("ERROR".to_string(), 13);
+        assert!(changed_materially(&before, &after, 20.0));
+    }
+
+    #[test]
+    fn timestamped_path_keeps_extension() {
+        let at = parse_datetime("2024-01-15 10:30:00").unwrap();
+        assert_eq!(
+            timestamped_path(Path::new("out/report.json"), at),
+            PathBuf::from("out/report-20240115T103000.json")
+        );
+    }
+
     #[test]
     fn filter_entries_respects_flags() {
         let entries = vec![
@@ -647,9 +4222,175 @@ mod tests {
         ];
 
         let since = parse_datetime("2024-01-15 10:30:00").ok();
-        let filtered = filter_entries(entries, true, Some("api"), since, None);
+        let filtered = filter_entries(entries.clone(), true, Some("api"), since, None, &[]);
         assert_eq!(filtered.len(), 1);
         assert_eq!(filtered[0].message, "API timeout");
+
+        let maintenance = parse_exclude_window("2024-01-15 10:30..2024-01-15 10:31").unwrap();
+        let filtered = filter_entries(entries, false, None, None, None, &[maintenance]);
+        assert_eq!(filtered.len(), 2);
+        assert_eq!(filtered[0].message, "OK");
+        assert!(parse_exclude_window("2024-01-15 10:31..2024-01-15 10:30").is_err());
+    }
+
+    #[test]
+    fn fixed_layout_fast_path_agrees_with_regex() {
+        let lines = [
+            "2024-01-15 10:30:45 [ERROR] API timeout",
+            "2024-01-15 10:30:45\t[warn]   disk 91% ",
+            "2024-01-15 10:30:45 [INFO]\u{a0}non-breaking",
+            "2024-01-15 10:30:45  [DEBUG] x",
+            "2024-01-15 10:30:45 [ERROR]   ",
+            "2024-01-15 10:30:45 [ERROR]",
+            "2024-01-15 10:30:45 [ERROR]no space",
+            "2024-01-15  10:30:45 [ERROR] double space",
+            "2024-02-30 10:30:45 [ERROR] bad date",
+            "2024-01-15 10:30:45 [FATAL] unknown level",
+            "2024-01-15 10:30:45 [ÉRREUR] unicode level",
+            "2024-01-15T10:30:45 [ERROR] iso",
+        ];
+        for line in lines {
+            let expected = parse_with_regex(line);
+            let fast = parse_fixed_layout(line);
+            assert!(fast.is_none() || fast == expected, "{line:?}");
+            assert_eq!(parse_log_line(line), expected, "{line:?}");
+        }
+        assert!(parse_fixed_layout(lines[0]).is_some());
+        assert!(parse_fixed_layout(lines[1]).is_some());
+    }
+
+    #[test]
+    fn summary_line_is_space_separated_key_values() {
+        let entries: Vec<LogEntry> = [
+            "2024-01-15 10:31:00 [WARNING] slow",
+            "2024-01-15 10:30:45 [ERROR] timeout",
+            "2024-01-15 10:32:10 [INFO] ok",
+        ]
+        .iter()
+        .map(|l| parse_log_line(l).unwrap())
+        .collect();
+        assert_eq!(
+            summary_line(&entries, 2, false),
+            "total=3 errors=1 warn=1 skipped=2 \
+             window=2024-01-15T10:30:45/2024-01-15T10:32:10"
+        );
+        assert_eq!(
+            summary_line(&[], 0, true),
+            "total=0 errors=0 warn=0 skipped=0 window=- partial=true"
+        );
+    }
+
+    #[test]
+    fn read_logs_drops_entries_rejected_while_parsing() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        fs::write(
+            &path,
+            "2024-01-15 10:30:45 [ERROR] API timeout\n\
+             garbage\n\
+             2024-01-15 10:31:45 [INFO] OK\n\
+             2024-01-15 10:32:45 [ERROR] Database down\n",
+        )
+        .unwrap();
+        let prepare = |e: &mut LogEntry| {
+            e.tags.push("seen".to_string());
+            e.level == LogLevel::Error
+        };
+        for parsed in [
+            read_logs(&path, None, &LineFormat::Text, false, &prepare).unwrap(),
+            read_logs_parallel(
+                &path,
+                None,
+                &LineFormat::Text,
+                false,
+                tuning::DEFAULT_CHUNK,
+                &prepare,
+            )
+            .unwrap(),
+        ] {
+            assert_eq!(parsed.entries.len(), 2);
+            assert_eq!(parsed.entries[1].message, "Database down");
+            assert_eq!(parsed.entries[0].tags, ["seen"]);
+            assert_eq!((parsed.dropped, parsed.skipped), (1, 1));
+        }
+    }
+
+    #[test]
+    fn read_logs_decompresses_gzip_files() {
+        use flate2::{Compression, write::GzEncoder};
+
+        let dir = tempfile::tempdir().unwrap();
+        // Rotation sans extension .gz: seule la signature compte
+        let path = dir.path().join("app.log.1");
+        let mut gz = GzEncoder::new(fs::File::create(&path).unwrap(), Compression::default());
+        gz.write_all(
+            b"2024-01-15 10:30:45 [ERROR] API timeout\n\
+              garbage\n\
+              2024-01-15 10:31:45 [INFO] OK\n",
+        )
+        .unwrap();
+        gz.finish().unwrap();
+
+        let progress = ProgressBar::hidden();
+        for parsed in [
+            read_logs(&path, Some(&progress), &LineFormat::Text, false, &|_| true).unwrap(),
+            read_logs_parallel(
+                &path,
+                None,
+                &LineFormat::Text,
+                false,
+                tuning::DEFAULT_CHUNK,
+                &|_| true,
+            )
+            .unwrap(),
+        ] {
+            assert_eq!(parsed.entries.len(), 2);
+            assert_eq!(parsed.entries[1].message, "OK");
+            assert_eq!(parsed.skipped, 1);
+        }
+        assert_eq!(progress.position(), fs::metadata(&path).unwrap().len());
+    }
+
+    #[test]
+    fn multiline_attaches_stack_traces_to_the_previous_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        fs::write(
+            &path,
+            "orphan line\n\
+             2024-01-15 10:30:45 [ERROR] Unhandled exception\n\
+             java.lang.NullPointerException: null\n\
+             \tat com.shop.Cart.total(Cart.java:42)\n\
+             \n\
+             2024-01-15 10:31:45 [INFO] OK\n\
+             2024-01-15 10:32:45 [ERROR] Traceback (most recent call last):\n\
+             \x20 File \"app.py\", line 7, in <module>\n\
+             KeyError: 'id'\n",
+        )
+        .unwrap();
+        let prepare = |e: &mut LogEntry| e.level == LogLevel::Error;
+        for parsed in [
+            read_logs(&path, None, &LineFormat::Text, true, &prepare).unwrap(),
+            read_logs_parallel(
+                &path,
+                None,
+                &LineFormat::Text,
+                true,
+                tuning::DEFAULT_CHUNK,
+                &prepare,
+            )
+            .unwrap(),
+        ] {
+            assert_eq!(parsed.entries.len(), 2);
+            assert_eq!(
+                parsed.entries[0].message,
+                "Unhandled exception\n\
+                 java.lang.NullPointerException: null\n\
+                 \tat com.shop.Cart.total(Cart.java:42)"
+            );
+            assert!(parsed.entries[1].message.ends_with("\nKeyError: 'id'"));
+            assert_eq!((parsed.dropped, parsed.skipped), (1, 2));
+        }
     }
 
     #[test]
@@ -661,11 +4402,47 @@ mod tests {
             entry("2024-01-15 10:33:45 [WARNING] High CPU"),
         ];
 
-        let stats = analyze_logs(&entries, 3, None, None, 0);
+        let stats = analyze_logs(&entries, 3, None, None, 0, &Categorizer::default(), &[]);
         assert_eq!(stats.total_entries, 4);
         assert_eq!(stats.by_level.get("ERROR"), Some(&2));
         assert_eq!(stats.by_level.get("INFO"), Some(&1));
         assert_eq!(stats.by_level.get("WARNING"), Some(&1));
         assert_eq!(stats.top_errors.first().map(|e| e.count), Some(2));
+        assert_eq!(stats.bytes_by_level.get("INFO"), Some(&30));
+        assert_eq!(stats.bytes_by_level.get("ERROR"), Some(&(2 * 40)));
+        assert_eq!(stats.bytes_by_hour.get("10:00"), Some(&stats.total_bytes));
+        assert_eq!(stats.entries_by_bucket_by_level["10:00"]["ERROR"], 2);
+        assert_eq!(stats.entries_by_bucket_by_level["10:00"]["WARNING"], 1);
+        assert_eq!(stats.errors_by_category.get("network"), Some(&2));
+        assert_eq!(
+            stats.errors_by_category_by_hour["network"].get("10:00"),
+            Some(&2)
+        );
+        assert!(stats.groups.is_empty());
+    }
+
+    #[test]
+    fn analyze_logs_groups_by_tag() {
+        let mut entries = vec![
+            entry("2024-01-15 10:30:45 [ERROR] Stripe charge failed"),
+            entry("2024-01-15 10:31:45 [INFO] Invoice sent"),
+            entry("2024-01-15 10:32:45 [INFO] OK"),
+        ];
+        entries[0].tags = vec!["payment".to_string()];
+        entries[1].tags = vec!["payment".to_string()];
+
+        let stats = analyze_logs(
+            &entries,
+            3,
+            None,
+            None,
+            0,
+            &Categorizer::default(),
+            &[Dimension::Tag],
+        );
+        assert_eq!(stats.groups["payment"].total, 2);
+        assert_eq!(stats.groups["payment"].bytes, 49 + 40);
+        assert_eq!(stats.groups["payment"].by_level.get("ERROR"), Some(&1));
+        assert_eq!(stats.groups[UNTAGGED].total, 1);
     }
 }
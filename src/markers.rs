@@ -0,0 +1,168 @@
+use crate::lookup::split_csv_line;
+use crate::{LogEntry, LogLevel, noise};
+use chrono::{DateTime, NaiveDateTime};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// Nombre maximal de nouveaux gabarits d'erreur cités par marqueur
+const NEW_ERRORS: usize = 3;
+
+/// Événement daté (déploiement, bascule...) annoté sur les séries
+#[derive(Debug, Clone, PartialEq)]
+pub struct Marker {
+    pub at: NaiveDateTime,
+    pub label: String,
+}
+
+fn parse_timestamp(value: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M"))
+        .ok()
+        .or_else(|| {
+            DateTime::parse_from_rfc3339(value)
+                .ok()
+                .map(|d| d.naive_utc())
+        })
+}
+
+/// Lit un CSV `timestamp,label`; une première ligne non datée est prise
+/// pour un en-tête.
+pub fn load(path: &Path) -> Result<Vec<Marker>, String> {
+    let raw = fs::read_to_string(path)
+        .map_err(|e| format!("Impossible de lire les marqueurs {}: {e}", path.display()))?;
+    parse(&raw).map_err(|line| format!("Marqueur invalide dans {}: {line}", path.display()))
+}
+
+fn parse(raw: &str) -> Result<Vec<Marker>, String> {
+    let mut markers = Vec::new();
+    for (i, line) in raw.lines().filter(|l| !l.trim().is_empty()).enumerate() {
+        let values = split_csv_line(line);
+        match parse_timestamp(&values[0]) {
+            Some(at) => markers.push(Marker {
+                at,
+                label: values.get(1).cloned().unwrap_or_default(),
+            }),
+            None if i == 0 => {}
+            None => return Err(line.to_string()),
+        }
+    }
+    markers.sort_by_key(|m| m.at);
+    Ok(markers)
+}
+
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct WindowStats {
+    pub entries: usize,
+    pub errors: usize,
+    pub error_rate: f64,
+}
+
+/// Comparaison des fenêtres de même durée qui encadrent un marqueur
+#[derive(Debug, Serialize)]
+pub struct MarkerImpact {
+    pub label: String,
+    pub at: String,
+    pub before: WindowStats,
+    pub after: WindowStats,
+    /// Gabarits d'erreur absents avant le marqueur, les plus fréquents d'abord
+    pub new_errors: Vec<String>,
+}
+
+fn window_stats<'a>(entries: impl Iterator<Item = &'a LogEntry>) -> WindowStats {
+    let mut stats = WindowStats::default();
+    for entry in entries {
+        stats.entries += 1;
+        if entry.level == LogLevel::Error {
+            stats.errors += 1;
+        }
+    }
+    if stats.entries > 0 {
+        stats.error_rate = stats.errors as f64 / stats.entries as f64 * 100.0;
+    }
+    stats
+}
+
+pub fn impacts(entries: &[LogEntry], markers: &[Marker], window: Duration) -> Vec<MarkerImpact> {
+    let window = chrono::Duration::from_std(window).unwrap_or(chrono::Duration::MAX);
+    markers
+        .iter()
+        .map(|marker| {
+            let before: Vec<&LogEntry> = entries
+                .iter()
+                .filter(|e| e.datetime < marker.at && marker.at - e.datetime <= window)
+                .collect();
+            let after: Vec<&LogEntry> = entries
+                .iter()
+                .filter(|e| e.datetime >= marker.at && e.datetime - marker.at < window)
+                .collect();
+
+            let known: HashSet<String> = before
+                .iter()
+                .filter(|e| e.level == LogLevel::Error)
+                .map(|e| noise::normalize(&e.message))
+                .collect();
+            let mut new_errors: Vec<(String, usize)> = Vec::new();
+            for entry in after.iter().filter(|e| e.level == LogLevel::Error) {
+                let template = noise::normalize(&entry.message);
+                if known.contains(&template) {
+                    continue;
+                }
+                match new_errors.iter_mut().find(|(t, _)| *t == template) {
+                    Some((_, count)) => *count += 1,
+                    None => new_errors.push((template, 1)),
+                }
+            }
+            new_errors.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+            new_errors.truncate(NEW_ERRORS);
+
+            MarkerImpact {
+                label: marker.label.clone(),
+                at: marker.at.format("%Y-%m-%d %H:%M:%S").to_string(),
+                before: window_stats(before.into_iter()),
+                after: window_stats(after.into_iter()),
+                new_errors: new_errors.into_iter().map(|(t, _)| t).collect(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_log_line;
+
+    #[test]
+    fn compares_windows_around_each_marker() {
+        let markers = parse("timestamp,label\n2024-01-15 10:42,\"deploy v2, api\"\n").unwrap();
+        assert_eq!(markers[0].label, "deploy v2, api");
+        assert!(parse("2024-01-15 10:42,a\nyesterday,b\n").is_err());
+
+        let entries: Vec<LogEntry> = [
+            "2024-01-15 09:30:00 [ERROR] old failure",
+            "2024-01-15 10:00:00 [ERROR] cache miss key=1",
+            "2024-01-15 10:30:00 [INFO] ok",
+            "2024-01-15 10:45:00 [ERROR] null pointer in handler",
+            "2024-01-15 10:50:00 [ERROR] cache miss key=2",
+            "2024-01-15 11:00:00 [ERROR] null pointer in handler",
+        ]
+        .iter()
+        .map(|l| parse_log_line(l).unwrap())
+        .collect();
+
+        let impact = &impacts(&entries, &markers, Duration::from_secs(3600))[0];
+        assert_eq!(impact.at, "2024-01-15 10:42:00");
+        assert_eq!(
+            impact.before,
+            WindowStats {
+                entries: 2,
+                errors: 1,
+                error_rate: 50.0
+            }
+        );
+        assert_eq!(impact.after.errors, 3);
+        assert_eq!(impact.new_errors, vec!["null pointer in handler"]);
+    }
+}
@@ -0,0 +1,108 @@
+use crate::checksums::ReadDigest;
+use serde::Serialize;
+use std::path::Path;
+use std::time::Duration;
+
+/// Entrée analysée, telle qu'elle a été lue
+#[derive(Debug, Serialize)]
+pub struct InputMeta {
+    pub file: String,
+    /// Octets lus, compressés le cas échéant; taille annoncée si la lecture
+    /// a été interrompue
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<u64>,
+    /// Absente si la lecture a été interrompue avant la fin
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+}
+
+impl InputMeta {
+    pub fn new(path: &Path, size: Option<u64>, digest: Option<ReadDigest>) -> Self {
+        InputMeta {
+            file: path.display().to_string(),
+            bytes: digest.as_ref().map(|d| d.bytes).or(size),
+            sha256: digest.map(|d| d.sha256),
+        }
+    }
+}
+
+/// Contexte joint à chaque rapport, pour qu'il se comprenne seul une fois
+/// versé dans un ticket: outil, commande, entrées, durée et machine
+#[derive(Debug, Serialize)]
+pub struct ReportMetadata {
+    pub version: &'static str,
+    pub command_line: String,
+    pub inputs: Vec<InputMeta>,
+    /// Fin de l'analyse, en UTC
+    pub generated_at: String,
+    pub wall_time_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+}
+
+impl ReportMetadata {
+    pub fn new(inputs: Vec<InputMeta>, wall_time: Duration) -> Self {
+        ReportMetadata {
+            version: env!("CARGO_PKG_VERSION"),
+            command_line: command_line(std::env::args()),
+            inputs,
+            generated_at: crate::now_utc().format("%Y-%m-%d %H:%M:%S").to_string(),
+            wall_time_ms: wall_time.as_millis() as u64,
+            host: crate::platform::hostname(),
+        }
+    }
+}
+
+/// Ligne de commande à rejouer dans un shell: programme sans son chemin,
+/// arguments entre apostrophes s'ils contiennent espaces ou caractères spéciaux
+fn command_line(args: impl IntoIterator<Item = String>) -> String {
+    let mut args = args.into_iter();
+    let program = args.next().unwrap_or_default();
+    let program = Path::new(&program)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or(program);
+    std::iter::once(program)
+        .chain(args.map(|arg| quote(&arg)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_alphanumeric() || "-_./:=,@%+".contains(c));
+    if plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quotes_the_command_line_for_a_shell() {
+        let args = [
+            "/usr/local/bin/loglyzer",
+            "app.log",
+            "--search",
+            "can't connect",
+            "--since",
+            "2024-01-15 10:00:00",
+        ];
+        assert_eq!(
+            command_line(args.map(String::from)),
+            r"loglyzer app.log --search 'can'\''t connect' --since '2024-01-15 10:00:00'"
+        );
+
+        let digest = ReadDigest::of(b"abc");
+        let input = InputMeta::new(Path::new("app.log"), Some(10), Some(digest));
+        assert_eq!(input.bytes, Some(3));
+        let partial = InputMeta::new(Path::new("app.log"), Some(10), None);
+        assert_eq!((partial.bytes, partial.sha256), (Some(10), None));
+    }
+}
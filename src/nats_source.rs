@@ -0,0 +1,116 @@
+use crate::follow::{CHANNEL_CAPACITY, LineSource};
+use async_nats::jetstream::{self, consumer::DeliverPolicy, consumer::pull::OrderedConfig};
+use futures_util::StreamExt;
+use std::error::Error;
+use std::io;
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use tokio::runtime::Runtime;
+
+type Lines = SyncSender<(String, Option<String>)>;
+
+/// Découpe la charge utile d'un message (une ou plusieurs lignes) et
+/// l'étiquette avec son sujet. Bloque tant que la file est pleine: appelé
+/// depuis le runtime, via `block_in_place`.
+fn forward_payload(tx: &Lines, subject: &str, payload: &[u8]) -> bool {
+    String::from_utf8_lossy(payload)
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .all(|line| {
+            tx.send((line.to_string(), Some(format!("subject={subject}"))))
+                .is_ok()
+        })
+}
+
+/// Messages publiés sur un sujet NATS, lus directement ou, avec `stream`,
+/// via un consommateur JetStream ordonné qui ne livre que les nouveaux messages.
+pub struct NatsSource {
+    lines: Receiver<(String, Option<String>)>,
+    // Garde la tâche de lecture en vie tant que la source existe
+    _runtime: Runtime,
+}
+
+impl NatsSource {
+    pub fn connect(
+        url: &str,
+        subject: &str,
+        stream: Option<&str>,
+    ) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?;
+        let client = runtime.block_on(async_nats::connect(url))?;
+        let (tx, lines) = mpsc::sync_channel(CHANNEL_CAPACITY);
+        let subject = subject.to_string();
+
+        match stream {
+            None => {
+                let mut subscriber = runtime.block_on(client.subscribe(subject))?;
+                runtime.spawn(async move {
+                    while let Some(message) = subscriber.next().await {
+                        if !tokio::task::block_in_place(|| {
+                            forward_payload(&tx, &message.subject, &message.payload)
+                        }) {
+                            break;
+                        }
+                    }
+                });
+            }
+            Some(name) => {
+                let mut messages = runtime.block_on(async {
+                    let stream = jetstream::new(client).get_stream(name).await?;
+                    let consumer = stream
+                        .create_consumer(OrderedConfig {
+                            filter_subject: subject,
+                            deliver_policy: DeliverPolicy::New,
+                            ..OrderedConfig::default()
+                        })
+                        .await?;
+                    Ok::<_, Box<dyn Error + Send + Sync>>(consumer.messages().await?)
+                })?;
+                runtime.spawn(async move {
+                    while let Some(message) = messages.next().await {
+                        let message = match message {
+                            Ok(message) => message,
+                            Err(err) => {
+                                eprintln!("Lecture JetStream interrompue: {err}");
+                                break;
+                            }
+                        };
+                        if !tokio::task::block_in_place(|| {
+                            forward_payload(&tx, &message.subject, &message.payload)
+                        }) {
+                            break;
+                        }
+                    }
+                });
+            }
+        }
+
+        Ok(NatsSource {
+            lines,
+            _runtime: runtime,
+        })
+    }
+}
+
+impl LineSource for NatsSource {
+    fn poll_lines(&mut self) -> io::Result<Vec<(String, Option<String>)>> {
+        Ok(self.lines.try_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_payload_into_tagged_lines() {
+        let (tx, rx) = mpsc::sync_channel(CHANNEL_CAPACITY);
+        let payload = b"2024-01-15 10:30:45 [ERROR] a\n\n2024-01-15 10:30:46 [INFO] b\n";
+        assert!(forward_payload(&tx, "logs.api", payload));
+        let lines: Vec<_> = rx.try_iter().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1].0, "2024-01-15 10:30:46 [INFO] b");
+        assert_eq!(lines[1].1.as_deref(), Some("subject=logs.api"));
+    }
+}
@@ -0,0 +1,74 @@
+use crate::throughput::Throttled;
+use std::io::{self, Read};
+use std::sync::OnceLock;
+use std::thread;
+
+/// Valeur de `nice` visée (de -20 à 19 sous Unix); une valeur déjà plus
+/// haute est conservée
+const NICENESS: i32 = 10;
+
+/// Débit de lecture des fichiers d'entrée avec `--nice`, en octets par seconde
+pub const READ_RATE: u64 = 16 * 1024 * 1024;
+
+/// Débit plafonné pour toutes les lectures de fichiers, fixé une fois par `apply`
+static READ_LIMIT: OnceLock<u64> = OnceLock::new();
+
+/// Fils de calcul laissés à l'analyse: le quart des cœurs, un au moins
+pub fn thread_cap() -> usize {
+    thread::available_parallelism()
+        .map(|n| n.get() / 4)
+        .unwrap_or(1)
+        .max(1)
+}
+
+#[cfg(unix)]
+fn lower_priority() -> io::Result<()> {
+    // SAFETY: getpriority et setpriority n'ont pas de précondition. Sous
+    // Linux, elles ne visent que le fil appelant, mais les fils créés ensuite
+    // en héritent.
+    let current = unsafe { libc::getpriority(libc::PRIO_PROCESS, 0) };
+    if unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, current.max(NICENESS)) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn lower_priority() -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!("priorité +{NICENESS} réservée aux systèmes Unix"),
+    ))
+}
+
+/// Mode `--nice`: priorité abaissée, pool rayon réduit à `thread_cap` fils et
+/// lectures limitées à `READ_RATE`. À appeler avant tout calcul parallèle.
+/// Une priorité qui ne peut être abaissée n'empêche pas le reste.
+pub fn apply() -> io::Result<()> {
+    // Avant la création du pool, dont les fils héritent de la priorité
+    let priority = lower_priority();
+    let _ = READ_LIMIT.set(READ_RATE);
+    // Échoue seulement si le pool global existe déjà: il garde alors sa taille
+    let _ = rayon::ThreadPoolBuilder::new()
+        .num_threads(thread_cap())
+        .build_global();
+    priority
+}
+
+/// Applique le débit de `--nice` à une lecture de fichier, inchangée sinon
+pub fn throttle<'a>(reader: impl Read + 'a) -> Box<dyn Read + 'a> {
+    match READ_LIMIT.get() {
+        Some(&rate) => Box::new(Throttled::new(reader, rate)),
+        None => Box::new(reader),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn caps_analysis_threads() {
+        assert!(thread_cap() >= 1);
+    }
+}
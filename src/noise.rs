@@ -0,0 +1,546 @@
+use crate::{LogEntry, LogLevel};
+use chrono::NaiveDateTime;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+/// Parties variables remplacées par un jeton, dans l'ordre d'application
+static VARIABLES: Lazy<Vec<(Regex, &str)>> = Lazy::new(|| {
+    [
+        (r#""[^"]*"|'[^']*'"#, "<str>"),
+        (
+            r"\b[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}\b",
+            "<uuid>",
+        ),
+        (r"\b\d{1,3}(?:\.\d{1,3}){3}(?::\d+)?\b", "<ip>"),
+        (r"\b0x[0-9a-fA-F]+\b|\b[0-9a-fA-F]{12,}\b", "<hex>"),
+        (r"\d+(?:\.\d+)?", "<num>"),
+    ]
+    .into_iter()
+    .map(|(re, token)| (Regex::new(re).unwrap(), token))
+    .collect()
+});
+
+/// Volume d'un gabarit de message DEBUG/INFO
+#[derive(Debug, Serialize)]
+pub struct NoiseTemplate {
+    pub level: String,
+    pub template: String,
+    pub count: usize,
+    /// Taille estimée des lignes brutes, saut de ligne compris
+    pub bytes: u64,
+    /// Part du volume total analysé, en %
+    pub share_pct: f64,
+}
+
+/// Score de bruit d'un gabarit, tous niveaux confondus
+#[derive(Debug, Serialize)]
+pub struct TemplateScore {
+    pub level: String,
+    pub template: String,
+    pub count: usize,
+    /// Entropie des messages concrets du gabarit, normalisée entre 0 (toujours
+    /// le même texte) et 1 (jamais deux fois le même)
+    pub variability: f64,
+    /// Part des entrées (en %) pondérée par `1 - variability`
+    pub score: f64,
+}
+
+/// Remplace identifiants, nombres, adresses et chaînes citées par des jetons
+/// pour regrouper les messages émis par la même instruction de log.
+pub fn normalize(message: &str) -> String {
+    VARIABLES
+        .iter()
+        .fold(message.to_string(), |msg, (re, token)| {
+            re.replace_all(&msg, *token).into_owned()
+        })
+}
+
+/// Gabarits DEBUG/INFO les plus volumineux, pour repérer les instructions à
+/// rétrograder afin de réduire les coûts d'ingestion.
+pub fn noisy_templates(entries: &[LogEntry], top_n: usize) -> Vec<NoiseTemplate> {
+    let mut total_bytes = 0;
+    let mut by_template: HashMap<(&str, String), (usize, u64)> = HashMap::new();
+    for entry in entries {
+        let bytes = entry.line_bytes();
+        total_bytes += bytes;
+        if matches!(entry.level, LogLevel::Debug | LogLevel::Info) {
+            let slot = by_template
+                .entry((entry.level.as_str(), normalize(&entry.message)))
+                .or_default();
+            slot.0 += 1;
+            slot.1 += bytes;
+        }
+    }
+
+    let mut templates: Vec<_> = by_template
+        .into_iter()
+        .map(|((level, template), (count, bytes))| NoiseTemplate {
+            level: level.to_string(),
+            template,
+            count,
+            bytes,
+            share_pct: bytes as f64 / total_bytes.max(1) as f64 * 100.0,
+        })
+        .collect();
+    templates.sort_by(|a, b| {
+        b.bytes
+            .cmp(&a.bytes)
+            .then_with(|| a.template.cmp(&b.template))
+    });
+    templates.truncate(top_n);
+    templates
+}
+
+/// Première apparition d'un gabarit d'erreur dans la fenêtre analysée
+#[derive(Debug, Serialize)]
+pub struct FirstOccurrence {
+    pub template: String,
+    pub first_seen: String,
+    pub count: usize,
+}
+
+/// Gabarit d'erreur silencieux depuis au moins `--resolved-after` à la fin
+/// de la fenêtre: probablement résolu
+#[derive(Debug, Serialize)]
+pub struct ResolvedError {
+    pub template: String,
+    pub first_seen: String,
+    pub last_seen: String,
+    pub count: usize,
+}
+
+/// Gabarit d'erreur dont le volume a crû de plus de `--growth-alert` % depuis
+/// l'exécution précédente; `change_pct` vaut `None` pour un gabarit nouveau
+#[derive(Debug, Serialize)]
+pub struct GrowingError {
+    pub template: String,
+    pub previous: usize,
+    pub current: usize,
+    pub change_pct: Option<f64>,
+}
+
+/// Gabarit passé d'ERROR à un niveau inférieur (`downgrade`, souvent une
+/// alerte réduite au silence) ou l'inverse (`upgrade`)
+#[derive(Debug, Serialize)]
+pub struct SeverityDrift {
+    pub template: String,
+    pub direction: &'static str,
+    pub from: &'static str,
+    pub to: &'static str,
+    /// Début de la série d'entrées au nouveau niveau
+    pub since: String,
+    pub count: usize,
+}
+
+/// Première et dernière occurrence, et nombre d'entrées, de chaque gabarit d'erreur
+fn error_templates(entries: &[LogEntry]) -> HashMap<String, (NaiveDateTime, NaiveDateTime, usize)> {
+    let mut by_template: HashMap<String, (NaiveDateTime, NaiveDateTime, usize)> = HashMap::new();
+    for entry in entries.iter().filter(|e| e.level == LogLevel::Error) {
+        let slot = by_template.entry(normalize(&entry.message)).or_insert((
+            entry.datetime,
+            entry.datetime,
+            0,
+        ));
+        slot.0 = slot.0.min(entry.datetime);
+        slot.1 = slot.1.max(entry.datetime);
+        slot.2 += 1;
+    }
+    by_template
+}
+
+fn format_time(datetime: NaiveDateTime) -> String {
+    datetime.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// Chronologie des types d'erreur distincts par date d'apparition: les
+/// `top_n` apparus le plus récemment, du plus ancien au plus récent, pour
+/// repérer l'erreur introduite par un déploiement.
+pub fn first_occurrences(entries: &[LogEntry], top_n: usize) -> Vec<FirstOccurrence> {
+    let mut timeline: Vec<_> = error_templates(entries).into_iter().collect();
+    timeline.sort_by(|a, b| a.1.0.cmp(&b.1.0).then_with(|| a.0.cmp(&b.0)));
+    let skip = timeline.len().saturating_sub(top_n);
+    timeline
+        .into_iter()
+        .skip(skip)
+        .map(|(template, (first, _, count))| FirstOccurrence {
+            template,
+            first_seen: format_time(first),
+            count,
+        })
+        .collect()
+}
+
+/// Nombre d'entrées par gabarit d'erreur, conservé d'une exécution à l'autre
+/// par `--state`
+pub fn error_counts(entries: &[LogEntry]) -> BTreeMap<String, usize> {
+    error_templates(entries)
+        .into_iter()
+        .map(|(template, (_, _, count))| (template, count))
+        .collect()
+}
+
+/// Gabarits d'erreur en hausse de plus de `threshold` % par rapport aux
+/// comptes de l'exécution précédente (nouveaux gabarits compris), les plus
+/// fortes hausses d'abord: une dérive lente se voit avant de devenir critique.
+pub fn growing(
+    previous: &BTreeMap<String, usize>,
+    current: &BTreeMap<String, usize>,
+    threshold: f64,
+    top_n: usize,
+) -> Vec<GrowingError> {
+    let mut growing: Vec<GrowingError> = current
+        .iter()
+        .filter_map(|(template, &count)| {
+            let before = previous.get(template).copied().unwrap_or(0);
+            let change_pct =
+                (before > 0).then(|| (count as f64 - before as f64) / before as f64 * 100.0);
+            change_pct
+                .is_none_or(|pct| pct > threshold)
+                .then(|| GrowingError {
+                    template: template.clone(),
+                    previous: before,
+                    current: count,
+                    change_pct,
+                })
+        })
+        .collect();
+    growing.sort_by(|a, b| {
+        let key = |g: &GrowingError| g.change_pct.unwrap_or(f64::INFINITY);
+        key(b)
+            .total_cmp(&key(a))
+            .then_with(|| b.current.cmp(&a.current))
+            .then_with(|| a.template.cmp(&b.template))
+    });
+    growing.truncate(top_n);
+    growing
+}
+
+/// Premier niveau, puis dernier niveau de chaque gabarit avec le début et la
+/// taille de sa série d'entrées à ce niveau, dans l'ordre chronologique
+fn level_runs(
+    entries: &[LogEntry],
+) -> HashMap<String, (&LogLevel, &LogLevel, NaiveDateTime, usize)> {
+    let mut chronological: Vec<&LogEntry> = entries.iter().collect();
+    chronological.sort_by_key(|e| e.datetime);
+    let mut runs: HashMap<String, (&LogLevel, &LogLevel, NaiveDateTime, usize)> = HashMap::new();
+    for entry in chronological {
+        let slot = runs.entry(normalize(&entry.message)).or_insert((
+            &entry.level,
+            &entry.level,
+            entry.datetime,
+            0,
+        ));
+        if *slot.1 != entry.level {
+            *slot = (slot.0, &entry.level, entry.datetime, 0);
+        }
+        slot.3 += 1;
+    }
+    runs
+}
+
+/// Dernier niveau de chaque gabarit, conservé d'une exécution à l'autre par
+/// `--state`
+pub fn template_levels(entries: &[LogEntry]) -> BTreeMap<String, String> {
+    level_runs(entries)
+        .into_iter()
+        .map(|(template, (_, last, _, _))| (template, last.as_str().to_string()))
+        .collect()
+}
+
+/// Gabarits dont le niveau a franchi la limite ERROR: niveau de l'exécution
+/// précédente (`previous`, via `--state`) ou de la première occurrence dans
+/// la fenêtre, comparé au dernier. Les rétrogradations d'abord, les plus
+/// récentes en tête.
+pub fn severity_drift(
+    entries: &[LogEntry],
+    previous: Option<&BTreeMap<String, String>>,
+    top_n: usize,
+) -> Vec<SeverityDrift> {
+    let mut drifts: Vec<SeverityDrift> = level_runs(entries)
+        .into_iter()
+        .filter_map(|(template, (first, last, since, count))| {
+            let from = previous
+                .and_then(|p| p.get(&template))
+                .and_then(|level| LogLevel::from_str(level))
+                .unwrap_or_else(|| first.clone());
+            if from == *last || (from != LogLevel::Error && *last != LogLevel::Error) {
+                return None;
+            }
+            Some(SeverityDrift {
+                direction: if from == LogLevel::Error {
+                    "downgrade"
+                } else {
+                    "upgrade"
+                },
+                from: from.as_str(),
+                to: last.as_str(),
+                since: format_time(since),
+                count,
+                template,
+            })
+        })
+        .collect();
+    drifts.sort_by(|a, b| {
+        a.direction
+            .cmp(b.direction)
+            .then_with(|| b.since.cmp(&a.since))
+            .then_with(|| a.template.cmp(&b.template))
+    });
+    drifts.truncate(top_n);
+    drifts
+}
+
+/// Gabarits d'erreur absents depuis `quiet` avant `end` (par défaut la
+/// dernière entrée), arrêtés le plus récemment d'abord, pour clore les
+/// actions de suivi d'un incident.
+pub fn resolved(
+    entries: &[LogEntry],
+    quiet: Duration,
+    end: Option<NaiveDateTime>,
+    top_n: usize,
+) -> Vec<ResolvedError> {
+    let Some(end) = end.or_else(|| entries.iter().map(|e| e.datetime).max()) else {
+        return Vec::new();
+    };
+    let cutoff = end - chrono::Duration::from_std(quiet).unwrap_or(chrono::Duration::MAX);
+    let mut resolved: Vec<_> = error_templates(entries)
+        .into_iter()
+        .filter(|(_, (_, last, _))| *last < cutoff)
+        .collect();
+    resolved.sort_by(|a, b| b.1.1.cmp(&a.1.1).then_with(|| a.0.cmp(&b.0)));
+    resolved.truncate(top_n);
+    resolved
+        .into_iter()
+        .map(|(template, (first, last, count))| ResolvedError {
+            template,
+            first_seen: format_time(first),
+            last_seen: format_time(last),
+            count,
+        })
+        .collect()
+}
+
+/// Entropie de Shannon des occurrences, rapportée au maximum `log2(total)`
+pub fn normalized_entropy<'a>(occurrences: impl Iterator<Item = &'a usize>, total: usize) -> f64 {
+    if total < 2 {
+        return 0.0;
+    }
+    let entropy: f64 = occurrences
+        .map(|&n| {
+            let p = n as f64 / total as f64;
+            -p * p.log2()
+        })
+        .sum();
+    entropy / (total as f64).log2()
+}
+
+/// Classe les gabarits volumineux mais peu informatifs: un message fréquent
+/// dont le texte varie peu est un bon candidat au nettoyage.
+pub fn scored_templates(entries: &[LogEntry], top_n: usize) -> Vec<TemplateScore> {
+    let mut by_template: HashMap<(&str, String), HashMap<&str, usize>> = HashMap::new();
+    for entry in entries {
+        *by_template
+            .entry((entry.level.as_str(), normalize(&entry.message)))
+            .or_default()
+            .entry(entry.message.as_str())
+            .or_insert(0) += 1;
+    }
+
+    let mut scores: Vec<_> = by_template
+        .into_iter()
+        .map(|((level, template), messages)| {
+            let count = messages.values().sum();
+            let variability = normalized_entropy(messages.values(), count);
+            TemplateScore {
+                level: level.to_string(),
+                template,
+                count,
+                variability,
+                score: count as f64 / entries.len().max(1) as f64 * 100.0 * (1.0 - variability),
+            }
+        })
+        .collect();
+    scores.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.template.cmp(&b.template))
+    });
+    scores.truncate(top_n);
+    scores
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_log_line;
+
+    #[test]
+    fn normalize_replaces_variable_parts() {
+        assert_eq!(
+            normalize("user 42 from 10.0.0.1:8080 fetched \"a.txt\" in 3.5ms"),
+            "user <num> from <ip> fetched <str> in <num>ms"
+        );
+        assert_eq!(
+            normalize("req 550e8400-e29b-41d4-a716-446655440000 done"),
+            "req <uuid> done"
+        );
+    }
+
+    #[test]
+    fn ranks_debug_and_info_templates_by_bytes() {
+        let entries: Vec<_> = [
+            "2024-01-15 10:00:00 [DEBUG] cache hit key=1",
+            "2024-01-15 10:00:01 [DEBUG] cache hit key=22",
+            "2024-01-15 10:00:02 [INFO] started",
+            "2024-01-15 10:00:03 [ERROR] cache hit key=3",
+        ]
+        .iter()
+        .map(|l| parse_log_line(l).unwrap())
+        .collect();
+
+        let noise = noisy_templates(&entries, 5);
+        assert_eq!(noise.len(), 2);
+        assert_eq!(noise[0].template, "cache hit key=<num>");
+        assert_eq!(noise[0].count, 2);
+        assert_eq!(noise[0].bytes, 44 + 45);
+        assert_eq!(noise[1].level, "INFO");
+    }
+
+    #[test]
+    fn first_occurrences_keep_the_latest_new_errors_in_order() {
+        let entries: Vec<_> = [
+            "2024-01-15 10:00:00 [ERROR] db timeout after 30s",
+            "2024-01-15 10:05:00 [ERROR] cache miss storm",
+            "2024-01-15 10:10:00 [ERROR] db timeout after 31s",
+            "2024-01-15 11:00:00 [INFO] deploy v2",
+            "2024-01-15 11:01:00 [ERROR] null pointer in checkout",
+            "2024-01-15 11:02:00 [ERROR] null pointer in checkout",
+        ]
+        .iter()
+        .map(|l| parse_log_line(l).unwrap())
+        .collect();
+
+        let timeline = first_occurrences(&entries, 2);
+        let templates: Vec<_> = timeline.iter().map(|f| f.template.as_str()).collect();
+        assert_eq!(templates, ["cache miss storm", "null pointer in checkout"]);
+        assert_eq!(timeline[1].first_seen, "2024-01-15 11:01:00");
+        assert_eq!(timeline[1].count, 2);
+        assert_eq!(
+            first_occurrences(&entries, 5)[0].template,
+            "db timeout after <num>s"
+        );
+    }
+
+    #[test]
+    fn growing_flags_templates_above_the_threshold() {
+        let entries: Vec<_> = [
+            "2024-01-15 10:00:00 [ERROR] db timeout after 30s",
+            "2024-01-15 10:01:00 [ERROR] db timeout after 31s",
+            "2024-01-15 10:02:00 [ERROR] db timeout after 32s",
+            "2024-01-15 10:03:00 [ERROR] cache miss storm",
+            "2024-01-15 10:04:00 [ERROR] disk full",
+            "2024-01-15 10:05:00 [INFO] ok",
+        ]
+        .iter()
+        .map(|l| parse_log_line(l).unwrap())
+        .collect();
+        let current = error_counts(&entries);
+        assert_eq!(current["db timeout after <num>s"], 3);
+
+        let previous = BTreeMap::from([
+            ("db timeout after <num>s".to_string(), 2),
+            ("cache miss storm".to_string(), 1),
+        ]);
+        let list = growing(&previous, &current, 25.0, 5);
+        let templates: Vec<_> = list.iter().map(|g| g.template.as_str()).collect();
+        assert_eq!(templates, ["disk full", "db timeout after <num>s"]);
+        assert_eq!(list[0].change_pct, None);
+        assert_eq!(list[1].change_pct, Some(50.0));
+        assert!(growing(&previous, &current, 50.0, 5).len() == 1);
+    }
+
+    #[test]
+    fn severity_drift_spots_downgraded_errors() {
+        let entries: Vec<_> = [
+            "2024-01-15 10:00:00 [ERROR] payment gateway refused card 42",
+            "2024-01-15 10:05:00 [ERROR] payment gateway refused card 43",
+            "2024-01-15 10:10:00 [WARNING] payment gateway refused card 44",
+            "2024-01-15 10:15:00 [WARNING] payment gateway refused card 45",
+            "2024-01-15 10:00:00 [INFO] cache warmed",
+            "2024-01-15 10:20:00 [DEBUG] cache warmed",
+            "2024-01-15 10:30:00 [INFO] queue depth 3",
+        ]
+        .iter()
+        .map(|l| parse_log_line(l).unwrap())
+        .collect();
+
+        let drifts = severity_drift(&entries, None, 5);
+        assert_eq!(drifts.len(), 1);
+        assert_eq!(drifts[0].template, "payment gateway refused card <num>");
+        assert_eq!(drifts[0].direction, "downgrade");
+        assert_eq!((drifts[0].from, drifts[0].to), ("ERROR", "WARNING"));
+        assert_eq!(drifts[0].since, "2024-01-15 10:10:00");
+        assert_eq!(drifts[0].count, 2);
+
+        // D'une exécution à l'autre: le niveau enregistré sert de référence
+        let previous = BTreeMap::from([("queue depth <num>".to_string(), "ERROR".to_string())]);
+        let drifts = severity_drift(&entries, Some(&previous), 5);
+        let templates: Vec<_> = drifts.iter().map(|d| d.template.as_str()).collect();
+        assert_eq!(
+            templates,
+            ["queue depth <num>", "payment gateway refused card <num>"]
+        );
+        assert_eq!(template_levels(&entries)["cache warmed"], "DEBUG");
+    }
+
+    #[test]
+    fn resolved_lists_errors_quiet_before_the_window_end() {
+        let entries: Vec<_> = [
+            "2024-01-15 10:00:00 [ERROR] db timeout after 30s",
+            "2024-01-15 10:20:00 [ERROR] db timeout after 31s",
+            "2024-01-15 10:05:00 [ERROR] cache miss storm",
+            "2024-01-15 10:50:00 [ERROR] disk full",
+            "2024-01-15 11:00:00 [INFO] ok",
+        ]
+        .iter()
+        .map(|l| parse_log_line(l).unwrap())
+        .collect();
+
+        let quiet = Duration::from_secs(30 * 60);
+        let list = resolved(&entries, quiet, None, 5);
+        let templates: Vec<_> = list.iter().map(|r| r.template.as_str()).collect();
+        assert_eq!(templates, ["db timeout after <num>s", "cache miss storm"]);
+        assert_eq!(list[0].last_seen, "2024-01-15 10:20:00");
+        assert_eq!(list[0].count, 2);
+
+        let until = parse_log_line("2024-01-15 10:40:00 [INFO] x")
+            .unwrap()
+            .datetime;
+        assert_eq!(resolved(&entries, quiet, Some(until), 5).len(), 1);
+    }
+
+    #[test]
+    fn repetitive_templates_score_higher_than_varied_ones() {
+        let entries: Vec<_> = [
+            "2024-01-15 10:00:00 [INFO] heartbeat 1",
+            "2024-01-15 10:00:01 [INFO] heartbeat 1",
+            "2024-01-15 10:00:02 [INFO] heartbeat 1",
+            "2024-01-15 10:00:03 [INFO] user 1 login",
+            "2024-01-15 10:00:04 [INFO] user 2 login",
+            "2024-01-15 10:00:05 [INFO] user 3 login",
+        ]
+        .iter()
+        .map(|l| parse_log_line(l).unwrap())
+        .collect();
+
+        let scores = scored_templates(&entries, 5);
+        assert_eq!(scores[0].template, "heartbeat <num>");
+        assert_eq!(scores[0].variability, 0.0);
+        assert!((scores[0].score - 50.0).abs() < 1e-9);
+        assert!((scores[1].variability - 1.0).abs() < 1e-9);
+        assert!(scores[1].score.abs() < 1e-9);
+    }
+}
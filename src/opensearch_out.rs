@@ -0,0 +1,160 @@
+use crate::LogEntry;
+use crate::export::entry_json;
+use crate::forward::Forwarder;
+use serde_json::Value;
+use std::io;
+
+/// Nombre d'entrées accumulées avant une requête `_bulk`
+const BATCH: usize = 500;
+const DEFAULT_STREAM: &str = "logs-loglyzer-default";
+
+/// Publie les entrées dans un data stream OpenSearch via l'API `_bulk`.
+///
+/// Un data stream n'accepte que des actions `create` et exige un champ
+/// `@timestamp`. Contrairement à Elasticsearch 8, OpenSearch refuse les
+/// en-têtes `compatible-with`: le corps est envoyé en `application/x-ndjson`.
+pub struct OpenSearchForwarder {
+    bulk_url: String,
+    pending: String,
+    pending_count: usize,
+}
+
+/// Un nom de data stream suit la convention `type-dataset-namespace`, en
+/// minuscules, sans les caractères interdits dans un nom d'index.
+fn valid_stream(name: &str) -> bool {
+    name.splitn(3, '-').filter(|p| !p.is_empty()).count() == 3
+        && !name.starts_with(['-', '_', '+', '.'])
+        && name
+            .chars()
+            .all(|c| !c.is_ascii_uppercase() && !r#"\/*?"<>| ,#:"#.contains(c))
+}
+
+/// `opensearch://[user:pass@]hôte:9200[/stream]` (HTTP) ou
+/// `opensearch+https://...` en URL de l'API `_bulk` du data stream
+fn bulk_url(url: &str) -> Option<String> {
+    let (scheme, rest) = url.split_once("://")?;
+    let scheme = match scheme {
+        "opensearch" => "http",
+        "opensearch+https" => "https",
+        _ => return None,
+    };
+    let (host, stream) = match rest.split_once('/') {
+        Some((host, stream)) if !stream.is_empty() => (host, stream),
+        Some((host, _)) => (host, DEFAULT_STREAM),
+        None => (rest, DEFAULT_STREAM),
+    };
+    (!host.is_empty() && valid_stream(stream)).then(|| format!("{scheme}://{host}/{stream}/_bulk"))
+}
+
+/// Corps de document: l'objet de `--format jsonl`, précédé de `@timestamp`
+/// (l'horodatage d'origine est supposé UTC).
+fn document(entry: &LogEntry) -> Value {
+    let mut doc = serde_json::Map::new();
+    doc.insert(
+        "@timestamp".to_string(),
+        Value::String(entry.datetime.format("%Y-%m-%dT%H:%M:%SZ").to_string()),
+    );
+    doc.extend(entry_json(entry, &[]));
+    Value::Object(doc)
+}
+
+/// Première erreur d'item d'une réponse `_bulk` marquée `errors: true`
+fn first_item_error(response: &Value) -> Option<String> {
+    if response.get("errors") != Some(&Value::Bool(true)) {
+        return None;
+    }
+    response["items"]
+        .as_array()?
+        .iter()
+        .find_map(|item| item.get("create")?.get("error").cloned())
+        .map(|error| error.to_string())
+        .or_else(|| Some("réponse _bulk en erreur".to_string()))
+}
+
+impl OpenSearchForwarder {
+    pub fn connect(url: &str) -> io::Result<Self> {
+        let bulk_url = bulk_url(url).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("URL OpenSearch invalide: {url} (opensearch://hôte:9200/logs-app-default)"),
+            )
+        })?;
+        Ok(OpenSearchForwarder {
+            bulk_url,
+            pending: String::new(),
+            pending_count: 0,
+        })
+    }
+}
+
+impl Forwarder for OpenSearchForwarder {
+    fn send(&mut self, entry: &LogEntry) -> io::Result<()> {
+        self.pending.push_str("{\"create\":{}}\n");
+        self.pending.push_str(&document(entry).to_string());
+        self.pending.push('\n');
+        self.pending_count += 1;
+        if self.pending_count >= BATCH {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let body = ureq::post(&self.bulk_url)
+            .header("Content-Type", "application/x-ndjson")
+            .send(self.pending.as_str())
+            .and_then(|mut r| r.body_mut().read_to_string())
+            .map_err(io::Error::other)?;
+        let response: Value = serde_json::from_str(&body)?;
+        self.pending.clear();
+        self.pending_count = 0;
+        match first_item_error(&response) {
+            Some(error) => Err(io::Error::other(format!("Rejet OpenSearch: {error}"))),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_log_line;
+
+    #[test]
+    fn maps_url_to_data_stream_bulk_endpoint() {
+        assert_eq!(
+            bulk_url("opensearch+https://admin:secret@os:9200/logs-api-prod").as_deref(),
+            Some("https://admin:secret@os:9200/logs-api-prod/_bulk")
+        );
+        assert_eq!(
+            bulk_url("opensearch://os:9200").as_deref(),
+            Some("http://os:9200/logs-loglyzer-default/_bulk")
+        );
+        assert_eq!(bulk_url("opensearch://os:9200/Logs-api-prod"), None);
+        assert_eq!(bulk_url("opensearch://os:9200/api"), None);
+        assert_eq!(bulk_url("http://os:9200/logs-api-prod"), None);
+    }
+
+    #[test]
+    fn builds_timestamped_documents_and_reads_item_errors() {
+        let entry = parse_log_line("2024-01-15 10:30:45 [ERROR] disk full").unwrap();
+        let doc = document(&entry);
+        assert_eq!(doc["@timestamp"], "2024-01-15T10:30:45Z");
+        assert_eq!(doc["level"], "ERROR");
+
+        let ok = serde_json::json!({"errors": false, "items": []});
+        assert_eq!(first_item_error(&ok), None);
+        let rejected = serde_json::json!({
+            "errors": true,
+            "items": [{"create": {"status": 400, "error": {"type": "mapper_parsing_exception"}}}]
+        });
+        assert!(
+            first_item_error(&rejected)
+                .unwrap()
+                .contains("mapper_parsing_exception")
+        );
+    }
+}
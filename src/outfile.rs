@@ -0,0 +1,95 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Conduite à tenir quand le fichier `--output` existe déjà
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Existing {
+    /// Refuser d'écrire (par défaut)
+    Refuse,
+    /// `--force`: remplacer le fichier
+    Overwrite,
+    /// `--append`: ajouter à la fin
+    Append,
+}
+
+/// Vrai si les deux chemins désignent le même fichier, que `output` existe
+/// déjà ou non (liens et chemins relatifs résolus).
+pub fn same_file(input: &Path, output: &Path) -> bool {
+    let Ok(input) = input.canonicalize() else {
+        return false;
+    };
+    let output = output.canonicalize().or_else(|_| {
+        let parent = match output.parent() {
+            Some(p) if !p.as_os_str().is_empty() => p,
+            _ => Path::new("."),
+        };
+        parent
+            .canonicalize()
+            .map(|p| p.join(output.file_name().unwrap_or_default()))
+    });
+    output.is_ok_and(|output| output == input)
+}
+
+/// Fichier voisin où préparer le contenu avant de le renommer sur `path`
+pub fn temp_path(path: &Path) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!(".{name}.tmp-{}", std::process::id()))
+}
+
+/// Écrit `bytes` dans `path`: via un fichier temporaire renommé ensuite (un
+/// lecteur ne voit jamais de rapport à moitié écrit), ou en fin de fichier
+/// avec `Existing::Append`.
+pub fn write(path: &Path, bytes: &[u8], existing: Existing) -> io::Result<()> {
+    match existing {
+        Existing::Append => OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?
+            .write_all(bytes),
+        Existing::Refuse if path.exists() => Err(already_exists(path)),
+        Existing::Refuse | Existing::Overwrite => {
+            let tmp = temp_path(path);
+            fs::write(&tmp, bytes)
+                .and_then(|_| fs::rename(&tmp, path))
+                .inspect_err(|_| {
+                    let _ = fs::remove_file(&tmp);
+                })
+        }
+    }
+}
+
+pub fn already_exists(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        format!(
+            "{} existe déjà: --force pour l'écraser, --append pour y ajouter",
+            path.display()
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refuses_overwrites_or_appends_existing_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.txt");
+        write(&path, b"first\n", Existing::Refuse).unwrap();
+        let err = write(&path, b"second\n", Existing::Refuse).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+
+        write(&path, b"second\n", Existing::Append).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "first\nsecond\n");
+        write(&path, b"third\n", Existing::Overwrite).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "third\n");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        let log = dir.path().join("app.log");
+        fs::write(&log, "").unwrap();
+        assert!(same_file(&log, &dir.path().join(".").join("app.log")));
+        assert!(!same_file(&log, &dir.path().join("new.txt")));
+    }
+}
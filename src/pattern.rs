@@ -0,0 +1,92 @@
+use crate::LogEntry;
+use crate::jsonl::{parse_level, parse_time};
+use chrono::{DateTime, NaiveDateTime};
+use regex::Regex;
+use std::collections::BTreeMap;
+
+/// Groupes nommés obligatoires de `--pattern`
+const REQUIRED: [&str; 3] = ["ts", "level", "msg"];
+
+/// Regex de `--pattern`: doit nommer les groupes `ts`, `level` et `msg`
+pub fn parse_pattern(input: &str) -> Result<Regex, String> {
+    let regex = Regex::new(input).map_err(|e| format!("Motif invalide: {e}"))?;
+    let names: Vec<&str> = regex.capture_names().flatten().collect();
+    let missing: Vec<&str> = REQUIRED
+        .into_iter()
+        .filter(|name| !names.contains(name))
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!(
+            "Le motif doit nommer les groupes ts, level et msg (manquant: {})",
+            missing.join(", ")
+        ));
+    }
+    Ok(regex)
+}
+
+/// Horodatage selon `--timestamp-format` (strftime, fuseau `%z` ramené en
+/// UTC), sinon selon les formats usuels de `jsonl::parse_time`
+fn parse_timestamp(ts: &str, format: Option<&str>) -> Option<NaiveDateTime> {
+    match format {
+        Some(format) => DateTime::parse_from_str(ts, format)
+            .map(|d| d.naive_utc())
+            .or_else(|_| NaiveDateTime::parse_from_str(ts, format))
+            .ok(),
+        None => parse_time(ts),
+    }
+}
+
+/// Une ligne lue avec le motif de l'utilisateur; les autres groupes nommés
+/// qui ont capturé deviennent des champs
+pub fn parse_line(line: &str, pattern: &Regex, timestamp_format: Option<&str>) -> Option<LogEntry> {
+    let caps = pattern.captures(line)?;
+    let datetime = parse_timestamp(caps.name("ts")?.as_str().trim(), timestamp_format)?;
+    let level = parse_level(caps.name("level")?.as_str().trim())?;
+    let fields: BTreeMap<String, String> = pattern
+        .capture_names()
+        .flatten()
+        .filter(|name| !REQUIRED.contains(name))
+        .filter_map(|name| Some((name.to_string(), caps.name(name)?.as_str().to_string())))
+        .collect();
+    Some(LogEntry {
+        timestamp: datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
+        datetime,
+        level,
+        message: caps.name("msg")?.as_str().to_string(),
+        tags: Vec::new(),
+        fields,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LogLevel;
+
+    #[test]
+    fn parses_lines_with_a_custom_pattern() {
+        let pattern =
+            parse_pattern(r"^(?P<ts>\S+ \S+) (?P<level>\w+) +\[(?P<thread>[^\]]+)\] (?P<msg>.*)$")
+                .unwrap();
+        let entry = parse_line(
+            "15/01/2024 10:30:45 WARN  [worker-3] queue almost full",
+            &pattern,
+            Some("%d/%m/%Y %H:%M:%S"),
+        )
+        .unwrap();
+        assert_eq!(entry.timestamp, "2024-01-15 10:30:45");
+        assert_eq!(entry.level, LogLevel::Warning);
+        assert_eq!(entry.message, "queue almost full");
+        assert_eq!(entry.fields["thread"], "worker-3");
+
+        let iso = parse_pattern(r"^(?P<ts>\S+) (?P<level>\w+): (?P<msg>.*)$").unwrap();
+        let entry = parse_line("2024-01-15T10:30:45+02:00 fatal: disk full", &iso, None).unwrap();
+        assert_eq!(entry.timestamp, "2024-01-15 08:30:45");
+        assert_eq!(entry.level, LogLevel::Error);
+
+        assert!(parse_line("15/01/2024 10:30:45 WARN  [w] x", &pattern, None).is_none());
+        let err = parse_pattern(r"(?P<ts>\S+) (?P<msg>.*)").unwrap_err();
+        assert!(err.contains("manquant: level"));
+        assert!(parse_pattern("(").is_err());
+    }
+}
@@ -0,0 +1,133 @@
+use std::borrow::Cow;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+/// Au-delà, Windows refuse les chemins qui ne sont pas au format étendu `\\?\`
+const MAX_PATH: usize = 260;
+
+/// ERROR_SHARING_VIOLATION et ERROR_LOCK_VIOLATION: fichier ouvert sans
+/// partage ou verrouillé par un autre processus (rotation en cours, agent
+/// de collecte...)
+const LOCKED: [i32; 2] = [32, 33];
+
+/// Nouvelles tentatives d'ouverture d'un fichier verrouillé, espacées de `RETRY_DELAY`
+const RETRIES: u32 = 5;
+const RETRY_DELAY: Duration = Duration::from_millis(200);
+
+/// Vrai si l'erreur signale un fichier verrouillé par un autre processus
+pub fn is_locked(err: &io::Error) -> bool {
+    cfg!(windows)
+        && err
+            .raw_os_error()
+            .is_some_and(|code| LOCKED.contains(&code))
+}
+
+/// Forme étendue d'un chemin absolu Windows: `C:\a` devient `\\?\C:\a` et
+/// le partage réseau `\\serveur\partage\a` devient `\\?\UNC\serveur\partage\a`.
+/// `None` pour un chemin déjà étendu, relatif ou d'un autre système.
+fn verbatim(path: &str) -> Option<String> {
+    if path.starts_with(r"\\?\") || path.starts_with(r"\\.\") {
+        return None;
+    }
+    let path = path.replace('/', r"\");
+    if let Some(share) = path.strip_prefix(r"\\") {
+        return Some(format!(r"\\?\UNC\{share}"));
+    }
+    let bytes = path.as_bytes();
+    let drive = bytes.len() >= 3 && bytes[0].is_ascii_alphabetic() && &bytes[1..3] == br":\";
+    drive.then(|| format!(r"\\?\{path}"))
+}
+
+/// Sous Windows, chemin étendu pour les chemins longs, relatifs compris;
+/// inchangé ailleurs ou s'il tient dans `MAX_PATH`
+pub fn long_path(path: &Path) -> Cow<'_, Path> {
+    if !cfg!(windows) || path.as_os_str().len() < MAX_PATH {
+        return Cow::Borrowed(path);
+    }
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    match absolute.to_str().and_then(verbatim) {
+        Some(extended) => Cow::Owned(PathBuf::from(extended)),
+        None => Cow::Owned(absolute),
+    }
+}
+
+/// Ouvre un fichier de log en lecture sans gêner les processus qui y écrivent
+/// (partage lecture, écriture et suppression sous Windows), en réessayant
+/// quelques fois s'il est momentanément verrouillé.
+pub fn open_shared(path: &Path) -> io::Result<File> {
+    let path = long_path(path);
+    let mut options = OpenOptions::new();
+    options.read(true);
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::OpenOptionsExt;
+        // FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE
+        options.share_mode(0x7);
+    }
+    let mut attempt = 0;
+    loop {
+        match options.open(&path) {
+            Err(err) if is_locked(&err) && attempt < RETRIES => {
+                attempt += 1;
+                thread::sleep(RETRY_DELAY);
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Métadonnées d'un fichier, chemins longs compris
+pub fn metadata(path: &Path) -> io::Result<std::fs::Metadata> {
+    std::fs::metadata(long_path(path))
+}
+
+/// Nom de la machine, pour situer un rapport
+#[cfg(unix)]
+pub fn hostname() -> Option<String> {
+    let mut buf = [0u8; 256];
+    // SAFETY: le tampon et sa longueur sont valides; le nom est tronqué sans
+    // erreur s'il est trop long, d'où le zéro final forcé
+    if unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len() - 1) } != 0 {
+        return None;
+    }
+    let end = buf.iter().position(|&b| b == 0)?;
+    Some(String::from_utf8_lossy(&buf[..end]).into_owned()).filter(|h| !h.is_empty())
+}
+
+#[cfg(not(unix))]
+pub fn hostname() -> Option<String> {
+    std::env::var("COMPUTERNAME").ok().filter(|h| !h.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extends_windows_paths() {
+        assert_eq!(
+            verbatim(r"C:\logs\app.log").as_deref(),
+            Some(r"\\?\C:\logs\app.log")
+        );
+        assert_eq!(
+            verbatim("D:/logs/app.log").as_deref(),
+            Some(r"\\?\D:\logs\app.log")
+        );
+        assert_eq!(
+            verbatim(r"\\fileserver\logs\iis\u_ex240115.log").as_deref(),
+            Some(r"\\?\UNC\fileserver\logs\iis\u_ex240115.log")
+        );
+        assert_eq!(verbatim(r"\\?\C:\logs\app.log"), None);
+        assert_eq!(verbatim("logs/app.log"), None);
+        assert_eq!(verbatim("/var/log/app.log"), None);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        std::fs::write(&path, "x").unwrap();
+        assert!(open_shared(&path).is_ok());
+        assert!(!is_locked(&io::Error::from_raw_os_error(2)));
+    }
+}
@@ -0,0 +1,137 @@
+use crate::rules::{Config, LevelRuleDef, RuleDef};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Définitions partagées déposées dans `presets.d`: mêmes sections que le
+/// fichier `--config`, hors réglages propres à un utilisateur (couleurs,
+/// taxonomie intégrée).
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Preset {
+    #[serde(default)]
+    pub category: Vec<RuleDef>,
+    #[serde(default)]
+    pub tag: Vec<RuleDef>,
+    #[serde(default)]
+    pub reclassify: Vec<LevelRuleDef>,
+    #[serde(default)]
+    pub exclude_windows: Vec<String>,
+    #[serde(default)]
+    pub queries: BTreeMap<String, Vec<String>>,
+}
+
+/// `$LOGLYZER_PRESETS_DIR` (vide pour désactiver), sinon
+/// `$XDG_CONFIG_HOME/loglyzer/presets.d` ou `~/.config/loglyzer/presets.d`
+pub fn dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("LOGLYZER_PRESETS_DIR") {
+        return (!dir.is_empty()).then(|| PathBuf::from(dir));
+    }
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| Path::new(&h).join(".config")))?;
+    Some(config_home.join("loglyzer").join("presets.d"))
+}
+
+/// Charge les presets du répertoire par ordre de nom: `*.toml` comme un
+/// fichier de configuration, `NOM.regex` comme la catégorie NOM (un motif
+/// par ligne, `#` pour commenter). Un répertoire absent ne fournit rien.
+pub fn load(dir: &Path) -> Result<Vec<Preset>, String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Impossible de lire {}: {e}", dir.display())),
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_file())
+        .collect();
+    paths.sort();
+
+    let mut presets = Vec::new();
+    for path in paths {
+        let read = || {
+            fs::read_to_string(&path)
+                .map_err(|e| format!("Impossible de lire le preset {}: {e}", path.display()))
+        };
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => presets.push(
+                toml::from_str(&read()?)
+                    .map_err(|e| format!("Preset invalide {}: {e}", path.display()))?,
+            ),
+            Some("regex") => {
+                let name = path.file_stem().unwrap_or_default().to_string_lossy();
+                let category = read()?
+                    .lines()
+                    .map(str::trim)
+                    .filter(|l| !l.is_empty() && !l.starts_with('#'))
+                    .map(|pattern| RuleDef {
+                        name: name.to_string(),
+                        pattern: pattern.to_string(),
+                    })
+                    .collect();
+                presets.push(Preset {
+                    category,
+                    ..Preset::default()
+                });
+            }
+            _ => {}
+        }
+    }
+    Ok(presets)
+}
+
+/// Ajoute les presets après les règles de `config`, qui restent prioritaires
+/// (première catégorie qui correspond, requête de même nom).
+pub fn apply(config: &mut Config, presets: Vec<Preset>) {
+    for preset in presets {
+        config.category.extend(preset.category);
+        config.tag.extend(preset.tag);
+        config.reclassify.extend(preset.reclassify);
+        config.exclude_windows.extend(preset.exclude_windows);
+        for (name, args) in preset.queries {
+            config.queries.entry(name).or_insert(args);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Categorizer;
+
+    #[test]
+    fn presets_extend_config_without_overriding_it() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("10-team.toml"),
+            "[[category]]\nname = \"payment\"\npattern = \"(?i)stripe\"\n\n\
+             [queries]\ntimeouts = [\"--search\", \"timeout\"]\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("cache.regex"),
+            "# cache misses\nredis\n\nmemcached\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("README.md"), "ignored").unwrap();
+
+        let mut config: Config = toml::from_str(
+            "[[category]]\nname = \"billing\"\npattern = \"invoice\"\n\n\
+             [queries]\ntimeouts = [\"--errors-only\"]\n",
+        )
+        .unwrap();
+        apply(&mut config, load(dir.path()).unwrap());
+        let cat = Categorizer::from_config(&config).unwrap();
+        assert_eq!(cat.categorize("stripe invoice failed"), "billing");
+        assert_eq!(cat.categorize("Stripe timeout"), "payment");
+        assert_eq!(cat.categorize("memcached down"), "cache");
+        assert_eq!(config.queries["timeouts"], vec!["--errors-only"]);
+
+        assert!(load(&dir.path().join("missing")).unwrap().is_empty());
+        fs::write(dir.path().join("30-bad.toml"), "colors = {}\n").unwrap();
+        assert!(load(dir.path()).is_err());
+    }
+}
@@ -0,0 +1,186 @@
+use crate::parse_log_line;
+use chrono::NaiveDateTime;
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Default, PartialEq)]
+pub struct PruneSummary {
+    pub archived: usize,
+    pub kept: usize,
+}
+
+/// Déplace les entrées antérieures à `before` vers `archive` (compressé en
+/// gzip si le nom se termine par `.gz`) et réécrit `input` avec le reste.
+///
+/// Les lignes sans horodatage (traces, continuations) suivent l'entrée qui
+/// les précède. Une archive existante est complétée, jamais tronquée (en
+/// gzip, un nouveau membre est ajouté). L'archive est complètement écrite
+/// avant que le fichier d'origine ne soit remplacé, via un fichier
+/// temporaire renommé qui reprend ses permissions.
+pub fn prune(input: &Path, before: NaiveDateTime, archive: &Path) -> io::Result<PruneSummary> {
+    let reader = BufReader::new(File::open(input)?);
+    let permissions = fs::metadata(input)?.permissions();
+    let archive_file = BufWriter::new(OpenOptions::new().create(true).append(true).open(archive)?);
+
+    let mut tmp_name = input.as_os_str().to_owned();
+    tmp_name.push(".prune-tmp");
+    let tmp = TempFile {
+        path: PathBuf::from(tmp_name),
+        persisted: false,
+    };
+    let mut kept_out = BufWriter::new(File::create(&tmp.path)?);
+
+    let summary = if archive.extension().is_some_and(|e| e == "gz") {
+        let mut encoder = GzEncoder::new(archive_file, Compression::default());
+        let summary = split(reader, before, &mut encoder, &mut kept_out)?;
+        encoder.finish()?.flush()?;
+        summary
+    } else {
+        let mut archive_out = archive_file;
+        let summary = split(reader, before, &mut archive_out, &mut kept_out)?;
+        archive_out.flush()?;
+        summary
+    };
+
+    kept_out.flush()?;
+    drop(kept_out);
+    fs::set_permissions(&tmp.path, permissions)?;
+    tmp.persist(input)?;
+
+    Ok(summary)
+}
+
+/// Fichier temporaire supprimé s'il n'a pas été renommé sur sa cible
+struct TempFile {
+    path: PathBuf,
+    persisted: bool,
+}
+
+impl TempFile {
+    fn persist(mut self, target: &Path) -> io::Result<()> {
+        fs::rename(&self.path, target)?;
+        self.persisted = true;
+        Ok(())
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        if !self.persisted {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+fn split(
+    reader: impl BufRead,
+    before: NaiveDateTime,
+    archive_out: &mut impl Write,
+    kept_out: &mut impl Write,
+) -> io::Result<PruneSummary> {
+    let mut summary = PruneSummary::default();
+    let mut archiving = false;
+    for line in reader.lines() {
+        let line = line?;
+        if let Some(entry) = parse_log_line(line.trim_end_matches('\r')) {
+            archiving = entry.datetime < before;
+        }
+        if archiving {
+            writeln!(archive_out, "{line}")?;
+            summary.archived += 1;
+        } else {
+            writeln!(kept_out, "{line}")?;
+            summary.kept += 1;
+        }
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    #[test]
+    fn prune_splits_by_timestamp_and_keeps_continuations() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("app.log");
+        let archive = dir.path().join("old.log.gz");
+        fs::write(
+            &input,
+            "2024-01-14 23:59:59 [ERROR] old failure\n\
+             \tat Foo.bar(Foo.java:1)\n\
+             2024-01-15 00:00:00 [INFO] new day\n",
+        )
+        .unwrap();
+
+        let before = crate::parse_datetime("2024-01-15 00:00:00").unwrap();
+        let summary = prune(&input, before, &archive).unwrap();
+        assert_eq!(
+            summary,
+            PruneSummary {
+                archived: 2,
+                kept: 1
+            }
+        );
+
+        assert_eq!(
+            fs::read_to_string(&input).unwrap(),
+            "2024-01-15 00:00:00 [INFO] new day\n"
+        );
+        let mut archived = String::new();
+        GzDecoder::new(File::open(&archive).unwrap())
+            .read_to_string(&mut archived)
+            .unwrap();
+        assert!(archived.starts_with("2024-01-14 23:59:59 [ERROR] old failure\n"));
+        assert!(archived.contains("Foo.java"));
+    }
+
+    #[test]
+    fn prune_appends_to_the_archive_and_keeps_permissions() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("app.log");
+        let archive = dir.path().join("old.log");
+        fs::write(&archive, "2024-01-13 08:00:00 [INFO] older\n").unwrap();
+        fs::write(
+            &input,
+            "2024-01-14 10:00:00 [INFO] old\n2024-01-15 10:00:00 [INFO] new\n",
+        )
+        .unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&input, fs::Permissions::from_mode(0o640)).unwrap();
+        }
+
+        let before = crate::parse_datetime("2024-01-15 00:00:00").unwrap();
+        prune(&input, before, &archive).unwrap();
+        assert_eq!(
+            fs::read_to_string(&archive).unwrap(),
+            "2024-01-13 08:00:00 [INFO] older\n2024-01-14 10:00:00 [INFO] old\n"
+        );
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&input).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o640);
+        }
+    }
+
+    #[test]
+    fn prune_removes_its_temporary_file_on_error() {
+        let dir = tempfile::tempdir().unwrap();
+        // Un répertoire s'ouvre mais sa lecture échoue en cours de route
+        let input = dir.path().join("logs");
+        fs::create_dir(&input).unwrap();
+        let archive = dir.path().join("old.log");
+
+        let before = crate::parse_datetime("2024-01-15 00:00:00").unwrap();
+        assert!(prune(&input, before, &archive).is_err());
+        assert!(!dir.path().join("logs.prune-tmp").exists());
+    }
+}
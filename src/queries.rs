@@ -0,0 +1,133 @@
+use crate::Cli;
+use crate::outfile::{self, Existing};
+use crate::presets;
+use crate::rules::Config;
+use clap::Parser;
+use std::ffi::OsString;
+use std::fs;
+use std::path::Path;
+use toml_edit::{Array, DocumentMut, Item, Table};
+
+fn valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Vérifie qu'un jeu d'arguments forme une analyse complète une fois le
+/// fichier fourni, sans fichier ni `--config` (donnés par `run`).
+fn check_args(args: &[String]) -> Result<(), String> {
+    let argv = ["loglyzer", "requete.log"].into_iter().map(String::from);
+    let cli = Cli::try_parse_from(argv.chain(args.iter().cloned()))
+        .map_err(|e| format!("Arguments de requête invalides: {}", e.render()))?;
+    if cli.config.is_some() {
+        return Err("--config ne fait pas partie d'une requête: run le fournit".to_string());
+    }
+    if cli.input.len() > 1 {
+        return Err(
+            "Le fichier de log ne fait pas partie d'une requête: run le fournit".to_string(),
+        );
+    }
+    Ok(())
+}
+
+/// Enregistre `args` sous `[queries]` dans la configuration, créée au
+/// besoin; le reste du fichier (commentaires compris) est conservé.
+pub fn save(config: &Path, name: &str, args: &[String]) -> Result<(), String> {
+    if !valid_name(name) {
+        return Err(format!(
+            "Nom de requête invalide: {name} (lettres, chiffres, - et _)"
+        ));
+    }
+    check_args(args)?;
+    let raw = match fs::read_to_string(config) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => {
+            return Err(format!(
+                "Impossible de lire la configuration {}: {e}",
+                config.display()
+            ));
+        }
+    };
+    let mut doc: DocumentMut = raw
+        .parse()
+        .map_err(|e| format!("Configuration invalide {}: {e}", config.display()))?;
+    let queries = doc
+        .entry("queries")
+        .or_insert_with(|| Item::Table(Table::new()))
+        .as_table_mut()
+        .ok_or_else(|| format!("[queries] n'est pas une table dans {}", config.display()))?;
+    queries[name] = toml_edit::value(args.iter().collect::<Array>());
+    outfile::write(config, doc.to_string().as_bytes(), Existing::Overwrite)
+        .map_err(|e| format!("Impossible d'écrire {}: {e}", config.display()))
+}
+
+/// Ligne de commande complète de `run NAME FILE [ARGS...]` (requête de la
+/// configuration ou d'un preset partagé): les arguments
+/// enregistrés, puis ceux ajoutés à l'appel (prioritaires pour les options
+/// à valeur unique, car lus en dernier).
+pub fn expand(
+    config_path: &Path,
+    name: &str,
+    file: &Path,
+    extra: &[String],
+) -> Result<Vec<OsString>, String> {
+    let mut config = Config::load(config_path)?;
+    if let Some(dir) = presets::dir() {
+        presets::apply(&mut config, presets::load(&dir)?);
+    }
+    let Some(args) = config.queries.get(name) else {
+        let known: Vec<&str> = config.queries.keys().map(String::as_str).collect();
+        return Err(format!(
+            "Requête inconnue: {name} (enregistrées: {})",
+            if known.is_empty() {
+                "aucune".to_string()
+            } else {
+                known.join(", ")
+            }
+        ));
+    };
+    let mut argv: Vec<OsString> = vec!["loglyzer".into(), file.into()];
+    argv.extend(["--config".into(), config_path.into()]);
+    argv.extend(args.iter().chain(extra).map(OsString::from));
+    Ok(argv)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saves_and_expands_named_queries() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("loglyzer.toml");
+        fs::write(&config, "# règles d'équipe\nexclude_windows = []\n").unwrap();
+
+        let args: Vec<String> = ["--errors-only", "--search", "timeout"]
+            .map(String::from)
+            .to_vec();
+        save(&config, "timeouts", &args).unwrap();
+        save(
+            &config,
+            "json",
+            &["--format".to_string(), "json".to_string()],
+        )
+        .unwrap();
+        let raw = fs::read_to_string(&config).unwrap();
+        assert!(raw.starts_with("# règles d'équipe\n"));
+
+        let argv = expand(&config, "timeouts", Path::new("app.log"), &[]).unwrap();
+        let cli = Cli::try_parse_from(argv).unwrap();
+        assert!(cli.errors_only);
+        assert_eq!(cli.search.as_deref(), Some("timeout"));
+        assert_eq!(cli.input(), Path::new("app.log"));
+
+        let err = expand(&config, "slow", Path::new("app.log"), &[]).unwrap_err();
+        assert!(err.contains("json, timeouts"));
+        assert!(save(&config, "bad name", &args).is_err());
+        assert!(save(&config, "bad", &["--top".to_string(), "0".to_string()]).is_err());
+        assert!(save(&config, "bad", &["other.log".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,170 @@
+use crate::LogLevel;
+use crate::follow::{CHANNEL_CAPACITY, LineSource};
+use chrono::{DateTime, NaiveDateTime};
+use redis::Commands;
+use redis::streams::{StreamReadOptions, StreamReadReply};
+use std::collections::HashMap;
+use std::io;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+/// Attente maximale d'un XREAD bloquant, en millisecondes
+const BLOCK_MS: usize = 5000;
+const BATCH: usize = 500;
+
+const TIMESTAMP_KEYS: [&str; 3] = ["timestamp", "ts", "time"];
+const LEVEL_KEYS: [&str; 3] = ["level", "severity", "lvl"];
+const MESSAGE_KEYS: [&str; 3] = ["message", "msg", "log"];
+
+fn get<'a>(fields: &'a HashMap<String, String>, keys: &[&str]) -> Option<&'a str> {
+    keys.iter().find_map(|k| fields.get(*k)).map(String::as_str)
+}
+
+/// Horodatage natif, RFC 3339 ou secondes depuis l'époque
+fn parse_timestamp(value: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .or_else(|| {
+            DateTime::parse_from_rfc3339(value)
+                .ok()
+                .map(|d| d.naive_utc())
+        })
+        .or_else(|| DateTime::from_timestamp(value.parse().ok()?, 0).map(|d| d.naive_utc()))
+}
+
+/// Convertit une entrée de stream en ligne native; sans champ d'horodatage,
+/// la partie milliseconde de l'identifiant (`1705314645000-0`) fait foi.
+pub fn to_line(id: &str, fields: &HashMap<String, String>) -> Option<String> {
+    let message = get(fields, &MESSAGE_KEYS)?;
+    let at = match get(fields, &TIMESTAMP_KEYS) {
+        Some(ts) => parse_timestamp(ts)?,
+        None => {
+            let ms: i64 = id.split('-').next()?.parse().ok()?;
+            DateTime::from_timestamp_millis(ms)?.naive_utc()
+        }
+    };
+    let level = get(fields, &LEVEL_KEYS)
+        .and_then(LogLevel::from_str)
+        .unwrap_or(LogLevel::Info);
+    Some(format!(
+        "{} [{}] {message}",
+        at.format("%Y-%m-%d %H:%M:%S"),
+        level.as_str()
+    ))
+}
+
+/// Consomme un stream Redis à partir des nouvelles entrées (`$`), comme
+/// `--follow` sur un fichier, ou de l'identifiant repris par `--state`.
+/// La lecture démarre au premier appel de `poll_lines`.
+pub struct StreamSource {
+    con: Option<redis::Connection>,
+    stream: String,
+    last_id: String,
+    lines: Option<Receiver<(String, String)>>,
+}
+
+impl StreamSource {
+    pub fn connect(url: &str, stream: &str) -> redis::RedisResult<Self> {
+        Ok(StreamSource {
+            con: Some(redis::Client::open(url)?.get_connection()?),
+            stream: stream.to_string(),
+            last_id: "$".to_string(),
+            lines: None,
+        })
+    }
+
+    fn start(
+        mut con: redis::Connection,
+        stream: String,
+        from: String,
+    ) -> Receiver<(String, String)> {
+        let (tx, lines) = mpsc::sync_channel(CHANNEL_CAPACITY);
+        thread::spawn(move || {
+            let options = StreamReadOptions::default().block(BLOCK_MS).count(BATCH);
+            let mut last_id = from;
+            loop {
+                let reply: StreamReadReply =
+                    match con.xread_options(&[&stream], &[&last_id], &options) {
+                        Ok(reply) => reply,
+                        Err(err) => {
+                            eprintln!("Lecture du stream {stream} interrompue: {err}");
+                            return;
+                        }
+                    };
+                for entry in reply.keys.into_iter().flat_map(|k| k.ids) {
+                    let fields: HashMap<String, String> = entry
+                        .map
+                        .iter()
+                        .filter_map(|(k, v)| {
+                            redis::from_redis_value_ref::<String>(v)
+                                .ok()
+                                .map(|v| (k.clone(), v))
+                        })
+                        .collect();
+                    if let Some(line) = to_line(&entry.id, &fields)
+                        && tx.send((line, entry.id.clone())).is_err()
+                    {
+                        return;
+                    }
+                    last_id = entry.id;
+                }
+            }
+        });
+        lines
+    }
+}
+
+impl LineSource for StreamSource {
+    fn poll_lines(&mut self) -> io::Result<Vec<(String, Option<String>)>> {
+        if let Some(con) = self.con.take() {
+            self.lines = Some(Self::start(con, self.stream.clone(), self.last_id.clone()));
+        }
+        let Some(lines) = &self.lines else {
+            return Ok(Vec::new());
+        };
+        let origin = format!("stream={}", self.stream);
+        Ok(lines
+            .try_iter()
+            .map(|(line, id)| {
+                self.last_id = id;
+                (line, Some(origin.clone()))
+            })
+            .collect())
+    }
+
+    /// Identifiant de la dernière entrée retournée
+    fn checkpoint(&self) -> Option<String> {
+        (self.last_id != "$").then(|| self.last_id.clone())
+    }
+
+    fn resume(&mut self, cursor: &str) -> io::Result<()> {
+        self.last_id = cursor.to_string();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_stream_fields_to_a_log_line() {
+        let fields: HashMap<String, String> = [("level", "warn"), ("msg", "slow query")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        assert_eq!(
+            to_line("1705314645000-0", &fields).as_deref(),
+            Some("2024-01-15 10:30:45 [WARNING] slow query")
+        );
+
+        let mut fields = fields;
+        fields.insert("ts".to_string(), "2024-01-15T11:00:00+01:00".to_string());
+        assert_eq!(
+            to_line("0-1", &fields).as_deref(),
+            Some("2024-01-15 10:00:00 [WARNING] slow query")
+        );
+        fields.remove("msg");
+        assert!(to_line("0-1", &fields).is_none());
+    }
+}
@@ -0,0 +1,133 @@
+use std::io::{self, Read};
+use std::path::Path;
+
+/// Protocole d'une entrée distante, et fonctionnalité cargo qui le compile
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Scheme {
+    Http,
+    S3,
+}
+
+impl Scheme {
+    fn of(path: &Path) -> Option<Scheme> {
+        let path = path.to_str()?;
+        if path.starts_with("http://") || path.starts_with("https://") {
+            Some(Scheme::Http)
+        } else if path.starts_with("s3://") {
+            Some(Scheme::S3)
+        } else {
+            None
+        }
+    }
+
+    fn unsupported(self) -> io::Error {
+        let (label, feature) = match self {
+            Scheme::Http => ("HTTP", "http"),
+            Scheme::S3 => ("S3", "s3"),
+        };
+        io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("lecture {label} non compilée (cargo build --features {feature})"),
+        )
+    }
+}
+
+/// Vrai si l'entrée est une URL `http://`, `https://` ou `s3://` plutôt qu'un chemin
+pub fn is_url(path: &Path) -> bool {
+    Scheme::of(path).is_some()
+}
+
+#[cfg(feature = "http")]
+fn to_io(err: ureq::Error) -> io::Error {
+    match err {
+        ureq::Error::StatusCode(404 | 410) => io::ErrorKind::NotFound.into(),
+        ureq::Error::StatusCode(code) => io::Error::other(format!("réponse HTTP {code}")),
+        ureq::Error::Io(err) => err,
+        err => io::Error::other(err),
+    }
+}
+
+#[cfg(feature = "http")]
+fn call(method: &str, url: &Path) -> Result<ureq::http::Response<ureq::Body>, ureq::Error> {
+    let request = ureq::http::Request::builder()
+        .method(method)
+        .uri(url.to_string_lossy().as_ref())
+        .body(())?;
+    ureq::run(request)
+}
+
+/// Taille annoncée par `Content-Length` en réponse à un HEAD, `None` si le
+/// serveur ne la donne pas ou refuse la méthode: la lecture se fera alors
+/// sans total. Une URL introuvable ou injoignable est signalée dès ici.
+#[cfg(feature = "http")]
+fn http_content_length(url: &Path) -> io::Result<Option<u64>> {
+    match call("HEAD", url) {
+        Ok(response) => Ok(response
+            .headers()
+            .get("content-length")
+            .and_then(|v| v.to_str().ok()?.parse().ok())),
+        Err(ureq::Error::StatusCode(code)) if !matches!(code, 404 | 410) => Ok(None),
+        Err(err) => Err(to_io(err)),
+    }
+}
+
+/// Taille de l'entrée distante, `None` si elle n'est pas connue d'avance
+pub fn content_length(url: &Path) -> io::Result<Option<u64>> {
+    match Scheme::of(url) {
+        #[cfg(feature = "http")]
+        Some(Scheme::Http) => http_content_length(url),
+        #[cfg(feature = "s3")]
+        Some(Scheme::S3) => crate::s3::content_length(&url.to_string_lossy()),
+        #[allow(unreachable_patterns)]
+        scheme => Err(scheme.unwrap_or(Scheme::Http).unsupported()),
+    }
+}
+
+/// Corps de la réponse à un GET (ou à un GetObject pour S3), lu au fil de
+/// l'analyse sans être téléchargé d'abord
+pub fn open(url: &Path) -> io::Result<Box<dyn Read>> {
+    match Scheme::of(url) {
+        #[cfg(feature = "http")]
+        Some(Scheme::Http) => {
+            let response = call("GET", url).map_err(to_io)?;
+            Ok(Box::new(response.into_body().into_reader()))
+        }
+        #[cfg(feature = "s3")]
+        Some(Scheme::S3) => crate::s3::open(&url.to_string_lossy()),
+        #[allow(unreachable_patterns)]
+        scheme => Err(scheme.unwrap_or(Scheme::Http).unsupported()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_remote_urls() {
+        assert!(is_url(Path::new("https://bucket.example.com/app.log")));
+        assert!(is_url(Path::new("http://artifacts:8080/build/42/app.log")));
+        assert_eq!(
+            Scheme::of(Path::new("s3://logs/app.log.gz")),
+            Some(Scheme::S3)
+        );
+        assert!(!is_url(Path::new("logs/https.log")));
+        assert!(!is_url(Path::new("-")));
+        #[cfg(not(feature = "http"))]
+        assert!(
+            open(Path::new("https://bucket.example.com/app.log"))
+                .err()
+                .unwrap()
+                .to_string()
+                .contains("--features http")
+        );
+        #[cfg(not(feature = "s3"))]
+        assert!(
+            open(Path::new("s3://logs/app.log"))
+                .err()
+                .unwrap()
+                .to_string()
+                .contains("--features s3")
+        );
+    }
+}
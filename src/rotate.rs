@@ -0,0 +1,135 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Politique de rotation de `--output-rotate`: par taille ou par durée
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RotatePolicy {
+    Size(u64),
+    Every(Duration),
+}
+
+pub fn parse_rotate(input: &str) -> Result<RotatePolicy, String> {
+    if let Ok(size) = crate::parse_size(input) {
+        return Ok(RotatePolicy::Size(size));
+    }
+    crate::parse_duration(input)
+        .map(RotatePolicy::Every)
+        .map_err(|_| format!("Rotation invalide '{input}' (ex: 100MB, 1h)"))
+}
+
+/// Fichier de sortie en ajout, renommé en `FILE.1`, `FILE.2`, ... quand la
+/// politique de rotation est atteinte; seules `keep` générations sont gardées.
+pub struct RotatingWriter {
+    path: PathBuf,
+    policy: Option<RotatePolicy>,
+    keep: usize,
+    file: File,
+    written: u64,
+    opened_at: Instant,
+}
+
+impl RotatingWriter {
+    pub fn open(path: &Path, policy: Option<RotatePolicy>, keep: usize) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let written = file.metadata()?.len();
+        Ok(RotatingWriter {
+            path: path.to_path_buf(),
+            policy,
+            keep,
+            file,
+            written,
+            opened_at: Instant::now(),
+        })
+    }
+
+    fn generation(&self, n: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{n}"));
+        PathBuf::from(name)
+    }
+
+    fn should_rotate(&self, incoming: u64) -> bool {
+        match self.policy {
+            Some(RotatePolicy::Size(max)) => self.written > 0 && self.written + incoming > max,
+            Some(RotatePolicy::Every(period)) => self.opened_at.elapsed() >= period,
+            None => false,
+        }
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        if self.keep == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            let oldest = self.generation(self.keep);
+            if oldest.exists() {
+                fs::remove_file(&oldest)?;
+            }
+            for n in (1..self.keep).rev() {
+                let from = self.generation(n);
+                if from.exists() {
+                    fs::rename(&from, self.generation(n + 1))?;
+                }
+            }
+            fs::rename(&self.path, self.generation(1))?;
+        }
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.written = 0;
+        self.opened_at = Instant::now();
+        Ok(())
+    }
+
+    pub fn write_line(&mut self, line: &str) -> io::Result<()> {
+        let incoming = line.len() as u64 + 1;
+        if self.should_rotate(incoming) {
+            self.rotate()?;
+        }
+        writeln!(self.file, "{line}")?;
+        self.written += incoming;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_rotate_accepts_size_and_duration() {
+        assert_eq!(
+            parse_rotate("100MB"),
+            Ok(RotatePolicy::Size(100 * 1024 * 1024))
+        );
+        assert_eq!(
+            parse_rotate("1h"),
+            Ok(RotatePolicy::Every(Duration::from_secs(3600)))
+        );
+        assert!(parse_rotate("often").is_err());
+    }
+
+    #[test]
+    fn rotates_by_size_and_keeps_generations() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.log");
+        let mut writer = RotatingWriter::open(&path, Some(RotatePolicy::Size(10)), 2).unwrap();
+        for line in ["first", "second", "third", "fourth"] {
+            writer.write_line(line).unwrap();
+        }
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "fourth\n");
+        assert_eq!(
+            fs::read_to_string(dir.path().join("out.log.1")).unwrap(),
+            "third\n"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join("out.log.2")).unwrap(),
+            "second\n"
+        );
+        assert!(!dir.path().join("out.log.3").exists());
+    }
+}
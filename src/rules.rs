@@ -0,0 +1,309 @@
+use crate::LogLevel;
+use crate::theme::ColorConfig;
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Catégorie attribuée aux erreurs qui ne correspondent à aucune règle
+pub const UNCATEGORIZED: &str = "other";
+
+const BUILTIN_CATEGORIES: &[(&str, &str)] = &[
+    (
+        "network",
+        r"(?i)timeout|timed out|connection (refused|reset|closed)|unreachable|\bdns\b|socket",
+    ),
+    (
+        "database",
+        r"(?i)database|\bsql\b|query|deadlock|transaction|\bdb\b",
+    ),
+    (
+        "auth",
+        r"(?i)\bauth|unauthori[sz]ed|forbidden|permission denied|credential|\btoken\b|login",
+    ),
+    (
+        "client-error",
+        r"(?i)\b4\d\d\b|bad request|not found|invalid|malformed",
+    ),
+    (
+        "server-error",
+        r"(?i)\b5\d\d\b|internal server error|panic|exception|out of memory",
+    ),
+];
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Ajoute la taxonomie intégrée (network, database, auth, ...) après les règles du fichier
+    #[serde(default = "default_true")]
+    pub builtin_categories: bool,
+    #[serde(default)]
+    pub category: Vec<RuleDef>,
+    #[serde(default)]
+    pub tag: Vec<RuleDef>,
+    #[serde(default)]
+    pub reclassify: Vec<LevelRuleDef>,
+    /// Plages de maintenance exclues de l'analyse, même format que `--exclude-window`
+    #[serde(default)]
+    pub exclude_windows: Vec<String>,
+    #[serde(default)]
+    pub colors: ColorConfig,
+    /// Requêtes nommées (`save-query` / `run`): arguments de la ligne de
+    /// commande, sans le fichier analysé
+    #[serde(default)]
+    pub queries: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleDef {
+    pub name: String,
+    pub pattern: String,
+}
+
+/// Change le niveau des entrées dont le message correspond à `pattern`,
+/// éventuellement restreint à celles de niveau `from`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LevelRuleDef {
+    pub pattern: String,
+    pub from: Option<String>,
+    pub to: String,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            builtin_categories: true,
+            category: Vec::new(),
+            tag: Vec::new(),
+            reclassify: Vec::new(),
+            exclude_windows: Vec::new(),
+            colors: ColorConfig::default(),
+            queries: BTreeMap::new(),
+        }
+    }
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let raw = fs::read_to_string(path).map_err(|e| {
+            format!(
+                "Impossible de lire la configuration {}: {e}",
+                path.display()
+            )
+        })?;
+        toml::from_str(&raw).map_err(|e| format!("Configuration invalide {}: {e}", path.display()))
+    }
+}
+
+/// Règles de catégorisation évaluées dans l'ordre: la première qui
+/// correspond au message l'emporte.
+#[derive(Debug)]
+pub struct Categorizer {
+    rules: Vec<(String, Regex)>,
+}
+
+impl Categorizer {
+    pub fn from_config(config: &Config) -> Result<Self, String> {
+        let mut rules = Vec::new();
+        for rule in &config.category {
+            let re = Regex::new(&rule.pattern)
+                .map_err(|e| format!("Règle de catégorie '{}' invalide: {e}", rule.name))?;
+            rules.push((rule.name.clone(), re));
+        }
+        if config.builtin_categories {
+            for (name, pattern) in BUILTIN_CATEGORIES {
+                rules.push((name.to_string(), Regex::new(pattern).unwrap()));
+            }
+        }
+        Ok(Categorizer { rules })
+    }
+
+    pub fn categorize(&self, message: &str) -> &str {
+        self.rules
+            .iter()
+            .find(|(_, re)| re.is_match(message))
+            .map(|(name, _)| name.as_str())
+            .unwrap_or(UNCATEGORIZED)
+    }
+}
+
+impl Default for Categorizer {
+    fn default() -> Self {
+        Categorizer::from_config(&Config::default()).unwrap()
+    }
+}
+
+/// Règles de tags: contrairement aux catégories, une entrée reçoit le tag
+/// de chaque règle qui correspond.
+#[derive(Debug, Default)]
+pub struct Tagger {
+    rules: Vec<(String, Regex)>,
+}
+
+impl Tagger {
+    pub fn from_config(config: &Config) -> Result<Self, String> {
+        let rules = config
+            .tag
+            .iter()
+            .map(|rule| {
+                Regex::new(&rule.pattern)
+                    .map(|re| (rule.name.clone(), re))
+                    .map_err(|e| format!("Règle de tag '{}' invalide: {e}", rule.name))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Tagger { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn tags(&self, message: &str) -> Vec<String> {
+        self.rules
+            .iter()
+            .filter(|(_, re)| re.is_match(message))
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+}
+
+/// Règles de reclassement appliquées avant l'analyse, pour corriger des
+/// niveaux mal attribués par l'application; la première qui correspond l'emporte.
+#[derive(Debug, Default)]
+pub struct Reclassifier {
+    rules: Vec<(Option<LogLevel>, Regex, LogLevel)>,
+}
+
+impl Reclassifier {
+    pub fn from_config(config: &Config) -> Result<Self, String> {
+        let level = |name: &str| {
+            LogLevel::from_str(name)
+                .ok_or_else(|| format!("Niveau '{name}' invalide dans [[reclassify]]"))
+        };
+        let rules = config
+            .reclassify
+            .iter()
+            .map(|rule| {
+                let re = Regex::new(&rule.pattern).map_err(|e| {
+                    format!("Règle de reclassement '{}' invalide: {e}", rule.pattern)
+                })?;
+                let from = rule.from.as_deref().map(level).transpose()?;
+                Ok((from, re, level(&rule.to)?))
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Reclassifier { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn level(&self, level: &LogLevel, message: &str) -> Option<LogLevel> {
+        self.rules
+            .iter()
+            .find(|(from, re, _)| from.as_ref().is_none_or(|f| f == level) && re.is_match(message))
+            .map(|(_, _, to)| to.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_taxonomy_matches_common_errors() {
+        let cat = Categorizer::default();
+        assert_eq!(
+            cat.categorize("Failed to connect to API: timeout"),
+            "network"
+        );
+        assert_eq!(
+            cat.categorize("Database query failed: syntax error"),
+            "database"
+        );
+        assert_eq!(cat.categorize("User unauthorized"), "auth");
+        assert_eq!(cat.categorize("Something odd"), UNCATEGORIZED);
+    }
+
+    #[test]
+    fn config_rules_take_precedence() {
+        let config: Config = toml::from_str(
+            r#"
+            [[category]]
+            name = "payment"
+            pattern = "(?i)stripe"
+            "#,
+        )
+        .unwrap();
+        let cat = Categorizer::from_config(&config).unwrap();
+        assert_eq!(cat.categorize("Stripe timeout"), "payment");
+        assert_eq!(cat.categorize("API timeout"), "network");
+    }
+
+    #[test]
+    fn tagger_collects_every_matching_tag() {
+        let config: Config = toml::from_str(
+            r#"
+            [[tag]]
+            name = "payment"
+            pattern = "stripe|invoice"
+
+            [[tag]]
+            name = "external"
+            pattern = "stripe|api"
+            "#,
+        )
+        .unwrap();
+        let tagger = Tagger::from_config(&config).unwrap();
+        assert_eq!(
+            tagger.tags("stripe charge failed"),
+            vec!["payment", "external"]
+        );
+        assert!(tagger.tags("cache miss").is_empty());
+    }
+
+    #[test]
+    fn reclassifier_escalates_matching_levels() {
+        let config: Config = toml::from_str(
+            r#"
+            [[reclassify]]
+            from = "WARNING"
+            pattern = 'disk 9\d%'
+            to = "ERROR"
+
+            [[reclassify]]
+            pattern = "healthcheck"
+            to = "debug"
+            "#,
+        )
+        .unwrap();
+        let rules = Reclassifier::from_config(&config).unwrap();
+        assert_eq!(
+            rules.level(&LogLevel::Warning, "disk 95% full"),
+            Some(LogLevel::Error)
+        );
+        assert_eq!(rules.level(&LogLevel::Info, "disk 95% full"), None);
+        assert_eq!(rules.level(&LogLevel::Warning, "disk 80% full"), None);
+        assert_eq!(
+            rules.level(&LogLevel::Info, "GET /healthcheck"),
+            Some(LogLevel::Debug)
+        );
+
+        let config: Config = toml::from_str(
+            r#"
+            [[reclassify]]
+            pattern = "x"
+            to = "FATAL"
+            "#,
+        )
+        .unwrap();
+        assert!(Reclassifier::from_config(&config).is_err());
+    }
+}
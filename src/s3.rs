@@ -0,0 +1,138 @@
+use aws_config::BehaviorVersion;
+use aws_config::meta::region::RegionProviderChain;
+use aws_sdk_s3::Client;
+use aws_sdk_s3::error::DisplayErrorContext;
+use std::env;
+use std::io::{self, Read};
+use std::pin::Pin;
+use std::sync::OnceLock;
+use tokio::io::{AsyncBufRead, AsyncReadExt};
+use tokio::runtime::Runtime;
+
+/// Région retenue quand la chaîne du SDK n'en trouve aucune
+const DEFAULT_REGION: &str = "us-east-1";
+
+/// Client partagé par les entrées S3 d'une exécution, avec le runtime qui le
+/// porte: les identifiants ne sont résolus qu'une fois
+struct Shared {
+    runtime: Runtime,
+    client: Client,
+}
+
+static SHARED: OnceLock<Shared> = OnceLock::new();
+
+/// Client configuré par la chaîne standard du SDK: variables AWS_*, profils
+/// de ~/.aws (SSO, credential_process...), identité web (IRSA), métadonnées
+/// d'instance (IMDS). Avec AWS_ENDPOINT_URL(_S3) (MinIO, Ceph...), les
+/// requêtes passent par le chemin `endpoint/bucket/clé`.
+fn shared() -> io::Result<&'static Shared> {
+    if let Some(shared) = SHARED.get() {
+        return Ok(shared);
+    }
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .enable_all()
+        .build()?;
+    let client = runtime.block_on(async {
+        let region = RegionProviderChain::default_provider().or_else(DEFAULT_REGION);
+        let config = aws_config::defaults(BehaviorVersion::latest())
+            .region(region)
+            .load()
+            .await;
+        let path_style =
+            config.endpoint_url().is_some() || env::var_os("AWS_ENDPOINT_URL_S3").is_some();
+        let config = aws_sdk_s3::config::Builder::from(&config)
+            .force_path_style(path_style)
+            .build();
+        Client::from_conf(config)
+    });
+    Ok(SHARED.get_or_init(|| Shared { runtime, client }))
+}
+
+/// Objet désigné par `s3://bucket/clé`
+#[derive(Debug, PartialEq)]
+struct Object<'a> {
+    bucket: &'a str,
+    key: &'a str,
+}
+
+fn object(url: &str) -> Option<Object<'_>> {
+    let (bucket, key) = url.strip_prefix("s3://")?.split_once('/')?;
+    (!bucket.is_empty() && !key.is_empty()).then_some(Object { bucket, key })
+}
+
+fn parse(url: &str) -> io::Result<Object<'_>> {
+    object(url).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("URL S3 invalide: {url} (s3://bucket/clé)"),
+        )
+    })
+}
+
+fn to_io(err: impl std::error::Error + Send + Sync + 'static, not_found: bool) -> io::Error {
+    if not_found {
+        return io::ErrorKind::NotFound.into();
+    }
+    io::Error::other(DisplayErrorContext(err).to_string())
+}
+
+/// Taille de l'objet, lue par HeadObject
+pub fn content_length(url: &str) -> io::Result<Option<u64>> {
+    let Object { bucket, key } = parse(url)?;
+    let shared = shared()?;
+    let output = shared
+        .runtime
+        .block_on(shared.client.head_object().bucket(bucket).key(key).send())
+        .map_err(|err| {
+            let not_found = err.as_service_error().is_some_and(|e| e.is_not_found());
+            to_io(err, not_found)
+        })?;
+    Ok(output.content_length().and_then(|n| u64::try_from(n).ok()))
+}
+
+/// Corps d'un GetObject, lu au fil de l'analyse sans être téléchargé d'abord
+struct Body {
+    runtime: &'static Runtime,
+    reader: Pin<Box<dyn AsyncBufRead + Send>>,
+}
+
+impl Read for Body {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.runtime.block_on(self.reader.read(buf))
+    }
+}
+
+pub fn open(url: &str) -> io::Result<Box<dyn Read>> {
+    let Object { bucket, key } = parse(url)?;
+    let shared = shared()?;
+    let output = shared
+        .runtime
+        .block_on(shared.client.get_object().bucket(bucket).key(key).send())
+        .map_err(|err| {
+            let not_found = err.as_service_error().is_some_and(|e| e.is_no_such_key());
+            to_io(err, not_found)
+        })?;
+    Ok(Box::new(Body {
+        runtime: &shared.runtime,
+        reader: Box::pin(output.body.into_async_read()),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_object_urls() {
+        assert_eq!(
+            object("s3://logs/2024/01/app.log.gz"),
+            Some(Object {
+                bucket: "logs",
+                key: "2024/01/app.log.gz"
+            })
+        );
+        assert_eq!(object("s3://logs/"), None);
+        assert!(parse("s3://logs").is_err());
+    }
+}
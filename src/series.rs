@@ -0,0 +1,94 @@
+use crate::LogEntry;
+use crate::chart::{STACK, hourly_series};
+use crate::outfile::{self, Existing};
+use chrono::Duration;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+const HEADER: &str = "bucket,level,count";
+
+/// Comptes par heure et par niveau au format long (`bucket,level,count`), une
+/// ligne par couple, heures vides comprises pour que la série reste régulière
+fn write_rows(out: &mut impl Write, entries: &[LogEntry]) -> io::Result<()> {
+    let Some(series) = hourly_series(entries) else {
+        return Ok(());
+    };
+    for (h, counts) in series.counts.iter().enumerate() {
+        let bucket = series.start + Duration::hours(h as i64);
+        for ((level, _), count) in STACK.iter().zip(counts) {
+            writeln!(
+                out,
+                "{},{},{count}",
+                bucket.format("%Y-%m-%d %H:%M:%S"),
+                level.as_str()
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Écrit la série dans `path` ligne à ligne, sans la composer en mémoire.
+/// Avec `Existing::Append`, l'en-tête n'est écrit que dans un fichier vide.
+pub fn write(path: &Path, entries: &[LogEntry], existing: Existing) -> io::Result<()> {
+    if existing == Existing::Append {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let empty = file.metadata()?.len() == 0;
+        let mut out = BufWriter::new(file);
+        if empty {
+            writeln!(out, "{HEADER}")?;
+        }
+        write_rows(&mut out, entries)?;
+        return out.flush();
+    }
+    if existing == Existing::Refuse && path.exists() {
+        return Err(outfile::already_exists(path));
+    }
+    let tmp = outfile::temp_path(path);
+    let written = File::create(&tmp).and_then(|file| {
+        let mut out = BufWriter::new(file);
+        writeln!(out, "{HEADER}")?;
+        write_rows(&mut out, entries)?;
+        out.flush()
+    });
+    written
+        .and_then(|_| fs::rename(&tmp, path))
+        .inspect_err(|_| {
+            let _ = fs::remove_file(&tmp);
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_log_line;
+
+    #[test]
+    fn writes_one_row_per_hour_and_level() {
+        let entries: Vec<LogEntry> = [
+            "2024-01-15 10:05:00 [ERROR] db down",
+            "2024-01-15 10:40:00 [INFO] retry",
+            "2024-01-15 12:10:00 [ERROR] db down",
+        ]
+        .iter()
+        .filter_map(|l| parse_log_line(l))
+        .collect();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("series.csv");
+        write(&path, &entries, Existing::Refuse).unwrap();
+
+        let text = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 1 + 3 * STACK.len());
+        assert_eq!(lines[0], HEADER);
+        assert!(lines.contains(&"2024-01-15 10:00:00,ERROR,1"));
+        assert!(lines.contains(&"2024-01-15 10:00:00,INFO,1"));
+        assert!(lines.contains(&"2024-01-15 11:00:00,ERROR,0"));
+        assert!(write(&path, &entries, Existing::Refuse).is_err());
+
+        write(&path, &entries[..1], Existing::Append).unwrap();
+        let appended = fs::read_to_string(&path).unwrap();
+        assert_eq!(appended.matches(HEADER).count(), 1);
+        assert_eq!(appended.lines().count(), lines.len() + STACK.len());
+    }
+}
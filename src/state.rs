@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Positions de lecture par source (décalage d'un fichier, identifiant d'un
+/// stream...), persistées par `--state` pour reprendre après un redémarrage
+/// là où le dernier lot réémis s'est arrêté. En analyse ponctuelle, le même
+/// fichier garde les comptes par gabarit d'erreur et le dernier niveau de
+/// chaque gabarit lors de l'exécution précédente.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Checkpoints {
+    #[serde(default)]
+    sources: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error_groups: Option<BTreeMap<String, usize>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    template_levels: Option<BTreeMap<String, String>>,
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl Checkpoints {
+    /// Un fichier absent équivaut à un état vide (premier lancement).
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let mut checkpoints: Checkpoints = match fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content)?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Checkpoints::default(),
+            Err(err) => return Err(err),
+        };
+        checkpoints.path = path.to_path_buf();
+        Ok(checkpoints)
+    }
+
+    pub fn get(&self, source: &str) -> Option<&str> {
+        self.sources.get(source).map(String::as_str)
+    }
+
+    /// Enregistre la position et réécrit le fichier si elle a changé.
+    pub fn commit(&mut self, source: &str, cursor: String) -> io::Result<()> {
+        if self.get(source) == Some(cursor.as_str()) {
+            return Ok(());
+        }
+        self.sources.insert(source.to_string(), cursor);
+        self.save()
+    }
+
+    /// Comptes par gabarit d'erreur de l'exécution précédente, `None` au
+    /// premier lancement
+    pub fn error_groups(&self) -> Option<&BTreeMap<String, usize>> {
+        self.error_groups.as_ref()
+    }
+
+    /// Dernier niveau de chaque gabarit à l'exécution précédente
+    pub fn template_levels(&self) -> Option<&BTreeMap<String, String>> {
+        self.template_levels.as_ref()
+    }
+
+    /// Remplace les relevés de l'exécution précédente par ceux-ci
+    pub fn commit_run(
+        &mut self,
+        error_groups: BTreeMap<String, usize>,
+        template_levels: BTreeMap<String, String>,
+    ) -> io::Result<()> {
+        self.error_groups = Some(error_groups);
+        self.template_levels = Some(template_levels);
+        self.save()
+    }
+
+    fn save(&self) -> io::Result<()> {
+        // Écriture puis renommage: un arrêt brutal laisse l'ancien état intact
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&tmp, &self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn persists_cursor_per_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        let mut checkpoints = Checkpoints::load(&path).unwrap();
+        assert_eq!(checkpoints.get("app.log"), None);
+
+        checkpoints.commit("app.log", "120".to_string()).unwrap();
+        checkpoints
+            .commit("redis logs", "1705314645000-0".to_string())
+            .unwrap();
+
+        let reloaded = Checkpoints::load(&path).unwrap();
+        assert_eq!(reloaded.get("app.log"), Some("120"));
+        assert_eq!(reloaded.get("redis logs"), Some("1705314645000-0"));
+        assert!(!path.with_extension("tmp").exists());
+
+        let mut reloaded = reloaded;
+        assert_eq!(reloaded.error_groups(), None);
+        let groups = BTreeMap::from([("disk full".to_string(), 3)]);
+        let levels = BTreeMap::from([("disk full".to_string(), "ERROR".to_string())]);
+        reloaded.commit_run(groups.clone(), levels.clone()).unwrap();
+        let reloaded = Checkpoints::load(&path).unwrap();
+        assert_eq!(reloaded.error_groups(), Some(&groups));
+        assert_eq!(reloaded.template_levels(), Some(&levels));
+        assert_eq!(reloaded.get("app.log"), Some("120"));
+    }
+}
@@ -0,0 +1,206 @@
+use crate::locale::Locale;
+use crate::{LogEntry, LogLevel, callers, noise};
+use prettytable::{Cell, Row, Table};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Instruction de log à rétrograder ou supprimer, avec le volume économisé
+/// si la production n'ingère plus que INFO et au-delà
+#[derive(Debug, PartialEq)]
+pub struct Suggestion {
+    pub action: &'static str,
+    pub level: &'static str,
+    pub template: String,
+    /// Site d'appel le plus fréquent du gabarit, s'il figure dans les messages
+    pub call_site: Option<String>,
+    pub count: usize,
+    pub savings_bytes: u64,
+    /// Part du volume total analysé, en %
+    pub savings_pct: f64,
+    pub variability: f64,
+}
+
+/// Rapport de `suggest-suppressions`
+#[derive(Debug)]
+pub struct SuppressionReport {
+    pub entries: usize,
+    pub total_bytes: u64,
+    pub suggestions: Vec<Suggestion>,
+}
+
+impl SuppressionReport {
+    /// Volume cumulé des suggestions retenues
+    pub fn savings_bytes(&self) -> u64 {
+        self.suggestions.iter().map(|s| s.savings_bytes).sum()
+    }
+}
+
+/// DEBUG est supprimé, INFO et WARNING passent en DEBUG; les erreurs ne
+/// sont jamais proposées.
+fn action(level: &LogLevel) -> Option<&'static str> {
+    match level {
+        LogLevel::Debug => Some("remove"),
+        LogLevel::Info | LogLevel::Warning => Some("demote to DEBUG"),
+        LogLevel::Error => None,
+    }
+}
+
+#[derive(Default)]
+struct Candidate<'a> {
+    bytes: u64,
+    messages: HashMap<&'a str, usize>,
+    call_sites: HashMap<String, usize>,
+}
+
+/// Gabarits hors ERROR classés par volume pondéré par `1 - variability`: un
+/// message fréquent et toujours identique passe avant un message aussi
+/// volumineux mais porteur d'informations différentes à chaque fois.
+pub fn suggest(entries: &[LogEntry], top_n: usize) -> SuppressionReport {
+    let mut total_bytes = 0;
+    let mut candidates: HashMap<(LogLevel, String), Candidate> = HashMap::new();
+    for entry in entries {
+        let bytes = entry.line_bytes();
+        total_bytes += bytes;
+        if action(&entry.level).is_none() {
+            continue;
+        }
+        let candidate = candidates
+            .entry((entry.level.clone(), noise::normalize(&entry.message)))
+            .or_default();
+        candidate.bytes += bytes;
+        *candidate
+            .messages
+            .entry(entry.message.as_str())
+            .or_insert(0) += 1;
+        if let Some(site) = callers::call_site(entry) {
+            *candidate.call_sites.entry(site).or_insert(0) += 1;
+        }
+    }
+
+    let mut ranked: Vec<(f64, Suggestion)> = candidates
+        .into_iter()
+        .filter_map(|((level, template), c)| {
+            let count = c.messages.values().sum();
+            let variability = noise::normalized_entropy(c.messages.values(), count);
+            let call_site = c
+                .call_sites
+                .into_iter()
+                .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+                .map(|(site, _)| site);
+            let suggestion = Suggestion {
+                action: action(&level)?,
+                level: level.as_str(),
+                template,
+                call_site,
+                count,
+                savings_bytes: c.bytes,
+                savings_pct: c.bytes as f64 / total_bytes.max(1) as f64 * 100.0,
+                variability,
+            };
+            Some((c.bytes as f64 * (1.0 - variability), suggestion))
+        })
+        .collect();
+    ranked.sort_by(|a, b| {
+        b.0.total_cmp(&a.0)
+            .then_with(|| a.1.template.cmp(&b.1.template))
+    });
+    ranked.truncate(top_n);
+
+    SuppressionReport {
+        entries: entries.len(),
+        total_bytes,
+        suggestions: ranked.into_iter().map(|(_, s)| s).collect(),
+    }
+}
+
+pub fn render(report: &SuppressionReport, source: &str) -> String {
+    let locale = Locale::Plain;
+    let mut output = String::new();
+    writeln!(output, "Suppression candidates: {source}\n").unwrap();
+    if report.suggestions.is_empty() {
+        writeln!(
+            output,
+            "Aucune instruction DEBUG, INFO ou WARNING à proposer."
+        )
+        .unwrap();
+        return output;
+    }
+    let savings = report.savings_bytes();
+    writeln!(
+        output,
+        "{} entrées, {} analysés; les {} suggestions ci-dessous économiseraient {} ({}).\n",
+        report.entries,
+        locale.bytes(report.total_bytes),
+        report.suggestions.len(),
+        locale.bytes(savings),
+        locale.pct(savings as f64 / report.total_bytes.max(1) as f64 * 100.0, 1)
+    )
+    .unwrap();
+
+    let mut table = Table::new();
+    table.add_row(Row::new(vec![
+        Cell::new("#"),
+        Cell::new("Action"),
+        Cell::new("Level"),
+        Cell::new("Call site"),
+        Cell::new("Template"),
+        Cell::new("Count"),
+        Cell::new("Savings"),
+        Cell::new("Share"),
+        Cell::new("Variability"),
+    ]));
+    for (rank, s) in report.suggestions.iter().enumerate() {
+        table.add_row(Row::new(vec![
+            Cell::new(&(rank + 1).to_string()),
+            Cell::new(s.action),
+            Cell::new(s.level),
+            Cell::new(s.call_site.as_deref().unwrap_or("-")),
+            Cell::new(&s.template),
+            Cell::new(&s.count.to_string()),
+            Cell::new(&locale.bytes(s.savings_bytes)),
+            Cell::new(&locale.pct(s.savings_pct, 1)),
+            Cell::new(&locale.dec(s.variability, 2)),
+        ]));
+    }
+    write!(output, "{table}").unwrap();
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_log_line;
+
+    #[test]
+    fn ranks_repetitive_statements_by_savings() {
+        let mut lines = vec![
+            "2024-01-15 10:00:00 [ERROR] src/db.rs:10 connection reset".to_string(),
+            "2024-01-15 10:00:00 [INFO] src/api.rs:55 request id=1".to_string(),
+            "2024-01-15 10:00:00 [INFO] src/api.rs:55 request id=2".to_string(),
+        ];
+        for _ in 0..4 {
+            lines.push("2024-01-15 10:00:00 [DEBUG] src/poll.rs:7 polling queue".to_string());
+        }
+        let entries: Vec<LogEntry> = lines.iter().map(|l| parse_log_line(l).unwrap()).collect();
+
+        let report = suggest(&entries, 5);
+        assert_eq!(report.entries, 7);
+        assert_eq!(report.suggestions.len(), 2);
+        let first = &report.suggestions[0];
+        assert_eq!(first.action, "remove");
+        assert_eq!(first.call_site.as_deref(), Some("src/poll.rs:7"));
+        assert_eq!(first.count, 4);
+        assert_eq!(first.variability, 0.0);
+        let second = &report.suggestions[1];
+        assert_eq!(second.action, "demote to DEBUG");
+        assert_eq!(second.variability, 1.0);
+        assert_eq!(
+            report.savings_bytes(),
+            report.total_bytes - entries[0].line_bytes()
+        );
+
+        let text = render(&report, "app.log");
+        assert!(text.contains("src/poll.rs:7"));
+        assert!(render(&suggest(&entries[..1], 5), "app.log").contains("Aucune instruction"));
+    }
+}
@@ -0,0 +1,265 @@
+use crate::jsonl::{parse_level, parse_time};
+use crate::{LogEntry, LogLevel};
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::BTreeMap;
+
+/// `[<PRI>]Mmm jj HH:MM:SS hôte app[pid]: message` (RFC 3164)
+static LINE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^(?:<(\d{1,3})>)?([A-Z][a-z]{2}) +(\d{1,2}) (\d{2}:\d{2}:\d{2}) (\S+) (?:([^\s:\[]+)(?:\[(\d+)\])?: ?)?(.*)$",
+    )
+    .unwrap()
+});
+
+/// `<PRI>VERSION horodatage hôte app procid msgid données-structurées [message]`
+/// (RFC 5424), champs absents notés `-`
+static LINE_5424: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^<(\d{1,3})>\d{1,2} (\S+) (\S+) (\S+) (\S+) (\S+) (.*)$").unwrap());
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// RFC 3164 n'indique pas l'année: on retient la plus récente qui ne place
+/// pas l'entrée après `reference` (date de modification du fichier, ou
+/// l'instant présent en suivi continu), à un jour près pour les fuseaux.
+/// Un fichier écrit en janvier garde ainsi ses lignes de décembre dans
+/// l'année précédente.
+fn infer_year(
+    month: u32,
+    day: u32,
+    time: NaiveTime,
+    reference: NaiveDateTime,
+) -> Option<NaiveDateTime> {
+    let limit = reference + chrono::Duration::days(1);
+    // Jusqu'à 4 ans en arrière pour trouver un 29 février
+    (0..=4).find_map(|back| {
+        NaiveDate::from_ymd_opt(reference.year() - back, month, day)
+            .map(|date| date.and_time(time))
+            .filter(|datetime| *datetime <= limit)
+    })
+}
+
+/// Sévérité de la priorité syslog vers niveau
+fn severity(pri: u8) -> LogLevel {
+    match pri % 8 {
+        0..=3 => LogLevel::Error,
+        4 => LogLevel::Warning,
+        5 | 6 => LogLevel::Info,
+        _ => LogLevel::Debug,
+    }
+}
+
+/// Niveau annoncé par le premier mot d'un message BSD (`error:`, `[WARN]`...),
+/// sinon par la priorité, sinon INFO
+fn level(message: &str, pri: Option<&str>) -> LogLevel {
+    message
+        .split_whitespace()
+        .next()
+        .and_then(|word| {
+            parse_level(word.trim_matches(|c| matches!(c, '[' | ']' | '<' | '>' | ':')))
+        })
+        .or_else(|| pri.and_then(|p| p.parse().ok()).map(severity))
+        .unwrap_or(LogLevel::Info)
+}
+
+/// Valeur d'un paramètre de données structurées, après son `="`: texte
+/// jusqu'au guillemet fermant, où `\"`, `\\` et `\]` désignent le caractère
+/// échappé. Renvoie aussi ce qui suit le guillemet.
+fn param_value(input: &str) -> Option<(String, &str)> {
+    let mut value = String::new();
+    let mut chars = input.char_indices();
+    loop {
+        match chars.next()? {
+            (_, '\\') => {
+                let (_, c) = chars.next()?;
+                if !matches!(c, '"' | '\\' | ']') {
+                    value.push('\\');
+                }
+                value.push(c);
+            }
+            (i, '"') => return Some((value, &input[i + 1..])),
+            (_, c) => value.push(c),
+        }
+    }
+}
+
+/// Bloc de données structurées RFC 5424 (`-` ou `[id clé="valeur" ...]...`)
+/// suivi du message: paramètres dans l'ordre, et reste de la ligne
+fn structured_data(input: &str) -> Option<(Vec<(String, String)>, &str)> {
+    let mut params = Vec::new();
+    let mut rest = match input.strip_prefix('-') {
+        Some(rest) => rest,
+        None => {
+            let mut rest = input;
+            while let Some(element) = rest.strip_prefix('[') {
+                // Identifiant de l'élément, puis ses paramètres
+                let mut body = &element[element.find([' ', ']'])?..];
+                loop {
+                    body = body.trim_start_matches(' ');
+                    if let Some(after) = body.strip_prefix(']') {
+                        rest = after;
+                        break;
+                    }
+                    let (name, after) = body.split_once("=\"")?;
+                    let (value, after) = param_value(after)?;
+                    params.push((name.to_string(), value));
+                    body = after;
+                }
+            }
+            if rest.len() == input.len() {
+                return None;
+            }
+            rest
+        }
+    };
+    if !rest.is_empty() {
+        rest = rest.strip_prefix(' ')?;
+    }
+    Some((params, rest))
+}
+
+/// Une ligne syslog RFC 5424. Le niveau vient de la priorité, toujours
+/// présente; un horodatage absent (`-`) est remplacé par `reference`. Hôte,
+/// application, procid et msgid deviennent les champs `host`, `app`, `pid`
+/// et `msgid`; chaque paramètre des données structurées devient un champ à
+/// son nom, sans l'identifiant de son élément (le premier l'emporte).
+fn parse_rfc5424(line: &str, reference: NaiveDateTime) -> Option<LogEntry> {
+    let caps = LINE_5424.captures(line)?;
+    let pri: u8 = caps[1].parse().ok()?;
+    let datetime = match &caps[2] {
+        "-" => reference,
+        time => parse_time(time)?,
+    };
+    let (params, message) = structured_data(caps.get(7)?.as_str())?;
+    let message = message.trim_start_matches('\u{feff}').to_string();
+
+    let mut fields = BTreeMap::new();
+    for (key, group) in [("host", 3), ("app", 4), ("pid", 5), ("msgid", 6)] {
+        if &caps[group] != "-" {
+            fields.insert(key.to_string(), caps[group].to_string());
+        }
+    }
+    for (name, value) in params {
+        fields.entry(name).or_insert(value);
+    }
+    Some(LogEntry {
+        timestamp: datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
+        datetime,
+        level: severity(pri),
+        message,
+        tags: Vec::new(),
+        fields,
+    })
+}
+
+/// Une ligne syslog RFC 5424 (`<PRI>1 ...`), sinon BSD (RFC 3164); pour une
+/// ligne BSD, le niveau vient du premier mot du message (`error:`,
+/// `[WARN]`...), sinon de la priorité, sinon INFO. L'hôte, le programme et
+/// son pid deviennent les champs `host`, `app` et `pid`.
+pub fn parse_line(line: &str, reference: NaiveDateTime) -> Option<LogEntry> {
+    if LINE_5424.is_match(line) {
+        return parse_rfc5424(line, reference);
+    }
+    let caps = LINE.captures(line)?;
+    let month = MONTHS.iter().position(|m| *m == &caps[2])? as u32 + 1;
+    let time = NaiveTime::parse_from_str(&caps[4], "%H:%M:%S").ok()?;
+    let datetime = infer_year(month, caps[3].parse().ok()?, time, reference)?;
+    let message = caps[8].to_string();
+    let level = level(&message, caps.get(1).map(|p| p.as_str()));
+
+    let mut fields = BTreeMap::from([("host".to_string(), caps[5].to_string())]);
+    for (key, group) in [("app", 6), ("pid", 7)] {
+        if let Some(value) = caps.get(group) {
+            fields.insert(key.to_string(), value.as_str().to_string());
+        }
+    }
+    Some(LogEntry {
+        timestamp: datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
+        datetime,
+        level,
+        message,
+        tags: Vec::new(),
+        fields,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_bsd_syslog_and_infers_the_year() {
+        let reference = NaiveDate::from_ymd_opt(2024, 1, 16)
+            .unwrap()
+            .and_hms_opt(8, 0, 0)
+            .unwrap();
+        let entry = parse_line(
+            "Jan 15 10:30:45 web1 nginx[1234]: error: upstream timed out",
+            reference,
+        )
+        .unwrap();
+        assert_eq!(entry.timestamp, "2024-01-15 10:30:45");
+        assert_eq!(entry.level, LogLevel::Error);
+        assert_eq!(entry.message, "error: upstream timed out");
+        assert_eq!(entry.fields["host"], "web1");
+        assert_eq!(entry.fields["app"], "nginx");
+        assert_eq!(entry.fields["pid"], "1234");
+
+        // Décembre dans un fichier écrit en janvier: année précédente
+        let entry = parse_line("<12>Dec 31 23:59:59 fw1 kernel: link down", reference).unwrap();
+        assert_eq!(entry.timestamp, "2023-12-31 23:59:59");
+        assert_eq!(entry.level, LogLevel::Warning);
+        assert!(!entry.fields.contains_key("pid"));
+
+        let entry = parse_line("Jan  5 00:00:01 host1 CRON job started", reference).unwrap();
+        assert_eq!(entry.level, LogLevel::Info);
+        assert_eq!(entry.message, "CRON job started");
+
+        assert!(parse_line("2024-01-15 10:30:45 [ERROR] text", reference).is_none());
+        assert!(parse_line("Foo 15 10:30:45 host app: x", reference).is_none());
+    }
+
+    #[test]
+    fn parses_rfc5424_with_structured_data() {
+        let reference = NaiveDate::from_ymd_opt(2024, 1, 16)
+            .unwrap()
+            .and_hms_opt(8, 0, 0)
+            .unwrap();
+        let line = "<165>1 2024-01-15T10:30:45.003+01:00 web1 evntslog 4242 ID47 \
+                    [exampleSDID@32473 iut=\"3\" eventSource=\"App\\\"lication\\]\"]\
+                    [meta iut=\"9\" seq=\"7\"] \u{feff}disk almost full";
+        let entry = parse_line(line, reference).unwrap();
+        assert_eq!(entry.timestamp, "2024-01-15 09:30:45");
+        assert_eq!(entry.level, LogLevel::Info);
+        assert_eq!(entry.message, "disk almost full");
+        assert_eq!(entry.fields["host"], "web1");
+        assert_eq!(entry.fields["app"], "evntslog");
+        assert_eq!(entry.fields["pid"], "4242");
+        assert_eq!(entry.fields["msgid"], "ID47");
+        assert_eq!(entry.fields["eventSource"], "App\"lication]");
+        assert_eq!(entry.fields["iut"], "3");
+        assert_eq!(entry.fields["seq"], "7");
+
+        let entry = parse_line(
+            "<11>1 2024-01-15T10:30:45Z - sshd - - - auth failed",
+            reference,
+        )
+        .unwrap();
+        assert_eq!(entry.level, LogLevel::Error);
+        assert_eq!(entry.message, "auth failed");
+        assert!(!entry.fields.contains_key("host"));
+
+        let entry = parse_line("<14>1 2024-01-15T10:30:45Z h app - - -", reference).unwrap();
+        assert_eq!(entry.message, "");
+        assert!(parse_line("<14>1 2024-01-15T10:30:45Z h app - - [broken", reference).is_none());
+        assert!(parse_line("<14>1 bad-time h app - - - x", reference).is_none());
+
+        // La priorité l'emporte sur le premier mot; horodatage absent
+        let entry = parse_line("<14>1 - h app - - - Error handling done", reference).unwrap();
+        assert_eq!(entry.level, LogLevel::Info);
+        assert_eq!(entry.datetime, reference);
+    }
+}
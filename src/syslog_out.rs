@@ -0,0 +1,111 @@
+use crate::forward::Forwarder;
+use crate::{LogEntry, LogLevel};
+use std::io::{self, Write};
+use std::net::{TcpStream, UdpSocket};
+
+/// Facility `user` (1) de la RFC 5424
+const FACILITY: u8 = 1;
+const APP_NAME: &str = "loglyzer";
+const DEFAULT_PORT: u16 = 514;
+
+enum Transport {
+    Udp(UdpSocket),
+    /// Trames à comptage d'octets (RFC 6587)
+    Tcp(TcpStream),
+}
+
+/// Réémet des entrées vers un serveur syslog en RFC 5424.
+pub struct SyslogForwarder {
+    transport: Transport,
+    proc_id: u32,
+}
+
+fn severity(level: &LogLevel) -> u8 {
+    match level {
+        LogLevel::Error => 3,
+        LogLevel::Warning => 4,
+        LogLevel::Info => 6,
+        LogLevel::Debug => 7,
+    }
+}
+
+/// `<PRI>1 TIMESTAMP HOSTNAME APP-NAME PROCID MSGID SD MSG`; l'horodatage
+/// d'origine est supposé UTC, faute de fuseau dans le format natif.
+pub fn format_rfc5424(entry: &LogEntry, proc_id: u32) -> String {
+    format!(
+        "<{}>1 {} - {APP_NAME} {proc_id} - - {}",
+        FACILITY * 8 + severity(&entry.level),
+        entry.datetime.format("%Y-%m-%dT%H:%M:%SZ"),
+        entry.message
+    )
+}
+
+impl SyslogForwarder {
+    /// `syslog://hôte[:port]` (UDP) ou `syslog+tcp://hôte[:port]`
+    pub fn connect(url: &str) -> io::Result<Self> {
+        let invalid = || {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "URL syslog invalide: {url} (syslog://hôte:port ou syslog+tcp://hôte:port)"
+                ),
+            )
+        };
+        let (scheme, host) = url.split_once("://").ok_or_else(invalid)?;
+        let addr = if host.contains(':') {
+            host.to_string()
+        } else {
+            format!("{host}:{DEFAULT_PORT}")
+        };
+        let transport = match scheme {
+            "syslog" | "syslog+udp" => {
+                let socket = UdpSocket::bind("0.0.0.0:0")?;
+                socket.connect(&addr)?;
+                Transport::Udp(socket)
+            }
+            "syslog+tcp" => Transport::Tcp(TcpStream::connect(&addr)?),
+            _ => return Err(invalid()),
+        };
+        Ok(SyslogForwarder {
+            transport,
+            proc_id: std::process::id(),
+        })
+    }
+}
+
+impl Forwarder for SyslogForwarder {
+    fn send(&mut self, entry: &LogEntry) -> io::Result<()> {
+        let message = format_rfc5424(entry, self.proc_id);
+        match &mut self.transport {
+            Transport::Udp(socket) => socket.send(message.as_bytes()).map(|_| ()),
+            Transport::Tcp(stream) => write!(stream, "{} {message}", message.len()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_log_line;
+
+    #[test]
+    fn sends_rfc5424_datagrams() {
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let url = format!("syslog://{}", server.local_addr().unwrap());
+        let mut forwarder = SyslogForwarder::connect(&url).unwrap();
+
+        let entry = parse_log_line("2024-01-15 10:30:45 [ERROR] disk full").unwrap();
+        forwarder.send(&entry).unwrap();
+
+        let mut buf = [0; 512];
+        let n = server.recv(&mut buf).unwrap();
+        assert_eq!(
+            std::str::from_utf8(&buf[..n]).unwrap(),
+            format!(
+                "<11>1 2024-01-15T10:30:45Z - loglyzer {} - - disk full",
+                std::process::id()
+            )
+        );
+        assert!(SyslogForwarder::connect("http://x").is_err());
+    }
+}
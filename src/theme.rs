@@ -0,0 +1,195 @@
+use crate::LogLevel;
+use clap::ValueEnum;
+use colored::{Color, ColoredString, Colorize};
+use serde::Deserialize;
+
+/// Thèmes de couleurs prédéfinis pour les niveaux
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeName {
+    /// ERROR en rouge, WARNING en jaune
+    #[default]
+    Default,
+    /// Tous les niveaux colorés (INFO en vert, DEBUG en gris)
+    Vivid,
+    /// Aucune couleur
+    Mono,
+}
+
+/// Section `[colors]` du fichier de configuration: un thème de base et des
+/// styles par niveau, ex. `error = "red bold"`, `debug = "bright black"`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ColorConfig {
+    #[serde(default)]
+    pub theme: ThemeName,
+    pub error: Option<String>,
+    pub warning: Option<String>,
+    pub info: Option<String>,
+    pub debug: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Style {
+    color: Option<Color>,
+    bold: bool,
+    dimmed: bool,
+    italic: bool,
+    underline: bool,
+}
+
+impl Style {
+    fn color(color: Color) -> Self {
+        Style {
+            color: Some(color),
+            ..Style::default()
+        }
+    }
+
+    fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    /// Analyse une spécification comme `"red bold"` ou `"bright black underline"`:
+    /// les attributs (bold, dimmed, italic, underline) sont retirés, le reste
+    /// forme le nom de la couleur.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut style = Style::default();
+        let mut color_words = Vec::new();
+        for word in spec.split_whitespace() {
+            match word.to_lowercase().as_str() {
+                "bold" => style.bold = true,
+                "dimmed" | "dim" => style.dimmed = true,
+                "italic" => style.italic = true,
+                "underline" => style.underline = true,
+                "none" => {}
+                other => color_words.push(other.to_string()),
+            }
+        }
+        if !color_words.is_empty() {
+            let name = color_words.join(" ");
+            let color = name
+                .parse::<Color>()
+                .map_err(|_| format!("Couleur inconnue: {name}"))?;
+            style.color = Some(color);
+        }
+        Ok(style)
+    }
+
+    pub fn apply(&self, text: &str) -> String {
+        let mut out: ColoredString = text.normal();
+        if let Some(color) = self.color {
+            out = out.color(color);
+        }
+        if self.bold {
+            out = out.bold();
+        }
+        if self.dimmed {
+            out = out.dimmed();
+        }
+        if self.italic {
+            out = out.italic();
+        }
+        if self.underline {
+            out = out.underline();
+        }
+        out.to_string()
+    }
+}
+
+/// Style appliqué à chaque niveau par `colorize_levels`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    error: Style,
+    warning: Style,
+    info: Style,
+    debug: Style,
+}
+
+impl Theme {
+    pub fn preset(name: ThemeName) -> Self {
+        match name {
+            ThemeName::Default => Theme {
+                error: Style::color(Color::Red).bold(),
+                warning: Style::color(Color::Yellow).bold(),
+                info: Style::default(),
+                debug: Style::default(),
+            },
+            ThemeName::Vivid => Theme {
+                error: Style::color(Color::BrightRed).bold(),
+                warning: Style::color(Color::Yellow).bold(),
+                info: Style::color(Color::Green),
+                debug: Style::color(Color::BrightBlack),
+            },
+            ThemeName::Mono => Theme {
+                error: Style::default(),
+                warning: Style::default(),
+                info: Style::default(),
+                debug: Style::default(),
+            },
+        }
+    }
+
+    /// Part du thème choisi (`--theme` prioritaire sur la configuration) puis
+    /// applique les styles par niveau de la configuration.
+    pub fn from_config(colors: &ColorConfig, theme: Option<ThemeName>) -> Result<Self, String> {
+        let mut out = Theme::preset(theme.unwrap_or(colors.theme));
+        for (spec, slot) in [
+            (&colors.error, &mut out.error),
+            (&colors.warning, &mut out.warning),
+            (&colors.info, &mut out.info),
+            (&colors.debug, &mut out.debug),
+        ] {
+            if let Some(spec) = spec {
+                *slot = Style::parse(spec)?;
+            }
+        }
+        Ok(out)
+    }
+
+    pub fn style(&self, level: &LogLevel) -> &Style {
+        match level {
+            LogLevel::Error => &self.error,
+            LogLevel::Warning => &self.warning,
+            LogLevel::Info => &self.info,
+            LogLevel::Debug => &self.debug,
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::preset(ThemeName::Default)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn style_parse_reads_color_and_attributes() {
+        let style = Style::parse("bright black bold").unwrap();
+        assert_eq!(style.color, Some(Color::BrightBlack));
+        assert!(style.bold);
+        assert!(Style::parse("chartreuse").is_err());
+    }
+
+    #[test]
+    fn config_overrides_preset() {
+        let colors: ColorConfig = toml::from_str(
+            r#"
+            theme = "mono"
+            debug = "blue"
+            "#,
+        )
+        .unwrap();
+        let theme = Theme::from_config(&colors, None).unwrap();
+        assert_eq!(theme.style(&LogLevel::Error), &Style::default());
+        assert_eq!(theme.style(&LogLevel::Debug).color, Some(Color::Blue));
+
+        let forced = Theme::from_config(&colors, Some(ThemeName::Default)).unwrap();
+        assert_eq!(forced.style(&LogLevel::Error).color, Some(Color::Red));
+    }
+}
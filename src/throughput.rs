@@ -0,0 +1,148 @@
+use crate::follow::LineSource;
+use std::io::{self, Read};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Débit plafonné des entrées distantes, fixé une fois d'après --max-throughput
+static REMOTE_LIMIT: OnceLock<u64> = OnceLock::new();
+
+pub fn limit_remote(rate: u64) {
+    let _ = REMOTE_LIMIT.set(rate);
+}
+
+/// Débit de `--max-throughput`, en octets par seconde (ex: 50MB/s, 2GB/min)
+pub fn parse_throughput(input: &str) -> Result<u64, String> {
+    let invalid = || format!("Débit invalide: {input} (ex: 50MB/s)");
+    let (size, per) = input.split_once('/').ok_or_else(invalid)?;
+    let size = crate::parse_size(size).map_err(|_| invalid())?;
+    let per = crate::parse_period(per).map_err(|_| invalid())?;
+    let rate = (size as f64 / per.as_secs_f64()) as u64;
+    if rate == 0 {
+        return Err(invalid());
+    }
+    Ok(rate)
+}
+
+/// Seau à jetons: `rate` octets par seconde, rafales d'une seconde au plus.
+/// Un lot plus gros que le seau est accepté, la dette étant attendue ensuite.
+pub struct TokenBucket {
+    rate: f64,
+    tokens: f64,
+    last: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(rate: u64) -> Self {
+        TokenBucket {
+            rate: rate as f64,
+            tokens: rate as f64,
+            last: Instant::now(),
+        }
+    }
+
+    /// Prélève `bytes` à l'instant `now` et renvoie l'attente qui ramène le
+    /// débit moyen sous la limite
+    pub fn take(&mut self, bytes: u64, now: Instant) -> Duration {
+        let refill = now.saturating_duration_since(self.last).as_secs_f64() * self.rate;
+        self.tokens = (self.tokens + refill).min(self.rate) - bytes as f64;
+        self.last = now;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        }
+    }
+}
+
+/// Lecture ou source suivie bridée: après chaque lot, la lecture suivante
+/// attend que le seau se remplisse. Pour une source réseau, les fils de
+/// réception se bloquent alors sur leur file pleine et cessent de lire la
+/// connexion, ce qui ralentit l'émetteur.
+pub struct Throttled<S> {
+    inner: S,
+    bucket: TokenBucket,
+}
+
+impl<S> Throttled<S> {
+    pub fn new(inner: S, rate: u64) -> Self {
+        Throttled {
+            inner,
+            bucket: TokenBucket::new(rate),
+        }
+    }
+
+    fn pace(&mut self, bytes: usize) {
+        let wait = self.bucket.take(bytes as u64, Instant::now());
+        if !wait.is_zero() {
+            thread::sleep(wait);
+        }
+    }
+}
+
+impl<R: Read> Read for Throttled<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.pace(n);
+        Ok(n)
+    }
+}
+
+impl LineSource for Throttled<&mut dyn LineSource> {
+    fn poll_lines(&mut self) -> io::Result<Vec<(String, Option<String>)>> {
+        let lines = self.inner.poll_lines()?;
+        self.pace(lines.iter().map(|(line, _)| line.len() + 1).sum());
+        Ok(lines)
+    }
+
+    fn checkpoint(&self) -> Option<String> {
+        self.inner.checkpoint()
+    }
+
+    fn resume(&mut self, cursor: &str) -> io::Result<()> {
+        self.inner.resume(cursor)
+    }
+}
+
+/// Applique --max-throughput à la lecture d'une entrée distante, inchangée sinon
+pub fn throttle_remote<'a>(reader: impl Read + 'a) -> Box<dyn Read + 'a> {
+    match REMOTE_LIMIT.get() {
+        Some(&rate) => Box::new(Throttled::new(reader, rate)),
+        None => Box::new(reader),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rates_and_refills_the_bucket() {
+        assert_eq!(parse_throughput("50MB/s"), Ok(50 * 1024 * 1024));
+        assert_eq!(parse_throughput("6KB/min"), Ok(102));
+        assert!(parse_throughput("50MB").is_err());
+        assert!(parse_throughput("1B/h").is_err());
+
+        let mut bucket = TokenBucket::new(1000);
+        let start = bucket.last;
+        assert_eq!(bucket.take(600, start), Duration::ZERO);
+        assert_eq!(bucket.take(900, start), Duration::from_millis(500));
+        // La dette remboursée, le seau plein ne garde qu'une seconde de débit
+        let later = start + Duration::from_secs(3);
+        assert_eq!(bucket.take(1000, later), Duration::ZERO);
+        assert_eq!(bucket.take(250, later), Duration::from_millis(250));
+    }
+
+    #[test]
+    fn throttled_reads_keep_to_the_rate() {
+        // Une seconde de débit passe d'emblée, le reste attend
+        let data = vec![b'x'; 11_000];
+        let started = Instant::now();
+        let mut out = Vec::new();
+        Throttled::new(data.as_slice(), 10_000)
+            .read_to_end(&mut out)
+            .unwrap();
+        assert_eq!(out.len(), 11_000);
+        assert!(started.elapsed() >= Duration::from_millis(95));
+    }
+}
@@ -0,0 +1,229 @@
+use crate::LogEntry;
+use chrono::NaiveDateTime;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+
+/// Percentiles des écarts (en secondes) entre entrées consécutives
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GapStats {
+    pub samples: usize,
+    pub p50: f64,
+    pub p95: f64,
+    pub p99: f64,
+}
+
+/// Percentile par rang le plus proche sur des valeurs triées
+fn percentile(sorted: &[f64], pct: f64) -> f64 {
+    let rank = ((pct / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+fn gap_stats(mut times: Vec<NaiveDateTime>) -> Option<GapStats> {
+    times.sort();
+    let mut gaps: Vec<f64> = times
+        .windows(2)
+        .map(|w| (w[1] - w[0]).num_milliseconds() as f64 / 1000.0)
+        .collect();
+    if gaps.is_empty() {
+        return None;
+    }
+    gaps.sort_by(|a, b| a.total_cmp(b));
+    Some(GapStats {
+        samples: gaps.len(),
+        p50: percentile(&gaps, 50.0),
+        p95: percentile(&gaps, 95.0),
+        p99: percentile(&gaps, 99.0),
+    })
+}
+
+/// Intervalle entre deux horodatages, bornes comprises
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Span {
+    pub start: String,
+    pub end: String,
+    pub seconds: i64,
+}
+
+impl Span {
+    fn new(start: NaiveDateTime, end: NaiveDateTime) -> Self {
+        let format = |t: NaiveDateTime| t.format("%Y-%m-%d %H:%M:%S").to_string();
+        Span {
+            start: format(start),
+            end: format(end),
+            seconds: (end - start).num_seconds(),
+        }
+    }
+}
+
+/// Période couverte par les entrées, de la plus ancienne à la plus récente
+pub fn time_span(entries: &[LogEntry]) -> Option<Span> {
+    let start = entries.iter().map(|e| e.datetime).min()?;
+    let end = entries.iter().map(|e| e.datetime).max()?;
+    Some(Span::new(start, end))
+}
+
+/// Plus long silence entre deux entrées consécutives (le premier en cas d'égalité)
+pub fn longest_gap(entries: &[LogEntry]) -> Option<Span> {
+    let mut times: Vec<NaiveDateTime> = entries.iter().map(|e| e.datetime).collect();
+    times.sort();
+    times
+        .windows(2)
+        .rev()
+        .max_by_key(|w| w[1] - w[0])
+        .filter(|w| w[1] > w[0])
+        .map(|w| Span::new(w[0], w[1]))
+}
+
+/// Temps de réponse (en secondes) des paires requête/réponse de `--correlate`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Correlation {
+    pub pairs: usize,
+    pub p50: f64,
+    pub p95: f64,
+    pub p99: f64,
+    pub max: f64,
+    pub orphan_requests: usize,
+    pub orphan_responses: usize,
+    /// Clés des premières requêtes restées sans réponse
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub orphan_keys: Vec<String>,
+}
+
+/// Associe chaque réponse à la plus ancienne requête en attente de même clé
+/// (premier groupe capturé), dans l'ordre chronologique.
+pub fn correlate(
+    entries: &[LogEntry],
+    request: &Regex,
+    response: &Regex,
+    top_n: usize,
+) -> Correlation {
+    let mut ordered: Vec<&LogEntry> = entries.iter().collect();
+    ordered.sort_by_key(|e| e.datetime);
+
+    let key = |re: &Regex, message: &str| {
+        re.captures(message)
+            .and_then(|c| c.get(1))
+            .map(|m| m.as_str().to_string())
+    };
+    let mut pending: HashMap<String, VecDeque<NaiveDateTime>> = HashMap::new();
+    let mut latencies = Vec::new();
+    let mut orphan_responses = 0;
+    for entry in ordered {
+        if let Some(k) = key(request, &entry.message) {
+            pending.entry(k).or_default().push_back(entry.datetime);
+        } else if let Some(k) = key(response, &entry.message) {
+            match pending.get_mut(&k).and_then(VecDeque::pop_front) {
+                Some(start) => {
+                    latencies.push((entry.datetime - start).num_milliseconds() as f64 / 1000.0)
+                }
+                None => orphan_responses += 1,
+            }
+        }
+    }
+
+    latencies.sort_by(|a, b| a.total_cmp(b));
+    // Requêtes restées en attente, les plus anciennes d'abord
+    let mut orphan_keys: Vec<(NaiveDateTime, String)> = pending
+        .into_iter()
+        .flat_map(|(k, times)| times.into_iter().map(move |t| (t, k.clone())))
+        .collect();
+    orphan_keys.sort();
+    let stat = |pct| {
+        if latencies.is_empty() {
+            0.0
+        } else {
+            percentile(&latencies, pct)
+        }
+    };
+    Correlation {
+        pairs: latencies.len(),
+        p50: stat(50.0),
+        p95: stat(95.0),
+        p99: stat(99.0),
+        max: latencies.last().copied().unwrap_or(0.0),
+        orphan_requests: orphan_keys.len(),
+        orphan_responses,
+        orphan_keys: orphan_keys
+            .into_iter()
+            .take(top_n)
+            .map(|(_, k)| k)
+            .collect(),
+    }
+}
+
+/// Écarts entre entrées consécutives, tous niveaux confondus puis par niveau,
+/// pour quantifier le caractère « en rafales » des logs.
+pub fn inter_arrival(entries: &[LogEntry]) -> (Option<GapStats>, HashMap<String, GapStats>) {
+    let mut by_level: HashMap<&str, Vec<NaiveDateTime>> = HashMap::new();
+    for entry in entries {
+        by_level
+            .entry(entry.level.as_str())
+            .or_default()
+            .push(entry.datetime);
+    }
+
+    let overall = gap_stats(entries.iter().map(|e| e.datetime).collect());
+    let by_level = by_level
+        .into_iter()
+        .filter_map(|(level, times)| gap_stats(times).map(|g| (level.to_string(), g)))
+        .collect();
+    (overall, by_level)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_log_line;
+
+    #[test]
+    fn inter_arrival_percentiles_overall_and_per_level() {
+        let entries: Vec<_> = [
+            "2024-01-15 10:00:00 [INFO] a",
+            "2024-01-15 10:00:01 [INFO] b",
+            "2024-01-15 10:00:02 [ERROR] c",
+            "2024-01-15 10:00:12 [INFO] d",
+        ]
+        .iter()
+        .map(|l| parse_log_line(l).unwrap())
+        .collect();
+
+        let (overall, by_level) = inter_arrival(&entries);
+        let overall = overall.unwrap();
+        assert_eq!(overall.samples, 3);
+        assert_eq!(overall.p50, 1.0);
+        assert_eq!(overall.p99, 10.0);
+        assert_eq!(by_level["INFO"].p99, 11.0);
+        assert!(!by_level.contains_key("ERROR"));
+
+        assert_eq!(time_span(&entries).unwrap().seconds, 12);
+        let gap = longest_gap(&entries).unwrap();
+        assert_eq!(gap.start, "2024-01-15 10:00:02");
+        assert_eq!(gap.seconds, 10);
+        assert!(longest_gap(&entries[..1]).is_none());
+    }
+
+    #[test]
+    fn correlate_pairs_requests_with_responses_by_key() {
+        let entries: Vec<_> = [
+            "2024-01-15 10:00:00 [INFO] request a1",
+            "2024-01-15 10:00:01 [INFO] request b2",
+            "2024-01-15 10:00:02 [INFO] request a1",
+            "2024-01-15 10:00:03 [INFO] response a1",
+            "2024-01-15 10:00:04 [INFO] request c3",
+            "2024-01-15 10:00:09 [INFO] response b2",
+            "2024-01-15 10:00:10 [INFO] response zz",
+        ]
+        .iter()
+        .map(|l| parse_log_line(l).unwrap())
+        .collect();
+
+        let request = Regex::new(r"request (\w+)").unwrap();
+        let response = Regex::new(r"response (\w+)").unwrap();
+        let c = correlate(&entries, &request, &response, 5);
+        assert_eq!(c.pairs, 2);
+        assert_eq!((c.p50, c.max), (3.0, 8.0));
+        assert_eq!((c.orphan_requests, c.orphan_responses), (2, 1));
+        assert_eq!(c.orphan_keys, ["a1", "c3"]);
+    }
+}
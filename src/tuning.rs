@@ -0,0 +1,258 @@
+use crate::LineFormat;
+use crate::compressed::Codec;
+use rayon::prelude::*;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Taille au-delà de laquelle une entrée qu'on ne peut pas échantillonner
+/// (URL) est lue en parallèle
+pub const FALLBACK_THRESHOLD: u64 = 10 * 1024 * 1024; // 10 MB
+
+/// Lignes analysées entre deux vérifications de Ctrl-C en mode parallèle,
+/// faute de mesure
+pub const DEFAULT_CHUNK: usize = 64 * 1024;
+
+/// Tampon de lecture des entrées par défaut, celui de `BufReader`
+const DEFAULT_BUFFER: usize = 8 * 1024;
+
+/// Bornes de --buffer-size
+const BUFFER_RANGE: (u64, u64) = (1024, 1024 * 1024 * 1024);
+
+/// Taille des lectures sur les entrées, fixée une fois par --buffer-size
+static BUFFER_SIZE: OnceLock<usize> = OnceLock::new();
+
+/// Octets (décompressés) analysés en tête de fichier pour mesurer le débit;
+/// un fichier plus petit est lu séquentiellement sans mesure
+const SAMPLE_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Gain minimal du parallèle sur un seul fil pour justifier de charger tout
+/// le fichier en mémoire
+const MIN_SPEEDUP: f64 = 1.5;
+
+/// Durée visée pour l'analyse d'un bloc, qui borne l'attente après Ctrl-C
+const CHUNK_TIME: Duration = Duration::from_millis(100);
+const CHUNK_RANGE: (usize, usize) = (4 * 1024, 1024 * 1024);
+
+pub fn set_buffer_size(size: usize) {
+    let _ = BUFFER_SIZE.set(size);
+}
+
+pub fn buffer_size() -> usize {
+    BUFFER_SIZE.get().copied().unwrap_or(DEFAULT_BUFFER)
+}
+
+/// Valeur de --buffer-size: taille avec unité (`256KB`, `4MB`), de 1 KB à 1 GB
+pub fn parse_buffer_size(input: &str) -> Result<usize, String> {
+    let size = crate::parse_size(input)?;
+    if !(BUFFER_RANGE.0..=BUFFER_RANGE.1).contains(&size) {
+        return Err(format!("Tampon hors bornes: {input} (de 1KB à 1GB)"));
+    }
+    Ok(size as usize)
+}
+
+/// Valeur de --chunk-size: un nombre de lignes
+pub fn parse_chunk_size(input: &str) -> Result<usize, String> {
+    match input.parse() {
+        Ok(0) | Err(_) => Err(format!(
+            "Taille de bloc invalide: {input} (nombre de lignes, au moins 1)"
+        )),
+        Ok(lines) => Ok(lines),
+    }
+}
+
+/// Lit toute l'entrée par lectures de `buffer_size()` octets
+pub fn read_all(mut reader: impl Read, raw: &mut Vec<u8>) -> io::Result<()> {
+    let mut buf = vec![0; buffer_size()];
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => return Ok(()),
+            Ok(n) => raw.extend_from_slice(&buf[..n]),
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Débits d'analyse mesurés sur l'échantillon, en octets par seconde
+#[derive(Debug, Clone, Copy)]
+pub struct Measure {
+    pub sample: usize,
+    pub lines: usize,
+    pub single: f64,
+    pub parallel: f64,
+    pub threads: usize,
+}
+
+impl Measure {
+    /// Lignes par bloc pour qu'un bloc s'analyse en `CHUNK_TIME` au débit parallèle
+    fn chunk(&self) -> usize {
+        let lines_per_second = self.parallel * self.lines as f64 / self.sample as f64;
+        let chunk = (lines_per_second * CHUNK_TIME.as_secs_f64()) as usize;
+        chunk.clamp(CHUNK_RANGE.0, CHUNK_RANGE.1)
+    }
+}
+
+/// Stratégie de lecture d'une entrée
+#[derive(Debug, Clone, Copy)]
+pub struct Plan {
+    pub parallel: bool,
+    /// Lignes par bloc en mode parallèle
+    pub chunk: usize,
+    pub measure: Option<Measure>,
+}
+
+impl Plan {
+    fn fixed(parallel: bool) -> Self {
+        Plan {
+            parallel,
+            chunk: DEFAULT_CHUNK,
+            measure: None,
+        }
+    }
+}
+
+/// Choisit entre lecture séquentielle et parallèle. `--parallel` l'impose
+/// sans mesure; une entrée de taille inconnue est lue séquentiellement, une
+/// URL selon `FALLBACK_THRESHOLD`. Pour un fichier, les premiers Mo sont
+/// analysés sur un fil puis sur tout le pool: le parallèle n'est retenu que
+/// s'il va au moins `MIN_SPEEDUP` fois plus vite sur cette machine, et la
+/// taille des blocs suit le débit mesuré.
+pub fn plan(path: &Path, size: Option<u64>, format: &LineFormat, forced: bool) -> Plan {
+    if forced {
+        return Plan::fixed(true);
+    }
+    let Some(size) = size else {
+        return Plan::fixed(false);
+    };
+    if crate::remote::is_url(path) {
+        return Plan::fixed(size > FALLBACK_THRESHOLD);
+    }
+    if size <= SAMPLE_BYTES {
+        return Plan::fixed(false);
+    }
+    let measure = match sample(path) {
+        Ok(text) => measure(&text, format),
+        Err(_) => None,
+    };
+    let Some(measure) = measure else {
+        return Plan::fixed(size > FALLBACK_THRESHOLD);
+    };
+    Plan {
+        parallel: measure.parallel >= measure.single * MIN_SPEEDUP,
+        chunk: measure.chunk(),
+        measure: Some(measure),
+    }
+}
+
+/// Tête du fichier, décompressée le cas échéant, coupée à la dernière fin de ligne
+fn sample(path: &Path) -> io::Result<String> {
+    let mut head = Vec::new();
+    crate::open_input(path)?
+        .take(SAMPLE_BYTES)
+        .read_to_end(&mut head)?;
+    if let Some(codec) = Codec::detect(&head) {
+        head.clear();
+        codec
+            .decoder(BufReader::new(crate::open_input(path)?))?
+            .take(SAMPLE_BYTES)
+            .read_to_end(&mut head)?;
+    }
+    if let Some(end) = memchr::memrchr(b'\n', &head) {
+        head.truncate(end);
+    }
+    Ok(String::from_utf8_lossy(&head).into_owned())
+}
+
+fn measure(text: &str, format: &LineFormat) -> Option<Measure> {
+    let lines: Vec<&str> = text.lines().collect();
+    if lines.is_empty() {
+        return None;
+    }
+    // Premières lignes à blanc: expressions compilées et pool démarré
+    let warmup = lines.len().min(1024);
+    let _ = lines[..warmup]
+        .par_iter()
+        .filter_map(|l| format.parse(l))
+        .count();
+
+    let started = Instant::now();
+    let _ = lines.iter().filter_map(|l| format.parse(l)).count();
+    let single = rate(text.len(), started.elapsed());
+    let started = Instant::now();
+    let _ = lines.par_iter().filter_map(|l| format.parse(l)).count();
+    let parallel = rate(text.len(), started.elapsed());
+    Some(Measure {
+        sample: text.len(),
+        lines: lines.len(),
+        single,
+        parallel,
+        threads: rayon::current_num_threads(),
+    })
+}
+
+fn rate(bytes: usize, elapsed: Duration) -> f64 {
+    bytes as f64 / elapsed.as_secs_f64().max(1e-6)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_files_large_enough_to_sample() {
+        let dir = tempfile::tempdir().unwrap();
+        let small = dir.path().join("small.log");
+        std::fs::write(&small, "2024-01-15 10:30:45 [ERROR] db down\n").unwrap();
+        let sequential = plan(&small, Some(36), &LineFormat::Text, false);
+        assert!(!sequential.parallel && sequential.measure.is_none());
+        assert!(plan(&small, Some(36), &LineFormat::Text, true).parallel);
+
+        let line = "2024-01-15 10:30:45 [INFO] GET /api/users 200 12ms\n";
+        let large = dir.path().join("large.log");
+        let count = (SAMPLE_BYTES as usize / line.len()) + 1000;
+        std::fs::write(&large, line.repeat(count)).unwrap();
+        let size = (line.len() * count) as u64;
+        let sampled = plan(&large, Some(size), &LineFormat::Text, false);
+        let measure = sampled.measure.unwrap();
+        assert!(measure.sample <= SAMPLE_BYTES as usize);
+        assert_eq!(measure.lines, measure.sample.div_ceil(line.len()));
+        assert!((CHUNK_RANGE.0..=CHUNK_RANGE.1).contains(&sampled.chunk));
+
+        let forced = plan(&large, Some(size), &LineFormat::Text, true);
+        assert!(forced.parallel && forced.measure.is_none());
+
+        let url = Path::new("https://example.com/app.log");
+        assert!(!plan_for_url(url, FALLBACK_THRESHOLD).parallel);
+        assert!(plan_for_url(url, FALLBACK_THRESHOLD + 1).parallel);
+    }
+
+    #[test]
+    fn parses_buffer_sizes_within_bounds() {
+        assert_eq!(parse_buffer_size("256KB"), Ok(256 * 1024));
+        assert_eq!(parse_buffer_size("1GB"), Ok(1024 * 1024 * 1024));
+        assert!(parse_buffer_size("512").is_err());
+        assert!(parse_buffer_size("2GB").is_err());
+    }
+
+    #[test]
+    fn parses_chunk_sizes_as_line_counts() {
+        assert_eq!(parse_chunk_size("4096"), Ok(4096));
+        assert!(parse_chunk_size("0").is_err());
+        assert!(parse_chunk_size("64KB").is_err());
+    }
+
+    #[test]
+    fn read_all_appends_the_whole_input() {
+        let line = "2024-01-15 10:30:45 [INFO] GET /api/users 200 12ms\n";
+        let mut raw = b"head\n".to_vec();
+        read_all(line.repeat(3).as_bytes(), &mut raw).unwrap();
+        assert_eq!(raw.len(), 5 + 3 * line.len());
+        assert!(raw.ends_with(line.as_bytes()));
+    }
+
+    fn plan_for_url(url: &Path, size: u64) -> Plan {
+        plan(url, Some(size), &LineFormat::Text, false)
+    }
+}
@@ -0,0 +1,181 @@
+use crate::parse_log_line;
+use chrono::NaiveDateTime;
+use hmac::{Hmac, KeyInit, Mac};
+use regex::Regex;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingKind {
+    /// Entrée datée avant une entrée précédente
+    Backdated,
+    /// Numéros de séquence manquants: des lignes ont pu être supprimées
+    SequenceGap,
+    /// Numéro de séquence inférieur ou égal au précédent
+    SequenceRegression,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Finding {
+    pub line: usize,
+    pub kind: FindingKind,
+    pub detail: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Signature {
+    pub algorithm: &'static str,
+    pub value: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VerifyReport {
+    pub file: String,
+    pub sha256: String,
+    pub lines: usize,
+    pub entries: usize,
+    pub findings: Vec<Finding>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<Signature>,
+}
+
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Parcourt le fichier pour l'audit: empreinte SHA-256, entrées antidatées
+/// (plus anciennes que le maximum déjà vu, au-delà de `tolerance_secs`) et,
+/// si `sequence` est fourni, trous dans les numéros de séquence capturés par
+/// son premier groupe.
+pub fn verify(
+    path: &Path,
+    sequence: Option<&Regex>,
+    tolerance_secs: i64,
+) -> io::Result<VerifyReport> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut hasher = Sha256::new();
+    let mut buf = Vec::new();
+    let mut report = VerifyReport {
+        file: path.display().to_string(),
+        sha256: String::new(),
+        lines: 0,
+        entries: 0,
+        findings: Vec::new(),
+        signature: None,
+    };
+    let mut latest: Option<NaiveDateTime> = None;
+    let mut last_seq: Option<u64> = None;
+
+    while reader.read_until(b'\n', &mut buf)? != 0 {
+        hasher.update(&buf);
+        report.lines += 1;
+        let line = String::from_utf8_lossy(&buf);
+        let line = line.trim_end_matches(['\n', '\r']);
+
+        if let Some(entry) = parse_log_line(line) {
+            report.entries += 1;
+            match latest {
+                Some(max) if (max - entry.datetime).num_seconds() > tolerance_secs => {
+                    report.findings.push(Finding {
+                        line: report.lines,
+                        kind: FindingKind::Backdated,
+                        detail: format!("{} antérieur à {}", entry.timestamp, max),
+                    });
+                }
+                Some(max) if max >= entry.datetime => {}
+                _ => latest = Some(entry.datetime),
+            }
+        }
+
+        if let Some(seq) = sequence
+            .and_then(|re| re.captures(line))
+            .and_then(|caps| caps.get(1)?.as_str().parse::<u64>().ok())
+        {
+            if let Some(prev) = last_seq {
+                if seq <= prev {
+                    report.findings.push(Finding {
+                        line: report.lines,
+                        kind: FindingKind::SequenceRegression,
+                        detail: format!("séquence {seq} après {prev}"),
+                    });
+                } else if seq > prev + 1 {
+                    report.findings.push(Finding {
+                        line: report.lines,
+                        kind: FindingKind::SequenceGap,
+                        detail: format!("{} numéros manquants ({prev} -> {seq})", seq - prev - 1),
+                    });
+                }
+            }
+            last_seq = Some(last_seq.map_or(seq, |prev| prev.max(seq)));
+        }
+
+        buf.clear();
+    }
+
+    report.sha256 = hex(&hasher.finalize());
+    Ok(report)
+}
+
+impl VerifyReport {
+    /// Signe le résumé (HMAC-SHA256 sur sa forme JSON sans signature).
+    pub fn sign(&mut self, key: &[u8]) {
+        self.signature = None;
+        let payload = serde_json::to_vec(self).unwrap_or_default();
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepte toute taille de clé");
+        mac.update(&payload);
+        self.signature = Some(Signature {
+            algorithm: "HMAC-SHA256",
+            value: hex(&mac.finalize().into_bytes()),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn verify_flags_backdated_entries_and_sequence_gaps() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.log");
+        fs::write(
+            &path,
+            "2024-01-15 10:00:00 [INFO] seq=1 start\n\
+             2024-01-15 10:05:00 [INFO] seq=2 work\n\
+             2024-01-15 10:01:00 [INFO] seq=5 late\n",
+        )
+        .unwrap();
+
+        let re = Regex::new(r"seq=(\d+)").unwrap();
+        let report = verify(&path, Some(&re), 0).unwrap();
+        assert_eq!(report.lines, 3);
+        assert_eq!(report.sha256.len(), 64);
+        let kinds: Vec<_> = report.findings.iter().map(|f| &f.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![&FindingKind::Backdated, &FindingKind::SequenceGap]
+        );
+
+        let tolerant = verify(&path, None, 600).unwrap();
+        assert!(tolerant.findings.is_empty());
+    }
+
+    #[test]
+    fn signature_depends_on_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.log");
+        fs::write(&path, "2024-01-15 10:00:00 [INFO] ok\n").unwrap();
+
+        let mut a = verify(&path, None, 0).unwrap();
+        let mut b = verify(&path, None, 0).unwrap();
+        a.sign(b"secret");
+        b.sign(b"other");
+        assert_ne!(a.signature.unwrap().value, b.signature.unwrap().value);
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{LogEntry, LogLevel};
+use chrono::Datelike;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Comptes d'une clé (niveau ou message d'erreur) pour chaque semaine ISO
+#[derive(Debug, Serialize)]
+pub struct WeeklySeries {
+    pub key: String,
+    pub counts: Vec<usize>,
+    /// Variation de la dernière semaine par rapport à la précédente, en %
+    pub change_pct: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WeekOverWeek {
+    pub weeks: Vec<String>,
+    pub by_level: Vec<WeeklySeries>,
+    pub by_error: Vec<WeeklySeries>,
+}
+
+fn iso_week(entry: &LogEntry) -> String {
+    let week = entry.datetime.date().iso_week();
+    format!("{}-W{:02}", week.year(), week.week())
+}
+
+fn series(key: String, weeks: &[String], counts: &BTreeMap<String, usize>) -> WeeklySeries {
+    let counts: Vec<usize> = weeks
+        .iter()
+        .map(|w| counts.get(w).copied().unwrap_or(0))
+        .collect();
+    let change_pct = match counts.as_slice() {
+        [.., prev, last] if *prev > 0 => Some((*last as f64 - *prev as f64) / *prev as f64 * 100.0),
+        _ => None,
+    };
+    WeeklySeries {
+        key,
+        counts,
+        change_pct,
+    }
+}
+
+/// Comparaison semaine par semaine par niveau et pour les erreurs les plus
+/// fréquentes; `None` si les entrées tiennent dans une seule semaine.
+pub fn week_over_week(entries: &[LogEntry], top_errors: &[&str]) -> Option<WeekOverWeek> {
+    let mut weeks = BTreeSet::new();
+    let mut by_level: BTreeMap<&str, BTreeMap<String, usize>> = BTreeMap::new();
+    let mut by_error: BTreeMap<&str, BTreeMap<String, usize>> = BTreeMap::new();
+
+    for entry in entries {
+        let week = iso_week(entry);
+        *by_level
+            .entry(entry.level.as_str())
+            .or_default()
+            .entry(week.clone())
+            .or_insert(0) += 1;
+        if entry.level == LogLevel::Error
+            && let Some(msg) = top_errors.iter().find(|m| **m == entry.message)
+        {
+            *by_error
+                .entry(msg)
+                .or_default()
+                .entry(week.clone())
+                .or_insert(0) += 1;
+        }
+        weeks.insert(week);
+    }
+
+    if weeks.len() < 2 {
+        return None;
+    }
+    let weeks: Vec<String> = weeks.into_iter().collect();
+
+    Some(WeekOverWeek {
+        by_level: by_level
+            .into_iter()
+            .map(|(level, counts)| series(level.to_string(), &weeks, &counts))
+            .collect(),
+        by_error: top_errors
+            .iter()
+            .filter_map(|msg| {
+                by_error
+                    .get(msg)
+                    .map(|c| series(msg.to_string(), &weeks, c))
+            })
+            .collect(),
+        weeks,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_log_line;
+
+    #[test]
+    fn compares_consecutive_weeks() {
+        let entries: Vec<_> = [
+            "2024-01-08 10:00:00 [ERROR] disk full",
+            "2024-01-15 10:00:00 [ERROR] disk full",
+            "2024-01-15 11:00:00 [ERROR] disk full",
+            "2024-01-15 12:00:00 [INFO] ok",
+        ]
+        .iter()
+        .map(|l| parse_log_line(l).unwrap())
+        .collect();
+
+        let wow = week_over_week(&entries, &["disk full"]).unwrap();
+        assert_eq!(wow.weeks, vec!["2024-W02", "2024-W03"]);
+        let errors = wow.by_level.iter().find(|s| s.key == "ERROR").unwrap();
+        assert_eq!(errors.counts, vec![1, 2]);
+        assert_eq!(errors.change_pct, Some(100.0));
+        let info = wow.by_level.iter().find(|s| s.key == "INFO").unwrap();
+        assert_eq!(info.change_pct, None);
+        assert_eq!(wow.by_error[0].counts, vec![1, 2]);
+
+        assert!(week_over_week(&entries[1..], &[]).is_none());
+    }
+}
@@ -40,6 +40,41 @@ fn filters_errors_and_search() {
         .stdout(predicate::str::contains("Database query failed"));
 }
 
+#[test]
+fn aggregates_several_files_with_a_per_file_breakdown() {
+    let first = make_log_file();
+    let mut second = NamedTempFile::new().expect("temp file");
+    writeln!(second, "2024-01-15 10:31:00 [ERROR] Disk full").unwrap();
+    cargo_bin_cmd!("TD3-Rust")
+        .args(["--format", "csv"])
+        .arg(first.path())
+        .arg(second.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("level,ERROR,3"))
+        .stdout(predicate::str::contains(format!(
+            "file_errors,\"{}\",1",
+            second.path().display()
+        )));
+}
+
+#[test]
+fn reads_logs_from_stdin() {
+    let file = make_log_file();
+    cargo_bin_cmd!("TD3-Rust")
+        .args(["--format", "csv", "-"])
+        .pipe_stdin(file.path())
+        .unwrap()
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("level,ERROR,2"));
+    cargo_bin_cmd!("TD3-Rust")
+        .args(["--follow", "-"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("entrée standard"));
+}
+
 #[test]
 fn respects_since_until_filters() {
     let file = make_log_file();
@@ -75,3 +110,187 @@ fn reports_missing_file() {
         .failure()
         .stderr(predicate::str::contains("Fichier introuvable"));
 }
+
+#[test]
+fn follows_exit_code_contract() {
+    let file = make_log_file();
+    cargo_bin_cmd!("TD3-Rust")
+        .arg("--search")
+        .arg("nothing like this")
+        .arg(file.path())
+        .assert()
+        .success();
+    cargo_bin_cmd!("TD3-Rust")
+        .arg("--search")
+        .arg("nothing like this")
+        .arg("--no-match-exit-code")
+        .arg("4")
+        .arg(file.path())
+        .assert()
+        .code(4);
+    cargo_bin_cmd!("TD3-Rust")
+        .arg("--error-budget")
+        .arg("1/d")
+        .arg(file.path())
+        .assert()
+        .code(3)
+        .stdout(predicate::str::contains("Error budget"));
+    cargo_bin_cmd!("TD3-Rust")
+        .arg("definitely_missing.log")
+        .assert()
+        .code(2);
+    cargo_bin_cmd!("TD3-Rust")
+        .arg("--top")
+        .arg("0")
+        .arg(file.path())
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn output_never_clobbers_input_or_existing_reports() {
+    let dir = tempfile::tempdir().unwrap();
+    let log = dir.path().join("app.log");
+    let report = dir.path().join("report.txt");
+    std::fs::copy(make_log_file().path(), &log).unwrap();
+
+    cargo_bin_cmd!("TD3-Rust")
+        .arg(&log)
+        .arg("--output")
+        .arg(&log)
+        .arg("--force")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("fichier analysé lui-même"));
+    assert!(
+        std::fs::read_to_string(&log)
+            .unwrap()
+            .starts_with("2024-01-15")
+    );
+
+    cargo_bin_cmd!("TD3-Rust")
+        .arg(&log)
+        .arg("--output")
+        .arg(&report)
+        .assert()
+        .success();
+    cargo_bin_cmd!("TD3-Rust")
+        .arg(&log)
+        .arg("--output")
+        .arg(&report)
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("--force"));
+    cargo_bin_cmd!("TD3-Rust")
+        .arg(&log)
+        .arg("--format")
+        .arg("csv")
+        .arg("--output")
+        .arg(&report)
+        .arg("--force")
+        .assert()
+        .success();
+    assert!(
+        std::fs::read_to_string(&report)
+            .unwrap()
+            .starts_with("metric,key,value")
+    );
+}
+
+#[test]
+fn jsonl_export_keeps_metadata_out_of_the_entry_stream() {
+    let dir = tempfile::tempdir().unwrap();
+    let meta = dir.path().join("meta.json");
+    let file = make_log_file();
+    let output = cargo_bin_cmd!("TD3-Rust")
+        .args(["--format", "jsonl", "--metadata-out"])
+        .arg(&meta)
+        .arg(file.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().count(), 4);
+    assert!(stdout.lines().all(|line| line.contains("\"level\":")));
+    let meta = std::fs::read_to_string(&meta).unwrap();
+    assert!(meta.contains(&format!("\"version\":\"{}\"", env!("CARGO_PKG_VERSION"))));
+}
+
+#[test]
+fn categorizes_errors_with_config_rules() {
+    let file = make_log_file();
+    let mut config = NamedTempFile::new().expect("temp file");
+    write!(
+        config,
+        "\
+[[category]]
+name = \"upstream\"
+pattern = \"(?i)api\"
+"
+    )
+    .unwrap();
+    cargo_bin_cmd!("TD3-Rust")
+        .arg("--config")
+        .arg(config.path())
+        .arg(file.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Errors by category"))
+        .stdout(predicate::str::contains("upstream"))
+        .stdout(predicate::str::contains("database"));
+}
+
+#[test]
+fn prune_archives_old_entries() {
+    let dir = tempfile::tempdir().unwrap();
+    let log = dir.path().join("app.log");
+    let archive = dir.path().join("old.log");
+    std::fs::copy(make_log_file().path(), &log).unwrap();
+
+    cargo_bin_cmd!("TD3-Rust")
+        .arg("prune")
+        .arg(&log)
+        .arg("--before")
+        .arg("2024-01-15 11:00:00")
+        .arg("--archive-to")
+        .arg(&archive)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("3 lignes archivées"));
+
+    assert_eq!(
+        std::fs::read_to_string(&log).unwrap(),
+        "2024-01-15 11:00:00 [INFO] Done\n"
+    );
+
+    cargo_bin_cmd!("TD3-Rust")
+        .arg("prune")
+        .arg(dir.path().join("missing.log"))
+        .arg("--before")
+        .arg("2024-01-15 11:00:00")
+        .arg("--archive-to")
+        .arg(&archive)
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("Fichier introuvable"));
+}
+
+#[test]
+fn verify_reports_missing_file_or_key() {
+    let file = make_log_file();
+    cargo_bin_cmd!("TD3-Rust")
+        .arg("verify")
+        .arg("definitely_missing.log")
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("Fichier introuvable"));
+    cargo_bin_cmd!("TD3-Rust")
+        .arg("verify")
+        .arg(file.path())
+        .arg("--key-file")
+        .arg("definitely_missing.key")
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("definitely_missing.key"));
+}